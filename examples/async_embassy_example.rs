@@ -15,6 +15,10 @@
 //! You'll need to provide:
 //! - An I2C peripheral that implements `embedded_hal_async::i2c::I2c`
 //! - Embassy runtime configured for your platform
+//!
+//! With the `sampler` feature, `ms4525do::sampler::Sampler` provides the
+//! sampling loop below, with drift-free scheduling and `embassy-sync`
+//! publishing.
 
 //!
 //! Note: This is a template example that needs to be adapted for your specific platform.
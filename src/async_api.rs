@@ -7,7 +7,7 @@
 //!
 //! ```ignore
 //! use ms4525do::async_api::Ms4525do;
-//! use embassy_time::{Duration, Timer};
+//! use embassy_time::{Duration, Instant, Timer};
 //!
 //! let mut sensor = Ms4525do::new(i2c);
 //!
@@ -27,12 +27,19 @@
 //! ```
 
 use crate::common::*;
+use crate::health::{Health, HealthStatus, HealthThresholds};
+#[cfg(feature = "bus-recovery")]
+use crate::recovery::{RecoverBus, RecoveringI2c};
+use crate::retry::{RetryPolicy, RetryStats};
+use crate::self_test::{SelfTestConfig, SelfTestReport, SelfTestRun};
+use crate::sensor::async_api::DifferentialPressureSensor;
+use crate::stuck::{ReinitHook, StuckConfig, StuckDetector};
+use crate::timing::{Clock, EmbassyClock, NoClock, TimestampedReading};
+use crate::transfer::{Ms4525doTransfer, TransferFunction};
+use crate::units::{Celsius, Pascals};
 use crate::Ms4525doError;
-use embassy_time::{Duration, Timer};
-use embedded_hal_async::i2c::I2c;
-
-#[cfg(feature = "defmt")]
-use defmt::info;
+use embassy_time::{Duration, Instant, Timer};
+use embedded_hal_async::i2c::{I2c, Operation};
 
 /// MS4525DO sensor driver with async I2C communication.
 ///
@@ -42,9 +49,23 @@ use defmt::info;
 /// # Type Parameters
 ///
 /// * `I2C` - The I2C peripheral type implementing `embedded_hal_async::i2c::I2c`
-pub struct Ms4525do<I2C> {
+/// * `T` - The transfer function converting raw counts to physical units
+///   (defaults to [`Ms4525doTransfer`], see [`crate::transfer`])
+/// * `H` - The hook re-initializing a stuck sensor (defaults to `fn()`,
+///   see [`Ms4525do::with_reinit_hook`])
+pub struct Ms4525do<I2C, T = Ms4525doTransfer, H = fn()> {
     i2c: I2C,
     address: u8,
+    transfer: T,
+    fetch_mode: FetchMode,
+    conversion_pending: bool,
+    last_fetch_us: u64,
+    retry_policy: RetryPolicy,
+    retry_stats: RetryStats,
+    health: Health,
+    stuck_detector: Option<StuckDetector>,
+    reinit_hook: Option<H>,
+    bus_recovered: fn(&mut I2C) -> bool,
 }
 
 impl<I2C> Ms4525do<I2C>
@@ -70,6 +91,16 @@ where
         Self {
             i2c,
             address: MS4525DO_ADDR,
+            transfer: Ms4525doTransfer::default(),
+            fetch_mode: FetchMode::default(),
+            conversion_pending: false,
+            last_fetch_us: 0,
+            retry_policy: RetryPolicy::default(),
+            retry_stats: RetryStats::default(),
+            health: Health::default(),
+            stuck_detector: None,
+            reinit_hook: None,
+            bus_recovered: no_bus_recovery,
         }
     }
 
@@ -84,7 +115,279 @@ where
     ///
     /// A new `Ms4525do` instance configured with the specified I2C address
     pub fn new_with_address(i2c: I2C, address: u8) -> Self {
-        Self { i2c, address }
+        Self {
+            i2c,
+            address,
+            transfer: Ms4525doTransfer::default(),
+            fetch_mode: FetchMode::default(),
+            conversion_pending: false,
+            last_fetch_us: 0,
+            retry_policy: RetryPolicy::default(),
+            retry_stats: RetryStats::default(),
+            health: Health::default(),
+            stuck_detector: None,
+            reinit_hook: None,
+            bus_recovered: no_bus_recovery,
+        }
+    }
+
+    /// Creates a new MS4525DO sensor instance for a shared I2C bus.
+    ///
+    /// Same as [`Ms4525do::new`] with [`FetchMode::Pipelined`]: after the
+    /// first reading, `read_data` fetches both data packets and requests the
+    /// next measurement in one I2C transaction, so no other device can use the
+    /// bus between them. See [`crate::shared_bus`] for the devices of
+    /// `embedded-hal-bus` and `embassy-embedded-hal` it is tested with.
+    ///
+    /// # Arguments
+    ///
+    /// * `i2c` - A shared-bus device for communication with the sensor
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let bus = RefCell::new(i2c);
+    /// let sensor = Ms4525do::new_shared(RefCellDevice::new(&bus));
+    /// ```
+    pub fn new_shared(i2c: I2C) -> Self {
+        Self {
+            fetch_mode: FetchMode::Pipelined,
+            ..Self::new(i2c)
+        }
+    }
+
+    /// Creates a new MS4525DO sensor instance for a shared I2C bus with a custom I2C address.
+    ///
+    /// Same as [`Ms4525do::new_with_address`] with [`FetchMode::Pipelined`],
+    /// see [`Ms4525do::new_shared`].
+    ///
+    /// # Arguments
+    ///
+    /// * `i2c` - A shared-bus device for communication with the sensor
+    /// * `address` - Custom 7-bit I2C address
+    pub fn new_shared_with_address(i2c: I2C, address: u8) -> Self {
+        Self {
+            fetch_mode: FetchMode::Pipelined,
+            ..Self::new_with_address(i2c, address)
+        }
+    }
+}
+
+impl<I2C, T> Ms4525do<I2C, T>
+where
+    I2C: I2c,
+    T: TransferFunction,
+{
+    /// Creates a new sensor instance for a part with a different transfer function.
+    ///
+    /// Use this to drive Honeywell TruStability HSC/SSC/ABP or All Sensors
+    /// DLVR sensors, which share the MS4525DO frame format.
+    ///
+    /// # Arguments
+    ///
+    /// * `i2c` - The I2C peripheral for communication with the sensor
+    /// * `address` - 7-bit I2C address
+    /// * `transfer` - Transfer function of the connected part
+    ///
+    /// # Returns
+    ///
+    /// A new `Ms4525do` instance using the given transfer function
+    pub fn new_with_transfer(i2c: I2C, address: u8, transfer: T) -> Self {
+        Self {
+            i2c,
+            address,
+            transfer,
+            fetch_mode: FetchMode::default(),
+            conversion_pending: false,
+            last_fetch_us: 0,
+            retry_policy: RetryPolicy::default(),
+            retry_stats: RetryStats::default(),
+            health: Health::default(),
+            stuck_detector: None,
+            reinit_hook: None,
+            bus_recovered: no_bus_recovery,
+        }
+    }
+
+    /// Replaces the hook run to re-initialize a stuck sensor when `auto_reinit` is enabled.
+    ///
+    /// Unlike [`Ms4525do::set_reinit_hook`], this accepts any [`ReinitHook`],
+    /// e.g. a closure owning the GPIO that switches the sensor supply.
+    ///
+    /// # Arguments
+    ///
+    /// * `hook` - The re-init hook
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let sensor = Ms4525do::new(i2c).with_reinit_hook(move || {
+    ///     power.set_low().ok();
+    ///     delay.delay_ms(10);
+    ///     power.set_high().ok();
+    /// });
+    /// ```
+    pub fn with_reinit_hook<H2: ReinitHook>(self, hook: H2) -> Ms4525do<I2C, T, H2> {
+        Ms4525do {
+            i2c: self.i2c,
+            address: self.address,
+            transfer: self.transfer,
+            fetch_mode: self.fetch_mode,
+            conversion_pending: self.conversion_pending,
+            last_fetch_us: self.last_fetch_us,
+            retry_policy: self.retry_policy,
+            retry_stats: self.retry_stats,
+            health: self.health,
+            stuck_detector: self.stuck_detector,
+            reinit_hook: Some(hook),
+            bus_recovered: self.bus_recovered,
+        }
+    }
+}
+
+impl<I2C, T, H> Ms4525do<I2C, T, H>
+where
+    I2C: I2c,
+    T: TransferFunction,
+    H: ReinitHook,
+{
+    /// Returns the transfer function used to convert raw counts.
+    pub fn transfer(&self) -> &T {
+        &self.transfer
+    }
+
+    /// Sets how the two data packets are fetched (default [`FetchMode::Transaction`]).
+    ///
+    /// Use [`FetchMode::SeparateReads`] if the I2C HAL cannot mix reads and
+    /// writes in one transaction, and [`FetchMode::Pipelined`] on a shared bus.
+    pub fn set_fetch_mode(&mut self, fetch_mode: FetchMode) {
+        self.fetch_mode = fetch_mode;
+        self.conversion_pending = false;
+    }
+
+    /// Returns how the two data packets are fetched.
+    pub fn fetch_mode(&self) -> FetchMode {
+        self.fetch_mode
+    }
+
+    /// Sets the retry policy applied by [`Ms4525do::read_data`].
+    ///
+    /// Use [`RetryPolicy::none`] to report every failed attempt.
+    pub fn set_retry_policy(&mut self, retry_policy: RetryPolicy) {
+        self.retry_policy = retry_policy;
+    }
+
+    /// Returns the retry policy applied by [`Ms4525do::read_data`].
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.retry_policy
+    }
+
+    /// Returns the read and retry counters.
+    pub fn retry_stats(&self) -> &RetryStats {
+        &self.retry_stats
+    }
+
+    /// Resets the read and retry counters.
+    pub fn reset_retry_stats(&mut self) {
+        self.retry_stats = RetryStats::default();
+    }
+
+    /// Returns the health statistics, updated on every read attempt.
+    ///
+    /// This does not access the sensor, so it can be called at any time.
+    pub fn health(&self) -> &Health {
+        &self.health
+    }
+
+    /// Sets the thresholds used by [`Ms4525do::health_status`].
+    pub fn set_health_thresholds(&mut self, thresholds: HealthThresholds) {
+        self.health.thresholds = thresholds;
+    }
+
+    /// Summarizes the health statistics, using `embassy_time` for the age of the last good reading.
+    pub fn health_status(&self) -> HealthStatus {
+        self.health.status(Some(Instant::now().as_millis()))
+    }
+
+    /// Resets the health statistics, keeping the thresholds.
+    pub fn reset_health(&mut self) {
+        self.health.reset();
+    }
+
+    /// Enables or disables stuck-sensor detection (disabled by default).
+    ///
+    /// When enabled, `read_data` returns [`Ms4525doError::SensorStuck`] while
+    /// the raw counts stay frozen, see [`crate::stuck`].
+    pub fn set_stuck_detection(&mut self, config: Option<StuckConfig>) {
+        self.stuck_detector = config.map(StuckDetector::new);
+    }
+
+    /// Returns the stuck-sensor detector, if enabled.
+    pub fn stuck_detector(&self) -> Option<&StuckDetector> {
+        self.stuck_detector.as_ref()
+    }
+
+    /// Sets the hook run to re-initialize a stuck sensor when `auto_reinit` is enabled.
+    ///
+    /// The MS4525DO has no reset command, so this is typically a function
+    /// that power cycles the sensor through a GPIO. Use
+    /// [`Ms4525do::with_reinit_hook`] to install a hook of another type.
+    pub fn set_reinit_hook(&mut self, hook: H) {
+        self.reinit_hook = Some(hook);
+    }
+
+    /// Re-initializes the sensor.
+    ///
+    /// Runs the re-init hook, if any, and restarts stuck-sensor detection.
+    /// The driver calls this itself when `auto_reinit` is enabled; call it
+    /// after recovering the bus (see `RecoveringI2c` in the `recovery` module).
+    pub fn reinit(&mut self) {
+        if let Some(hook) = self.reinit_hook.as_mut() {
+            hook.reinit();
+        }
+        if let Some(detector) = self.stuck_detector.as_mut() {
+            detector.reset();
+        }
+        self.conversion_pending = false;
+    }
+
+    /// Wraps the I2C peripheral in a [`RecoveringI2c`]
+    /// that recovers the bus after `threshold` consecutive failed transactions.
+    ///
+    /// `read_data` then re-initializes the sensor (see [`Ms4525do::reinit`])
+    /// after every read attempt during which the bus was recovered.
+    ///
+    /// # Arguments
+    ///
+    /// * `hook` - Recovery hook, typically running
+    ///   [`BusRecovery::recover`](crate::recovery::BusRecovery::recover)
+    /// * `threshold` - Number of consecutive failed transactions that trigger recovery
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let sensor = Ms4525do::new(i2c).with_bus_recovery(hook, 5);
+    /// ```
+    #[cfg(feature = "bus-recovery")]
+    pub fn with_bus_recovery<R: RecoverBus<I2C>>(
+        self,
+        hook: R,
+        threshold: u8,
+    ) -> Ms4525do<RecoveringI2c<I2C, R>, T, H> {
+        Ms4525do {
+            i2c: RecoveringI2c::new(self.i2c, hook, threshold),
+            address: self.address,
+            transfer: self.transfer,
+            fetch_mode: self.fetch_mode,
+            conversion_pending: self.conversion_pending,
+            last_fetch_us: self.last_fetch_us,
+            retry_policy: self.retry_policy,
+            retry_stats: self.retry_stats,
+            health: self.health,
+            stuck_detector: self.stuck_detector,
+            reinit_hook: self.reinit_hook,
+            bus_recovered: RecoveringI2c::take_recovered,
+        }
     }
 
     /// Reads pressure and temperature data from the sensor asynchronously.
@@ -92,9 +395,18 @@ where
     /// This method implements a double-read validation strategy to ensure data freshness:
     /// 1. Sends a measurement request command
     /// 2. Waits 2ms for fresh data (as per datasheet recommendations)
-    /// 3. Reads two consecutive 4-byte packets
+    ///
+    ///    With [`FetchMode::Pipelined`], steps 1 and 2 are skipped when the
+    ///    previous fetch requested this measurement
+    /// 3. Reads two consecutive 4-byte packets (see [`FetchMode`])
     /// 4. Validates status progression: NormalOperation → StaleData
     /// 5. Ensures pressure and temperature consistency between reads
+    /// 6. Rejects data outside the output band of the transfer function
+    ///
+    /// Failed attempts are retried according to the [`RetryPolicy`] set with
+    /// [`Ms4525do::set_retry_policy`], and the outcome is counted in
+    /// [`Ms4525do::retry_stats`]. Every attempt is also recorded in
+    /// [`Ms4525do::health`]. The error of the last attempt is returned.
     ///
     /// # Returns
     ///
@@ -107,6 +419,8 @@ where
     /// * `Ms4525doError::FaultDetected` - Sensor reports fault status
     /// * `Ms4525doError::InvalidStatus` - Unexpected status code
     /// * `Ms4525doError::StaleDataMismatch` - Data inconsistency between reads
+    /// * `Ms4525doError::SensorStuck` - Frozen output, if stuck-sensor detection is enabled
+    /// * `Ms4525doError::DataOutOfRange` - Saturated output or disconnected bridge
     ///
     /// # Example
     ///
@@ -119,82 +433,214 @@ where
     /// }
     /// ```
     pub async fn read_data(&mut self) -> Result<(f32, f32), Ms4525doError> {
-        // Send measurement request
-        let cmd = [READ_MR];
-        self.i2c
-            .write(self.address, &cmd)
-            .await
-            .map_err(|_| Ms4525doError::I2cError)?;
+        let sample = self.read_data_with_clock(&mut NoClock).await?;
+        Ok(sample.reading.into())
+    }
 
-        // Wait 2ms for fresh data (per datasheet and PX4 implementation)
-        Timer::after(Duration::from_millis(2)).await;
+    /// Timestamped equivalent of [`Ms4525do::read_data`].
+    ///
+    /// Timestamps come from `embassy_time::Instant` ([`EmbassyClock`]): one
+    /// just before the measurement request is sent and one once both data
+    /// packets have been read. With retries, the timestamps are those of the
+    /// successful attempt. With [`FetchMode::Pipelined`], a measurement
+    /// requested by the previous fetch has the fetch time of the previous
+    /// reading as its request time.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(TimestampedReading)` - The reading with its request and fetch times
+    /// * `Err(Ms4525doError)` - Error if communication fails or data is invalid
+    pub async fn read_data_timestamped(&mut self) -> Result<TimestampedReading, Ms4525doError> {
+        self.read_data_with_clock(&mut EmbassyClock).await
+    }
 
-        // Read two consecutive 4-byte packets for validation
-        let mut data_1 = [0u8; DATA_SIZE];
-        let mut data_2 = [0u8; DATA_SIZE];
+    async fn read_data_with_clock<C: Clock>(
+        &mut self,
+        clock: &mut C,
+    ) -> Result<TimestampedReading, Ms4525doError> {
+        let mut attempt = 1;
+        loop {
+            let result = self.read_once(clock).await;
+            if result.is_err() && (self.bus_recovered)(&mut self.i2c) {
+                // The bus was recovered during this attempt
+                self.reinit();
+            }
+            self.health
+                .record(&result, Some(Instant::now().as_millis()));
+            match result {
+                Ok(sample) => {
+                    self.retry_stats.record(attempt, true);
+                    return Ok(sample);
+                }
+                Err(e) if self.retry_policy.should_retry(&e, attempt) => {
+                    let backoff_ms = self.retry_policy.backoff_ms(attempt);
+                    Timer::after(Duration::from_millis(backoff_ms as u64)).await;
+                    attempt += 1;
+                }
+                Err(e) => {
+                    self.retry_stats.record(attempt, false);
+                    return Err(e);
+                }
+            }
+        }
+    }
 
-        self.i2c
-            .read(self.address, &mut data_1)
-            .await
-            .map_err(|_| Ms4525doError::I2cError)?;
+    /// Performs a single, unretried read attempt.
+    async fn read_once<C: Clock>(
+        &mut self,
+        clock: &mut C,
+    ) -> Result<TimestampedReading, Ms4525doError> {
+        // A pipelined measurement was requested by the previous fetch
+        let request_us = if self.conversion_pending {
+            self.last_fetch_us
+        } else {
+            clock.now_us()
+        };
+        let [data_1, data_2] = self.read_raw_frames().await?;
+        let fetch_us = clock.now_us();
+        self.last_fetch_us = fetch_us;
 
-        self.i2c
-            .read(self.address, &mut data_2)
-            .await
-            .map_err(|_| Ms4525doError::I2cError)?;
+        let frames = validate_frames(&data_1, &data_2);
+        if frames.is_err() {
+            // Send a new measurement request on the next attempt
+            self.conversion_pending = false;
+        }
+        let (bridge_data, temperature) = frames?;
+        check_range(bridge_data, self.transfer.output_band())?;
+        if let Some(detector) = self.stuck_detector.as_mut() {
+            if detector.update(bridge_data, temperature, Some(Instant::now().as_millis())) {
+                if detector.config().auto_reinit {
+                    self.reinit();
+                }
+                return Err(Ms4525doError::SensorStuck);
+            }
+        }
 
-        // Parse status from both reads
-        let status_1 = Status::from(data_1[0] >> 6);
-        let status_2 = Status::from(data_2[0] >> 6);
+        // Convert to physical units
+        let reading = Reading {
+            differential_pressure_pa: self.transfer.pressure_pa(bridge_data),
+            temperature_c: self.transfer.temperature_c(temperature),
+        };
 
-        // Check for sensor fault
-        if status_1 == Status::FaultDetected || status_2 == Status::FaultDetected {
-            return Err(Ms4525doError::FaultDetected);
-        }
+        Ok(TimestampedReading {
+            reading,
+            request_us,
+            fetch_us,
+        })
+    }
 
-        // Validate expected status progression: Normal → Stale
-        // This ensures we're getting fresh data followed by the same stale data
-        if status_1 != Status::NormalOperation || status_2 != Status::StaleData {
-            #[cfg(feature = "defmt")]
-            info!("Invalid status sequence: {:?} -> {:?}", status_1, status_2);
+    /// Typed equivalent of [`Ms4525do::read_data`].
+    ///
+    /// # Returns
+    ///
+    /// * `Ok((Pascals, Celsius))` - Differential pressure and temperature
+    /// * `Err(Ms4525doError)` - Error if communication fails or data is invalid
+    pub async fn read_data_typed(&mut self) -> Result<(Pascals, Celsius), Ms4525doError> {
+        let (diff_press_pa, temp_c) = self.read_data().await?;
+        Ok((Pascals(diff_press_pa), Celsius(temp_c)))
+    }
 
-            #[cfg(all(not(feature = "defmt"), feature = "log"))]
-            log::info!("Invalid status sequence: {:?} -> {:?}", status_1, status_2);
+    /// Runs a self-test and returns a detailed report.
+    ///
+    /// Takes a window of `config.samples` readings and checks that the sensor
+    /// answers, that the status progression, temperature and noise are
+    /// plausible, and that the output stays inside the band of the transfer
+    /// function, see [`crate::self_test`]. Readings are not retried, and the
+    /// health statistics, retry counters and stuck-sensor detector are not
+    /// updated.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - Test window and limits
+    ///
+    /// # Returns
+    ///
+    /// A [`SelfTestReport`] with the outcome of every check; use
+    /// [`SelfTestReport::passed`] for a single verdict
+    pub async fn self_test(&mut self, config: &SelfTestConfig) -> SelfTestReport {
+        let mut run = SelfTestRun::new(config);
+        for sample in 0..run.samples() {
+            if sample > 0 {
+                Timer::after(Duration::from_millis(run.interval_ms() as u64)).await;
+            }
+            let frames = self.read_raw_frames().await;
+            if !run.record(frames, &self.transfer) {
+                break;
+            }
+        }
+        run.finish()
+    }
 
-            return Err(Ms4525doError::InvalidStatus(status_1));
+    /// Reads the two raw 4-byte packets used for double-read validation.
+    ///
+    /// Performs steps 1-3 of [`Ms4525do::read_data`] without validating or
+    /// converting the result, which is useful for logging raw sensor frames.
+    /// Use [`crate::validate_frames`] to check the returned packets.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok([[u8; 4]; 2])` - The first and second packet as read from the sensor
+    /// * `Err(Ms4525doError::I2cError)` - I2C communication failure
+    pub async fn read_raw_frames(&mut self) -> Result<[[u8; DATA_SIZE]; 2], Ms4525doError> {
+        // Send measurement request, unless the previous pipelined fetch
+        // requested this measurement. It is a transaction of its own: holding
+        // a shared bus through the conversion time would stall every other
+        // device, and their traffic does not disturb the conversion.
+        if self.fetch_mode != FetchMode::Pipelined || !self.conversion_pending {
+            let cmd = [READ_MR];
+            self.i2c
+                .write(self.address, &cmd)
+                .await
+                .map_err(|_| Ms4525doError::I2cError)?;
+
+            // Wait 2ms for fresh data (per datasheet and PX4 implementation)
+            Timer::after(Duration::from_millis(2)).await;
         }
+        self.conversion_pending = false;
 
-        // Extract pressure and temperature from both reads
-        let bridge_data_1 = extract_bridge_data(&data_1);
-        let bridge_data_2 = extract_bridge_data(&data_2);
-        let temperature_1 = read_temperature(&data_1);
-        let temperature_2 = read_temperature(&data_2);
-
-        // Validate data consistency between reads
-        if bridge_data_1 != bridge_data_2 || temperature_1 != temperature_2 {
-            #[cfg(feature = "defmt")]
-            info!(
-                "Data mismatch: pressure {} != {}, temp {} != {}",
-                bridge_data_1, bridge_data_2, temperature_1, temperature_2
-            );
-
-            #[cfg(all(not(feature = "defmt"), feature = "log"))]
-            log::info!(
-                "Data mismatch: pressure {} != {}, temp {} != {}",
-                bridge_data_1,
-                bridge_data_2,
-                temperature_1,
-                temperature_2
-            );
-
-            return Err(Ms4525doError::StaleDataMismatch);
+        // Read two consecutive 4-byte packets for validation
+        let mut data_1 = [0u8; DATA_SIZE];
+        let mut data_2 = [0u8; DATA_SIZE];
+
+        match self.fetch_mode {
+            FetchMode::Transaction | FetchMode::Pipelined => self
+                .i2c
+                // Adjacent reads would be merged into one 8-byte read, so a
+                // measurement request separates them: the change of direction
+                // forces a repeated start before each read. The second read
+                // must start before the conversion it starts completes, see
+                // the transaction timing of `FetchMode`.
+                .transaction(
+                    self.address,
+                    &mut [
+                        Operation::Read(&mut data_1),
+                        Operation::Write(&[READ_MR]),
+                        Operation::Read(&mut data_2),
+                    ],
+                )
+                .await
+                .map_err(|_| Ms4525doError::I2cError)?,
+            FetchMode::SeparateReads => {
+                self.i2c
+                    .read(self.address, &mut data_1)
+                    .await
+                    .map_err(|_| Ms4525doError::I2cError)?;
+                self.i2c
+                    .read(self.address, &mut data_2)
+                    .await
+                    .map_err(|_| Ms4525doError::I2cError)?;
+            }
         }
+        // The request between the packets started the next conversion
+        self.conversion_pending = self.fetch_mode == FetchMode::Pipelined;
 
-        // Convert to physical units
-        let diff_press_pa = calculate_pressure_differential_pa(bridge_data_1);
-        let temp_c = calculate_temperature_deg_c(temperature_1);
+        Ok([data_1, data_2])
+    }
 
-        Ok((diff_press_pa, temp_c))
+    /// Returns the underlying I2C peripheral.
+    #[cfg(feature = "bus-recovery")]
+    pub(crate) fn i2c_mut(&mut self) -> &mut I2C {
+        &mut self.i2c
     }
 
     /// Consumes the sensor driver and returns the underlying I2C peripheral.
@@ -204,3 +650,21 @@ where
         self.i2c
     }
 }
+
+/// Bus check of a driver whose I2C peripheral does not recover the bus
+fn no_bus_recovery<I2C>(_i2c: &mut I2C) -> bool {
+    false
+}
+
+impl<I2C, T, H> DifferentialPressureSensor for Ms4525do<I2C, T, H>
+where
+    I2C: I2c,
+    T: TransferFunction,
+    H: ReinitHook,
+{
+    type Error = Ms4525doError;
+
+    async fn read_pressure(&mut self) -> Result<Reading, Ms4525doError> {
+        self.read_data().await.map(Reading::from)
+    }
+}
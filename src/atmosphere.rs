@@ -0,0 +1,460 @@
+//! International Standard Atmosphere (ISA) model and air density.
+//!
+//! Implements the ISA troposphere (constant lapse rate up to 11 km) and the
+//! lower stratosphere (isothermal from 11 km to 20 km), which covers the
+//! operating envelope of pitot-based airspeed sensing. Altitudes are
+//! geopotential metres; pressures are in Pascals.
+//!
+//! Use these functions to turn a static pressure measurement into pressure
+//! altitude, density or density altitude, and pass the density to
+//! [`crate::calculate_airspeed_with_density`] or the static pressure to
+//! [`crate::calculate_true_airspeed`] instead of assuming sea level. When a
+//! relative humidity measurement is available, [`moist_air_density`] corrects
+//! the density for water vapour.
+//!
+//! # Example
+//!
+//! ```
+//! use ms4525do::atmosphere;
+//! use ms4525do::calculate_true_airspeed;
+//!
+//! // Static pressure from a barometer at a 2500 m MSL site on a warm day
+//! let static_pa = atmosphere::isa_pressure_pa(2500.0);
+//! let altitude = atmosphere::pressure_altitude_m(static_pa);
+//! assert!((altitude - 2500.0).abs() < 1.0);
+//!
+//! let deviation = atmosphere::isa_temperature_deviation_c(static_pa, 20.0);
+//! assert!(deviation > 15.0);
+//!
+//! let tas = calculate_true_airspeed(200.0, 20.0, static_pa);
+//! assert!(tas > 19.0);
+//! ```
+
+/// ISA sea-level pressure in Pascals
+pub const SEA_LEVEL_PRESSURE_PA: f32 = 101_325.0;
+
+/// ISA sea-level temperature in Kelvin
+pub const SEA_LEVEL_TEMPERATURE_K: f32 = 288.15;
+
+/// Offset between degrees Celsius and Kelvin
+pub const CELSIUS_TO_KELVIN: f32 = 273.15;
+
+/// Specific gas constant for dry air, in J/(kg·K)
+pub const GAS_CONSTANT_DRY_AIR: f32 = 287.05;
+
+/// Specific gas constant for water vapour, in J/(kg·K)
+pub const GAS_CONSTANT_WATER_VAPOR: f32 = 461.495;
+
+/// Standard gravity, in m/s²
+pub const STANDARD_GRAVITY: f32 = 9.806_65;
+
+/// ISA temperature lapse rate in the troposphere, in K/m
+pub const LAPSE_RATE_K_PER_M: f32 = 0.0065;
+
+/// Altitude of the ISA tropopause, in metres
+pub const TROPOPAUSE_ALTITUDE_M: f32 = 11_000.0;
+
+/// Upper altitude limit of this model, in metres
+pub const MAX_ALTITUDE_M: f32 = 20_000.0;
+
+/// ISA temperature at and above the tropopause, in Kelvin
+pub const TROPOPAUSE_TEMPERATURE_K: f32 =
+    SEA_LEVEL_TEMPERATURE_K - LAPSE_RATE_K_PER_M * TROPOPAUSE_ALTITUDE_M;
+
+/// Exponent g / (R · L) of the troposphere pressure equation
+const PRESSURE_EXPONENT: f32 = STANDARD_GRAVITY / (GAS_CONSTANT_DRY_AIR * LAPSE_RATE_K_PER_M);
+
+/// ISA sea-level air density, in kg/m³
+pub fn sea_level_density() -> f32 {
+    air_density(
+        SEA_LEVEL_PRESSURE_PA,
+        SEA_LEVEL_TEMPERATURE_K - CELSIUS_TO_KELVIN,
+    )
+}
+
+/// Returns the ISA pressure at the tropopause, in Pascals.
+fn tropopause_pressure_pa() -> f32 {
+    SEA_LEVEL_PRESSURE_PA
+        * libm::powf(
+            TROPOPAUSE_TEMPERATURE_K / SEA_LEVEL_TEMPERATURE_K,
+            PRESSURE_EXPONENT,
+        )
+}
+
+/// Returns the ISA temperature at an altitude.
+///
+/// # Arguments
+///
+/// * `altitude_m` - Geopotential altitude in metres, clamped to [`MAX_ALTITUDE_M`]
+///
+/// # Returns
+///
+/// Temperature in Kelvin
+pub fn isa_temperature_k(altitude_m: f32) -> f32 {
+    let altitude_m = altitude_m.min(MAX_ALTITUDE_M);
+    if altitude_m <= TROPOPAUSE_ALTITUDE_M {
+        SEA_LEVEL_TEMPERATURE_K - LAPSE_RATE_K_PER_M * altitude_m
+    } else {
+        TROPOPAUSE_TEMPERATURE_K
+    }
+}
+
+/// Returns the ISA static pressure at an altitude.
+///
+/// # Arguments
+///
+/// * `altitude_m` - Geopotential altitude in metres, clamped to [`MAX_ALTITUDE_M`]
+///
+/// # Returns
+///
+/// Static pressure in Pascals
+pub fn isa_pressure_pa(altitude_m: f32) -> f32 {
+    let altitude_m = altitude_m.min(MAX_ALTITUDE_M);
+    if altitude_m <= TROPOPAUSE_ALTITUDE_M {
+        SEA_LEVEL_PRESSURE_PA
+            * libm::powf(
+                isa_temperature_k(altitude_m) / SEA_LEVEL_TEMPERATURE_K,
+                PRESSURE_EXPONENT,
+            )
+    } else {
+        tropopause_pressure_pa()
+            * libm::expf(
+                -STANDARD_GRAVITY * (altitude_m - TROPOPAUSE_ALTITUDE_M)
+                    / (GAS_CONSTANT_DRY_AIR * TROPOPAUSE_TEMPERATURE_K),
+            )
+    }
+}
+
+/// Returns the ISA air density at an altitude.
+///
+/// # Arguments
+///
+/// * `altitude_m` - Geopotential altitude in metres, clamped to [`MAX_ALTITUDE_M`]
+///
+/// # Returns
+///
+/// Air density in kg/m³
+pub fn isa_density(altitude_m: f32) -> f32 {
+    isa_pressure_pa(altitude_m) / (GAS_CONSTANT_DRY_AIR * isa_temperature_k(altitude_m))
+}
+
+/// Converts static pressure to ISA pressure altitude.
+///
+/// # Arguments
+///
+/// * `static_pressure_pa` - Static (ambient) pressure in Pascals
+///
+/// # Returns
+///
+/// Pressure altitude in metres, clamped to [`MAX_ALTITUDE_M`]. Pressures above
+/// the ISA sea-level pressure give negative altitudes.
+pub fn pressure_altitude_m(static_pressure_pa: f32) -> f32 {
+    if static_pressure_pa >= tropopause_pressure_pa() {
+        altitude_above_reference_m(static_pressure_pa, SEA_LEVEL_PRESSURE_PA)
+    } else {
+        let altitude = TROPOPAUSE_ALTITUDE_M
+            - GAS_CONSTANT_DRY_AIR * TROPOPAUSE_TEMPERATURE_K / STANDARD_GRAVITY
+                * libm::logf(static_pressure_pa / tropopause_pressure_pa());
+        altitude.min(MAX_ALTITUDE_M)
+    }
+}
+
+/// Troposphere altitude of a static pressure above a reference pressure level.
+fn altitude_above_reference_m(static_pressure_pa: f32, reference_pressure_pa: f32) -> f32 {
+    SEA_LEVEL_TEMPERATURE_K / LAPSE_RATE_K_PER_M
+        * (1.0
+            - libm::powf(
+                static_pressure_pa / reference_pressure_pa,
+                1.0 / PRESSURE_EXPONENT,
+            ))
+}
+
+/// Converts static pressure to altitude above mean sea level using a QNH setting.
+///
+/// # Arguments
+///
+/// * `static_pressure_pa` - Static (ambient) pressure in Pascals
+/// * `qnh_pa` - Altimeter setting (sea-level pressure) in Pascals
+///
+/// # Returns
+///
+/// Altitude above mean sea level in metres
+pub fn altitude_qnh_m(static_pressure_pa: f32, qnh_pa: f32) -> f32 {
+    altitude_above_reference_m(static_pressure_pa, qnh_pa)
+}
+
+/// Converts static pressure to height above a field using a QFE setting.
+///
+/// # Arguments
+///
+/// * `static_pressure_pa` - Static (ambient) pressure in Pascals
+/// * `qfe_pa` - Pressure at the field elevation in Pascals
+///
+/// # Returns
+///
+/// Height above the field in metres
+pub fn height_qfe_m(static_pressure_pa: f32, qfe_pa: f32) -> f32 {
+    altitude_above_reference_m(static_pressure_pa, qfe_pa)
+}
+
+/// Reduces a field pressure (QFE) to a sea-level altimeter setting (QNH).
+///
+/// # Arguments
+///
+/// * `qfe_pa` - Pressure at the field elevation in Pascals
+/// * `field_elevation_m` - Field elevation above mean sea level in metres
+///
+/// # Returns
+///
+/// QNH in Pascals
+pub fn qnh_from_qfe(qfe_pa: f32, field_elevation_m: f32) -> f32 {
+    qfe_pa
+        / libm::powf(
+            1.0 - LAPSE_RATE_K_PER_M * field_elevation_m / SEA_LEVEL_TEMPERATURE_K,
+            PRESSURE_EXPONENT,
+        )
+}
+
+/// Computes dry-air density from the ideal gas law, ρ = P / (R · T).
+///
+/// # Arguments
+///
+/// * `static_pressure_pa` - Static (ambient) pressure in Pascals
+/// * `temperature_c` - Outside air temperature in degrees Celsius
+///
+/// # Returns
+///
+/// Air density in kg/m³
+pub fn air_density(static_pressure_pa: f32, temperature_c: f32) -> f32 {
+    static_pressure_pa / (GAS_CONSTANT_DRY_AIR * (temperature_c + CELSIUS_TO_KELVIN))
+}
+
+/// Computes the saturation vapour pressure over water using the Buck (1996) equation.
+///
+/// Accurate to about 0.05% between -40°C and +50°C.
+///
+/// # Arguments
+///
+/// * `temperature_c` - Air temperature in degrees Celsius
+///
+/// # Returns
+///
+/// Saturation vapour pressure in Pascals
+pub fn saturation_vapor_pressure_pa(temperature_c: f32) -> f32 {
+    611.21
+        * libm::expf((18.678 - temperature_c / 234.5) * (temperature_c / (257.14 + temperature_c)))
+}
+
+/// Computes the saturation vapour pressure over water using the Magnus formula.
+///
+/// Uses the Alduchov and Eskridge (1996) coefficients. Slightly cheaper than
+/// [`saturation_vapor_pressure_pa`] and within 0.4% of it between -40°C and +50°C.
+///
+/// # Arguments
+///
+/// * `temperature_c` - Air temperature in degrees Celsius
+///
+/// # Returns
+///
+/// Saturation vapour pressure in Pascals
+pub fn saturation_vapor_pressure_magnus_pa(temperature_c: f32) -> f32 {
+    610.94 * libm::expf(17.625 * temperature_c / (temperature_c + 243.04))
+}
+
+/// Computes the partial pressure of water vapour from relative humidity.
+///
+/// # Arguments
+///
+/// * `temperature_c` - Air temperature in degrees Celsius
+/// * `relative_humidity_percent` - Relative humidity in percent, clamped to 0-100
+///
+/// # Returns
+///
+/// Vapour pressure in Pascals
+pub fn vapor_pressure_pa(temperature_c: f32, relative_humidity_percent: f32) -> f32 {
+    relative_humidity_percent.clamp(0.0, 100.0) / 100.0
+        * saturation_vapor_pressure_pa(temperature_c)
+}
+
+/// Computes the density of moist air.
+///
+/// Treats the air as a mixture of dry air and water vapour, each obeying the
+/// ideal gas law: ρ = P_d / (R_d · T) + P_v / (R_v · T). Humid air is less
+/// dense than dry air at the same pressure and temperature, so ignoring
+/// humidity under-reads true airspeed.
+///
+/// # Arguments
+///
+/// * `static_pressure_pa` - Static (ambient) pressure in Pascals
+/// * `temperature_c` - Outside air temperature in degrees Celsius
+/// * `relative_humidity_percent` - Relative humidity in percent, clamped to 0-100
+///
+/// # Returns
+///
+/// Air density in kg/m³
+///
+/// # Example
+///
+/// ```
+/// use ms4525do::atmosphere::moist_air_density;
+/// use ms4525do::calculate_airspeed_with_density;
+///
+/// // Humidity from an SHT or BME sensor next to the pitot
+/// let density = moist_air_density(100_800.0, 32.0, 85.0);
+/// let tas = calculate_airspeed_with_density(250.0, density);
+/// println!("TAS: {:.2} m/s", tas);
+/// ```
+pub fn moist_air_density(
+    static_pressure_pa: f32,
+    temperature_c: f32,
+    relative_humidity_percent: f32,
+) -> f32 {
+    let temperature_k = temperature_c + CELSIUS_TO_KELVIN;
+    let vapor_pa = vapor_pressure_pa(temperature_c, relative_humidity_percent);
+    let dry_pa = static_pressure_pa - vapor_pa;
+    dry_pa / (GAS_CONSTANT_DRY_AIR * temperature_k)
+        + vapor_pa / (GAS_CONSTANT_WATER_VAPOR * temperature_k)
+}
+
+/// Converts an air density to the ISA altitude with the same density.
+///
+/// # Arguments
+///
+/// * `density` - Air density in kg/m³
+///
+/// # Returns
+///
+/// Density altitude in metres, clamped to [`MAX_ALTITUDE_M`]
+pub fn density_altitude_from_density_m(density: f32) -> f32 {
+    let tropopause_density = isa_density(TROPOPAUSE_ALTITUDE_M);
+    if density >= tropopause_density {
+        SEA_LEVEL_TEMPERATURE_K / LAPSE_RATE_K_PER_M
+            * (1.0
+                - libm::powf(
+                    density / sea_level_density(),
+                    1.0 / (PRESSURE_EXPONENT - 1.0),
+                ))
+    } else {
+        let altitude = TROPOPAUSE_ALTITUDE_M
+            - GAS_CONSTANT_DRY_AIR * TROPOPAUSE_TEMPERATURE_K / STANDARD_GRAVITY
+                * libm::logf(density / tropopause_density);
+        altitude.min(MAX_ALTITUDE_M)
+    }
+}
+
+/// Computes the density altitude from static pressure and outside air temperature.
+///
+/// # Arguments
+///
+/// * `static_pressure_pa` - Static (ambient) pressure in Pascals
+/// * `temperature_c` - Outside air temperature in degrees Celsius
+///
+/// # Returns
+///
+/// Density altitude in metres, clamped to [`MAX_ALTITUDE_M`]
+pub fn density_altitude_m(static_pressure_pa: f32, temperature_c: f32) -> f32 {
+    density_altitude_from_density_m(air_density(static_pressure_pa, temperature_c))
+}
+
+/// Returns the deviation of the outside air temperature from ISA at the pressure altitude.
+///
+/// # Arguments
+///
+/// * `static_pressure_pa` - Static (ambient) pressure in Pascals
+/// * `temperature_c` - Outside air temperature in degrees Celsius
+///
+/// # Returns
+///
+/// ISA deviation in °C (positive when warmer than standard)
+pub fn isa_temperature_deviation_c(static_pressure_pa: f32, temperature_c: f32) -> f32 {
+    let isa_k = isa_temperature_k(pressure_altitude_m(static_pressure_pa));
+    temperature_c + CELSIUS_TO_KELVIN - isa_k
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_isa_reference_values() {
+        // (altitude, pressure, temperature) from the ISA tables
+        let table = [
+            (0.0, 101_325.0, 288.15),
+            (2_500.0, 74_682.0, 271.90),
+            (11_000.0, 22_632.0, 216.65),
+            (20_000.0, 5_474.9, 216.65),
+        ];
+        for (altitude, pressure, temperature) in table {
+            let p = isa_pressure_pa(altitude);
+            assert!(
+                (p - pressure).abs() / pressure < 1e-3,
+                "ISA pressure at {} m: {}",
+                altitude,
+                p
+            );
+            assert!((isa_temperature_k(altitude) - temperature).abs() < 0.01);
+        }
+        assert!((sea_level_density() - 1.225).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_pressure_altitude_round_trip() {
+        for altitude in [-500.0, 0.0, 2_500.0, 10_999.0, 15_000.0, 19_999.0] {
+            let h = pressure_altitude_m(isa_pressure_pa(altitude));
+            assert!((h - altitude).abs() < 1.0, "{} m -> {} m", altitude, h);
+        }
+    }
+
+    #[test]
+    fn test_density_altitude() {
+        for altitude in [0.0, 2_500.0, 15_000.0] {
+            let p = isa_pressure_pa(altitude);
+            let t = isa_temperature_k(altitude) - CELSIUS_TO_KELVIN;
+            assert!((density_altitude_m(p, t) - altitude).abs() < 2.0);
+            assert!(isa_temperature_deviation_c(p, t).abs() < 0.01);
+        }
+
+        // A hot day at 2500 m pushes density altitude well above the field
+        let p = isa_pressure_pa(2_500.0);
+        assert!(density_altitude_m(p, 30.0) > 3_500.0);
+    }
+
+    #[test]
+    fn test_qnh_qfe() {
+        let qnh = 102_000.0;
+        let field_elevation = 600.0;
+        let qfe = qnh
+            * libm::powf(
+                1.0 - LAPSE_RATE_K_PER_M * field_elevation / SEA_LEVEL_TEMPERATURE_K,
+                PRESSURE_EXPONENT,
+            );
+
+        assert!((qnh_from_qfe(qfe, field_elevation) - qnh).abs() < 0.5);
+        assert!((altitude_qnh_m(qfe, qnh) - field_elevation).abs() < 0.5);
+        assert!(height_qfe_m(qfe, qfe).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_saturation_vapor_pressure() {
+        // (temperature, saturation vapour pressure) from the WMO tables
+        for (temperature, expected) in [(0.0, 611.2), (20.0, 2_339.0), (35.0, 5_629.0)] {
+            let buck = saturation_vapor_pressure_pa(temperature);
+            let magnus = saturation_vapor_pressure_magnus_pa(temperature);
+            assert!((buck - expected).abs() / expected < 2e-3, "{}", buck);
+            assert!((magnus - expected).abs() / expected < 5e-3, "{}", magnus);
+        }
+    }
+
+    #[test]
+    fn test_moist_air_density() {
+        let dry = air_density(101_325.0, 30.0);
+        assert_eq!(moist_air_density(101_325.0, 30.0, 0.0), dry);
+
+        // Saturated air at 30°C is about 1.5% lighter than dry air
+        let wet = moist_air_density(101_325.0, 30.0, 100.0);
+        assert!(wet < dry);
+        assert!((1.0 - wet / dry - 0.0155).abs() < 0.002);
+
+        assert_eq!(moist_air_density(101_325.0, 30.0, 150.0), wet);
+    }
+}
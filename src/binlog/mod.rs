@@ -0,0 +1,897 @@
+//! Compact binary log format for raw sensor frames.
+//!
+//! Every record stores the timestamp, both raw 4-byte packets of a
+//! double-read, their decoded status codes and the error returned by
+//! validation (if any). Records are CRC-protected and versioned so logs stay
+//! decodable as fields are added.
+//!
+//! [`BinLogWriter`] writes records to any [`LogStorage`] in `no_std`
+//! environments, buffering them into 512-byte blocks and handling file
+//! rotation and flushing. With the `sdmmc` feature, [`SdmmcStorage`] writes
+//! the log files to an `embedded-sdmmc` FAT volume. With the `std` feature,
+//! [`write_csv`] and [`write_json`] convert a log file for offline analysis.
+//!
+//! # Record Layout
+//!
+//! | Byte   | Field                                         |
+//! |--------|-----------------------------------------------|
+//! | 0-1    | Sync word (0xA5 0x4C)                         |
+//! | 2      | Format version                                |
+//! | 3      | Payload length N                              |
+//! | 4..4+N | Payload                                       |
+//! | 4+N..  | CRC-16/CCITT-FALSE over bytes 0..4+N (little-endian) |
+//!
+//! Version 1 payload (18 bytes, little-endian):
+//!
+//! | Byte  | Field                                                  |
+//! |-------|--------------------------------------------------------|
+//! | 0-7   | Timestamp in microseconds                              |
+//! | 8-11  | First raw packet                                       |
+//! | 12-15 | Second raw packet                                      |
+//! | 16    | Status of first packet (bits 0-1) and second (bits 2-3) |
+//! | 17    | Error code (0 = none), status payload in bits 4-5      |
+//!
+//! Decoders accept payloads longer than they understand and ignore the
+//! trailing bytes, so new fields must only ever be appended.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use ms4525do::binlog::{BinLogWriter, LogConfig, LogRecord};
+//!
+//! let mut log = BinLogWriter::new(storage, LogConfig::default())?;
+//!
+//! loop {
+//!     let record = match sensor.read_raw_frames(&mut delay) {
+//!         Ok(frames) => LogRecord::from_frames(now_us(), frames, sensor.transfer()),
+//!         Err(e) => LogRecord::from_error(now_us(), e),
+//!     };
+//!     log.write_record(&record)?;
+//! }
+//! ```
+
+use crate::common::{
+    check_range, extract_bridge_data, read_temperature, validate_frames, Status, DATA_SIZE,
+};
+use crate::transfer::TransferFunction;
+use crate::Ms4525doError;
+
+#[cfg(feature = "defmt")]
+use defmt::Format;
+
+#[cfg(feature = "sdmmc")]
+mod sdmmc;
+#[cfg(feature = "sdmmc")]
+pub use sdmmc::SdmmcStorage;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Sync word marking the start of every record
+pub const SYNC_WORD: [u8; 2] = [0xA5, 0x4C];
+
+/// Record format version written by this crate
+pub const FORMAT_VERSION: u8 = 1;
+
+/// Payload length of a version 1 record
+pub const PAYLOAD_SIZE: usize = 18;
+
+/// Total encoded size of a record written by this crate (24 bytes)
+pub const RECORD_SIZE: usize = 4 + PAYLOAD_SIZE + 2;
+
+/// Size of the write buffer, matching the SD card block size
+pub const BLOCK_SIZE: usize = 512;
+
+/// One logged sensor read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LogRecord {
+    /// Time the read was made, in microseconds
+    pub timestamp_us: u64,
+    /// First and second raw packet of the double-read
+    pub frames: [[u8; DATA_SIZE]; 2],
+    /// Error the read produced, if any
+    pub error: Option<Ms4525doError>,
+}
+
+/// Errors that can occur while decoding a log record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+pub enum DecodeError {
+    /// Not enough bytes left for a complete record.
+    Truncated,
+    /// The record does not start with the sync word.
+    BadSync,
+    /// The record was written with an unknown format version.
+    UnsupportedVersion(u8),
+    /// The payload is shorter than required by its format version.
+    PayloadTooShort,
+    /// The stored CRC does not match the record contents.
+    CrcMismatch,
+    /// The stored error code does not name a known error.
+    InvalidErrorCode(u8),
+}
+
+impl core::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            DecodeError::Truncated => write!(f, "Truncated log record"),
+            DecodeError::BadSync => write!(f, "Missing log record sync word"),
+            DecodeError::UnsupportedVersion(version) => {
+                write!(f, "Unsupported log format version: {}", version)
+            }
+            DecodeError::PayloadTooShort => write!(f, "Log record payload too short"),
+            DecodeError::CrcMismatch => write!(f, "Log record CRC mismatch"),
+            DecodeError::InvalidErrorCode(code) => {
+                write!(f, "Invalid log record error code: {}", code)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeError {}
+
+impl LogRecord {
+    /// Creates a record from the raw packets of a double-read.
+    ///
+    /// The packets go through the same checks as one `read_data` attempt:
+    /// [`validate_frames`], then [`check_range`] against the output band of
+    /// `transfer`. The record carries the error `read_data` would have
+    /// returned, except for `SensorStuck`, which depends on earlier readings.
+    ///
+    /// # Arguments
+    ///
+    /// * `timestamp_us` - Time of the read in microseconds
+    /// * `frames` - Raw packets, e.g. from `read_raw_frames`
+    /// * `transfer` - Transfer function of the sensor, e.g. from the driver's `transfer()`
+    pub fn from_frames<T: TransferFunction>(
+        timestamp_us: u64,
+        frames: [[u8; DATA_SIZE]; 2],
+        transfer: &T,
+    ) -> Self {
+        let error = validate_frames(&frames[0], &frames[1])
+            .and_then(|(bridge_data, _)| check_range(bridge_data, transfer.output_band()))
+            .err();
+        Self {
+            timestamp_us,
+            frames,
+            error,
+        }
+    }
+
+    /// Creates a record for a read that failed before any data was received.
+    ///
+    /// # Arguments
+    ///
+    /// * `timestamp_us` - Time of the read in microseconds
+    /// * `error` - Error returned by the driver
+    pub fn from_error(timestamp_us: u64, error: Ms4525doError) -> Self {
+        Self {
+            timestamp_us,
+            frames: [[0; DATA_SIZE]; 2],
+            error: Some(error),
+        }
+    }
+
+    /// Returns the status codes of the first and second packet.
+    pub fn statuses(&self) -> (Status, Status) {
+        (
+            Status::from(self.frames[0][0] >> 6),
+            Status::from(self.frames[1][0] >> 6),
+        )
+    }
+
+    /// Returns the decoded (differential_pressure_pa, temperature_c) of a successful read.
+    ///
+    /// # Arguments
+    ///
+    /// * `transfer` - Transfer function of the sensor that produced the record
+    pub fn reading<T: TransferFunction>(&self, transfer: &T) -> Option<(f32, f32)> {
+        if self.error.is_some() {
+            return None;
+        }
+        let frame = &self.frames[0];
+        Some((
+            transfer.pressure_pa(extract_bridge_data(frame)),
+            transfer.temperature_c(read_temperature(frame)),
+        ))
+    }
+
+    /// Encodes the record in the current format version.
+    pub fn encode(&self) -> [u8; RECORD_SIZE] {
+        let (status_1, status_2) = self.statuses();
+
+        let mut record = [0u8; RECORD_SIZE];
+        record[0..2].copy_from_slice(&SYNC_WORD);
+        record[2] = FORMAT_VERSION;
+        record[3] = PAYLOAD_SIZE as u8;
+        record[4..12].copy_from_slice(&self.timestamp_us.to_le_bytes());
+        record[12..16].copy_from_slice(&self.frames[0]);
+        record[16..20].copy_from_slice(&self.frames[1]);
+        record[20] = status_1 as u8 | ((status_2 as u8) << 2);
+        record[21] = self.error.map_or(0, |e| e.code());
+
+        let crc = crc16(&record[..RECORD_SIZE - 2]);
+        record[RECORD_SIZE - 2..].copy_from_slice(&crc.to_le_bytes());
+        record
+    }
+
+    /// Decodes one record from the start of `bytes`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok((LogRecord, usize))` - The record and the number of bytes it occupied
+    /// * `Err(DecodeError)` - The bytes do not start with a valid record
+    pub fn decode(bytes: &[u8]) -> Result<(Self, usize), DecodeError> {
+        if bytes.len() < 4 {
+            return Err(DecodeError::Truncated);
+        }
+        if bytes[0..2] != SYNC_WORD {
+            return Err(DecodeError::BadSync);
+        }
+        if bytes[2] == 0 || bytes[2] > FORMAT_VERSION {
+            return Err(DecodeError::UnsupportedVersion(bytes[2]));
+        }
+
+        let payload_len = bytes[3] as usize;
+        let record_len = 4 + payload_len + 2;
+        if bytes.len() < record_len {
+            return Err(DecodeError::Truncated);
+        }
+
+        let stored_crc = u16::from_le_bytes([bytes[record_len - 2], bytes[record_len - 1]]);
+        if crc16(&bytes[..record_len - 2]) != stored_crc {
+            return Err(DecodeError::CrcMismatch);
+        }
+        if payload_len < PAYLOAD_SIZE {
+            return Err(DecodeError::PayloadTooShort);
+        }
+
+        let payload = &bytes[4..4 + payload_len];
+        let mut frames = [[0u8; DATA_SIZE]; 2];
+        frames[0].copy_from_slice(&payload[8..12]);
+        frames[1].copy_from_slice(&payload[12..16]);
+
+        let record = Self {
+            timestamp_us: u64::from_le_bytes(payload[0..8].try_into().unwrap()),
+            frames,
+            error: decode_error(payload[17])?,
+        };
+        Ok((record, record_len))
+    }
+}
+
+/// Maps an on-disk code back to its error.
+fn decode_error(code: u8) -> Result<Option<Ms4525doError>, DecodeError> {
+    match code {
+        0 => Ok(None),
+        _ => Ms4525doError::from_code(code)
+            .map(Some)
+            .ok_or(DecodeError::InvalidErrorCode(code)),
+    }
+}
+
+/// Computes CRC-16/CCITT-FALSE (polynomial 0x1021, initial value 0xFFFF).
+fn crc16(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0xFFFF;
+    for &byte in data {
+        crc ^= (byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+/// Iterator over the records of a log file, see [`decode_records`].
+pub struct Records<'a> {
+    bytes: &'a [u8],
+}
+
+/// Decodes all records in a log file.
+///
+/// Corrupt records are reported as errors, after which the iterator
+/// resynchronizes on the next sync word. A truncated final record (e.g. after
+/// a power loss) ends the iteration with [`DecodeError::Truncated`].
+pub fn decode_records(bytes: &[u8]) -> Records<'_> {
+    Records { bytes }
+}
+
+impl Iterator for Records<'_> {
+    type Item = Result<LogRecord, DecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.bytes.is_empty() {
+            return None;
+        }
+
+        match LogRecord::decode(self.bytes) {
+            Ok((record, len)) => {
+                self.bytes = &self.bytes[len..];
+                Some(Ok(record))
+            }
+            Err(DecodeError::Truncated) => {
+                self.bytes = &[];
+                Some(Err(DecodeError::Truncated))
+            }
+            Err(e) => {
+                // Skip to the next candidate sync word
+                let skip = self.bytes[1..]
+                    .windows(2)
+                    .position(|w| w == SYNC_WORD)
+                    .map_or(self.bytes.len(), |pos| pos + 1);
+                self.bytes = &self.bytes[skip..];
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+/// Storage backend for [`BinLogWriter`].
+///
+/// Implement this for the filesystem in use. `SdmmcStorage` (feature
+/// `sdmmc`) implements it for `embedded-sdmmc` FAT volumes.
+pub trait LogStorage {
+    /// Error type reported by the storage backend
+    type Error;
+
+    /// Creates (or truncates) the named file and makes it the current file.
+    fn create(&mut self, name: &str) -> Result<(), Self::Error>;
+
+    /// Appends data to the current file.
+    fn write(&mut self, data: &[u8]) -> Result<(), Self::Error>;
+
+    /// Commits written data of the current file to the medium.
+    fn flush(&mut self) -> Result<(), Self::Error>;
+
+    /// Closes the current file.
+    fn close(&mut self) -> Result<(), Self::Error>;
+}
+
+/// When buffered records are written through to storage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum FlushPolicy {
+    /// Flush after every record (safest, slowest)
+    EveryRecord,
+    /// Flush after the given number of records
+    EveryNRecords(u16),
+    /// Only write full 512-byte blocks (fastest, loses up to one block on power loss)
+    BlockFull,
+}
+
+/// Configuration of a [`BinLogWriter`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LogConfig {
+    /// Maximum file size in bytes before rotating to the next file
+    pub max_file_size: u32,
+    /// When to write buffered records through to storage
+    pub flush_policy: FlushPolicy,
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        Self {
+            max_file_size: 16 * 1024 * 1024,
+            flush_policy: FlushPolicy::EveryNRecords(50),
+        }
+    }
+}
+
+/// Returns the 8.3 file name used for the log file with the given index.
+///
+/// Files are named `LOG00000.BIN` to `LOG99999.BIN`; larger indices wrap.
+pub fn log_file_name(index: u32) -> [u8; 12] {
+    let mut name = *b"LOG00000.BIN";
+    let mut value = index % 100_000;
+    for digit in name[3..8].iter_mut().rev() {
+        *digit = b'0' + (value % 10) as u8;
+        value /= 10;
+    }
+    name
+}
+
+/// Buffered, rotating writer of binary log records.
+///
+/// Records are collected in a 512-byte buffer and written to storage
+/// according to the configured [`FlushPolicy`]. When the current file would
+/// exceed `max_file_size`, it is closed and the next `LOGnnnnn.BIN` file is
+/// created.
+pub struct BinLogWriter<S> {
+    storage: S,
+    config: LogConfig,
+    buffer: [u8; BLOCK_SIZE],
+    buffered: usize,
+    file_index: u32,
+    file_size: u32,
+    unflushed_records: u16,
+}
+
+impl<S> BinLogWriter<S>
+where
+    S: LogStorage,
+{
+    /// Creates a new writer and opens the first log file (`LOG00000.BIN`).
+    ///
+    /// # Errors
+    ///
+    /// Returns any error reported by the storage backend.
+    pub fn new(storage: S, config: LogConfig) -> Result<Self, S::Error> {
+        Self::new_with_index(storage, config, 0)
+    }
+
+    /// Creates a new writer starting at the given file index.
+    ///
+    /// Useful to continue after the last file found on the card instead of
+    /// overwriting it.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error reported by the storage backend.
+    pub fn new_with_index(mut storage: S, config: LogConfig, index: u32) -> Result<Self, S::Error> {
+        storage.create(file_name_str(&log_file_name(index)))?;
+        Ok(Self {
+            storage,
+            config,
+            buffer: [0; BLOCK_SIZE],
+            buffered: 0,
+            file_index: index,
+            file_size: 0,
+            unflushed_records: 0,
+        })
+    }
+
+    /// Appends a record to the log.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error reported by the storage backend.
+    pub fn write_record(&mut self, record: &LogRecord) -> Result<(), S::Error> {
+        let encoded = record.encode();
+
+        if self.file_size > 0 && self.file_size + RECORD_SIZE as u32 > self.config.max_file_size {
+            self.rotate()?;
+        }
+
+        let mut remaining = &encoded[..];
+        while !remaining.is_empty() {
+            let n = remaining.len().min(BLOCK_SIZE - self.buffered);
+            self.buffer[self.buffered..self.buffered + n].copy_from_slice(&remaining[..n]);
+            self.buffered += n;
+            remaining = &remaining[n..];
+
+            if self.buffered == BLOCK_SIZE {
+                self.write_buffer()?;
+            }
+        }
+        self.file_size += RECORD_SIZE as u32;
+        self.unflushed_records = self.unflushed_records.saturating_add(1);
+
+        match self.config.flush_policy {
+            FlushPolicy::EveryRecord => self.flush(),
+            FlushPolicy::EveryNRecords(n) if self.unflushed_records >= n => self.flush(),
+            _ => Ok(()),
+        }
+    }
+
+    /// Writes all buffered records to storage and flushes the current file.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error reported by the storage backend.
+    pub fn flush(&mut self) -> Result<(), S::Error> {
+        self.write_buffer()?;
+        self.storage.flush()?;
+        self.unflushed_records = 0;
+        Ok(())
+    }
+
+    /// Closes the current file and continues logging in the next one.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error reported by the storage backend.
+    pub fn rotate(&mut self) -> Result<(), S::Error> {
+        self.flush()?;
+        self.storage.close()?;
+        self.file_index = self.file_index.wrapping_add(1);
+        self.file_size = 0;
+        self.storage
+            .create(file_name_str(&log_file_name(self.file_index)))
+    }
+
+    /// Returns the index of the file currently being written.
+    pub fn file_index(&self) -> u32 {
+        self.file_index
+    }
+
+    /// Returns the number of bytes logged to the current file, including buffered records.
+    pub fn file_size(&self) -> u32 {
+        self.file_size
+    }
+
+    /// Flushes and closes the current file, returning the storage backend.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error reported by the storage backend.
+    pub fn close(mut self) -> Result<S, S::Error> {
+        self.flush()?;
+        self.storage.close()?;
+        Ok(self.storage)
+    }
+
+    fn write_buffer(&mut self) -> Result<(), S::Error> {
+        if self.buffered > 0 {
+            self.storage.write(&self.buffer[..self.buffered])?;
+            self.buffered = 0;
+        }
+        Ok(())
+    }
+}
+
+fn file_name_str(name: &[u8; 12]) -> &str {
+    // Only ever contains ASCII letters, digits and '.'
+    core::str::from_utf8(name).unwrap_or("LOG00000.BIN")
+}
+
+/// Converts a binary log to CSV.
+///
+/// Writes a header line followed by one line per valid record with the
+/// columns `timestamp_us, frame_1, frame_2, status_1, status_2, error,
+/// pressure_pa, temperature_c`. Frames are hex encoded; pressure and
+/// temperature are empty for failed reads. Corrupt records are skipped.
+///
+/// # Arguments
+///
+/// * `log` - Contents of a log file
+/// * `transfer` - Transfer function used to convert the raw counts
+/// * `out` - Writer receiving the CSV
+///
+/// # Returns
+///
+/// Number of records written
+///
+/// # Errors
+///
+/// Returns any error reported by the writer.
+#[cfg(feature = "std")]
+pub fn write_csv<T, W>(log: &[u8], transfer: &T, out: &mut W) -> std::io::Result<usize>
+where
+    T: TransferFunction,
+    W: std::io::Write,
+{
+    writeln!(
+        out,
+        "timestamp_us,frame_1,frame_2,status_1,status_2,error,pressure_pa,temperature_c"
+    )?;
+
+    let mut count = 0;
+    for record in decode_records(log).flatten() {
+        let (status_1, status_2) = record.statuses();
+        write!(
+            out,
+            "{},{},{},{:?},{:?},",
+            record.timestamp_us,
+            Hex(&record.frames[0]),
+            Hex(&record.frames[1]),
+            status_1,
+            status_2
+        )?;
+        match record.error {
+            Some(error) => writeln!(out, "{:?},,", error)?,
+            None => {
+                let (pressure_pa, temp_c) = record.reading(transfer).unwrap_or_default();
+                writeln!(out, ",{},{}", pressure_pa, temp_c)?
+            }
+        }
+        count += 1;
+    }
+    Ok(count)
+}
+
+/// Converts a binary log to JSON Lines (one JSON object per record).
+///
+/// Uses the same fields as [`write_csv`]; `error`, `pressure_pa` and
+/// `temperature_c` are `null` where not applicable. Corrupt records are
+/// skipped.
+///
+/// # Arguments
+///
+/// * `log` - Contents of a log file
+/// * `transfer` - Transfer function used to convert the raw counts
+/// * `out` - Writer receiving the JSON Lines
+///
+/// # Returns
+///
+/// Number of records written
+///
+/// # Errors
+///
+/// Returns any error reported by the writer.
+#[cfg(feature = "std")]
+pub fn write_json<T, W>(log: &[u8], transfer: &T, out: &mut W) -> std::io::Result<usize>
+where
+    T: TransferFunction,
+    W: std::io::Write,
+{
+    let mut count = 0;
+    for record in decode_records(log).flatten() {
+        let (status_1, status_2) = record.statuses();
+        write!(
+            out,
+            "{{\"timestamp_us\":{},\"frame_1\":\"{}\",\"frame_2\":\"{}\",\
+             \"status_1\":\"{:?}\",\"status_2\":\"{:?}\",",
+            record.timestamp_us,
+            Hex(&record.frames[0]),
+            Hex(&record.frames[1]),
+            status_1,
+            status_2
+        )?;
+        match record.error {
+            Some(error) => writeln!(
+                out,
+                "\"error\":\"{:?}\",\"pressure_pa\":null,\"temperature_c\":null}}",
+                error
+            )?,
+            None => {
+                let (pressure_pa, temp_c) = record.reading(transfer).unwrap_or_default();
+                writeln!(
+                    out,
+                    "\"error\":null,\"pressure_pa\":{},\"temperature_c\":{}}}",
+                    pressure_pa, temp_c
+                )?
+            }
+        }
+        count += 1;
+    }
+    Ok(count)
+}
+
+/// Formats bytes as lowercase hex without separators.
+#[cfg(feature = "std")]
+struct Hex<'a>(&'a [u8]);
+
+#[cfg(feature = "std")]
+impl core::fmt::Display for Hex<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for byte in self.0 {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::RangeStatus;
+    use crate::transfer::{Ms4525doTransfer, OutputType};
+
+    extern crate std;
+    use std::string::String;
+    use std::vec::Vec;
+
+    const FRESH: [u8; 4] = [0x20, 0x00, 0x5F, 0xE0];
+    const STALE: [u8; 4] = [0xA0, 0x00, 0x5F, 0xE0];
+    const TRANSFER: Ms4525doTransfer = Ms4525doTransfer {
+        output_type: OutputType::A,
+    };
+
+    /// In-memory storage that keeps every created file.
+    #[derive(Default)]
+    struct MemoryStorage {
+        files: Vec<(String, Vec<u8>)>,
+        writes: usize,
+        flushes: usize,
+        open: bool,
+    }
+
+    impl LogStorage for MemoryStorage {
+        type Error = ();
+
+        fn create(&mut self, name: &str) -> Result<(), ()> {
+            assert!(!self.open, "previous file not closed");
+            self.files.push((name.into(), Vec::new()));
+            self.open = true;
+            Ok(())
+        }
+
+        fn write(&mut self, data: &[u8]) -> Result<(), ()> {
+            assert!(self.open);
+            self.writes += 1;
+            self.files.last_mut().unwrap().1.extend_from_slice(data);
+            Ok(())
+        }
+
+        fn flush(&mut self) -> Result<(), ()> {
+            self.flushes += 1;
+            Ok(())
+        }
+
+        fn close(&mut self) -> Result<(), ()> {
+            self.open = false;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_record_round_trip() {
+        let records = [
+            LogRecord::from_frames(1, [FRESH, STALE], &TRANSFER),
+            LogRecord::from_frames(2, [STALE, STALE], &TRANSFER),
+            LogRecord::from_error(3, Ms4525doError::I2cError),
+        ];
+        assert_eq!(records[0].error, None);
+        assert_eq!(
+            records[1].error,
+            Some(Ms4525doError::InvalidStatus(Status::StaleData))
+        );
+
+        for record in records {
+            let encoded = record.encode();
+            assert_eq!(LogRecord::decode(&encoded), Ok((record, RECORD_SIZE)));
+        }
+    }
+
+    #[test]
+    fn test_record_matches_read_data() {
+        // Valid status progression, but above the Type A band
+        let near_full_scale = [[0x3A, 0x00, 0x5F, 0xE0], [0xBA, 0x00, 0x5F, 0xE0]];
+        let record = LogRecord::from_frames(1, near_full_scale, &TRANSFER);
+        assert_eq!(
+            record.error,
+            Some(Ms4525doError::DataOutOfRange(RangeStatus::SaturatedHigh))
+        );
+        assert_eq!(record.reading(&TRANSFER), None);
+        assert_eq!(
+            LogRecord::decode(&record.encode()),
+            Ok((record, RECORD_SIZE))
+        );
+
+        // The same counts are inside the band of a Type B part
+        let type_b = Ms4525doTransfer::new(OutputType::B);
+        let record = LogRecord::from_frames(1, near_full_scale, &type_b);
+        assert_eq!(record.error, None);
+        let (pressure_pa, _) = record.reading(&type_b).unwrap();
+        assert_eq!(pressure_pa, type_b.pressure_pa(0x3A00));
+    }
+
+    #[test]
+    fn test_decode_rejects_corruption() {
+        let mut encoded = LogRecord::from_frames(1, [FRESH, STALE], &TRANSFER).encode();
+        encoded[10] ^= 0x01;
+        assert_eq!(LogRecord::decode(&encoded), Err(DecodeError::CrcMismatch));
+        assert_eq!(
+            LogRecord::decode(&encoded[..10]),
+            Err(DecodeError::Truncated)
+        );
+    }
+
+    #[test]
+    fn test_decode_accepts_longer_payload() {
+        // A future writer appending two bytes to the payload
+        let encoded = LogRecord::from_frames(7, [FRESH, STALE], &TRANSFER).encode();
+        let mut extended = Vec::from(&encoded[..RECORD_SIZE - 2]);
+        extended[3] += 2;
+        extended.extend_from_slice(&[0xAB, 0xCD]);
+        let crc = crc16(&extended);
+        extended.extend_from_slice(&crc.to_le_bytes());
+
+        let (record, len) = LogRecord::decode(&extended).unwrap();
+        assert_eq!(record.timestamp_us, 7);
+        assert_eq!(len, RECORD_SIZE + 2);
+    }
+
+    #[test]
+    fn test_decode_records_resyncs() {
+        let mut log = Vec::new();
+        log.extend_from_slice(&LogRecord::from_frames(1, [FRESH, STALE], &TRANSFER).encode());
+        log.extend_from_slice(&[0x00, 0x11, 0x22]);
+        log.extend_from_slice(&LogRecord::from_frames(2, [FRESH, STALE], &TRANSFER).encode());
+
+        let results: Vec<_> = decode_records(&log).collect();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[1], Err(DecodeError::BadSync));
+        assert_eq!(results[2].unwrap().timestamp_us, 2);
+    }
+
+    #[test]
+    fn test_log_file_name() {
+        assert_eq!(&log_file_name(0), b"LOG00000.BIN");
+        assert_eq!(&log_file_name(123), b"LOG00123.BIN");
+    }
+
+    #[test]
+    fn test_writer_rotation() {
+        let config = LogConfig {
+            max_file_size: 3 * RECORD_SIZE as u32,
+            flush_policy: FlushPolicy::BlockFull,
+        };
+        let mut writer = BinLogWriter::new(MemoryStorage::default(), config).unwrap();
+        for i in 0..7 {
+            writer
+                .write_record(&LogRecord::from_frames(i, [FRESH, STALE], &TRANSFER))
+                .unwrap();
+        }
+        assert_eq!(writer.file_index(), 2);
+        let storage = writer.close().unwrap();
+
+        let names: Vec<_> = storage.files.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(names, ["LOG00000.BIN", "LOG00001.BIN", "LOG00002.BIN"]);
+        assert_eq!(storage.files[0].1.len(), 3 * RECORD_SIZE);
+        assert_eq!(storage.files[2].1.len(), RECORD_SIZE);
+
+        let timestamps: Vec<_> = storage
+            .files
+            .iter()
+            .flat_map(|(_, data)| decode_records(data))
+            .map(|r| r.unwrap().timestamp_us)
+            .collect();
+        assert_eq!(timestamps, [0, 1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn test_writer_flush_policy() {
+        let config = LogConfig {
+            max_file_size: u32::MAX,
+            flush_policy: FlushPolicy::EveryNRecords(4),
+        };
+        let mut writer = BinLogWriter::new(MemoryStorage::default(), config).unwrap();
+        for i in 0..10 {
+            writer
+                .write_record(&LogRecord::from_frames(i, [FRESH, STALE], &TRANSFER))
+                .unwrap();
+        }
+        assert_eq!(writer.storage.flushes, 2);
+        assert_eq!(writer.storage.files[0].1.len(), 8 * RECORD_SIZE);
+
+        // Block-full writes only happen in 512-byte chunks
+        let config = LogConfig {
+            max_file_size: u32::MAX,
+            flush_policy: FlushPolicy::BlockFull,
+        };
+        let mut writer = BinLogWriter::new(MemoryStorage::default(), config).unwrap();
+        for i in 0..30 {
+            writer
+                .write_record(&LogRecord::from_frames(i, [FRESH, STALE], &TRANSFER))
+                .unwrap();
+        }
+        assert_eq!(writer.storage.writes, 1);
+        assert_eq!(writer.storage.files[0].1.len(), BLOCK_SIZE);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_write_csv_and_json() {
+        let mut log = Vec::new();
+        log.extend_from_slice(&LogRecord::from_frames(1, [FRESH, STALE], &TRANSFER).encode());
+        log.extend_from_slice(&LogRecord::from_error(2, Ms4525doError::FaultDetected).encode());
+
+        let mut csv = Vec::new();
+        assert_eq!(write_csv(&log, &TRANSFER, &mut csv).unwrap(), 2);
+        let csv = String::from_utf8(csv).unwrap();
+        let lines: Vec<_> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[1].starts_with("1,20005fe0,a0005fe0,NormalOperation,StaleData,,"));
+        assert!(lines[2].ends_with("FaultDetected,,"));
+
+        let mut json = Vec::new();
+        assert_eq!(write_json(&log, &TRANSFER, &mut json).unwrap(), 2);
+        let json = String::from_utf8(json).unwrap();
+        assert!(json
+            .lines()
+            .nth(1)
+            .unwrap()
+            .contains("\"error\":\"FaultDetected\",\"pressure_pa\":null"));
+    }
+}
@@ -0,0 +1,285 @@
+//! [`LogStorage`] on an `embedded-sdmmc` FAT volume.
+
+use super::LogStorage;
+use embedded_sdmmc::{BlockDevice, Error, Mode, RawDirectory, RawFile, TimeSource, VolumeManager};
+
+/// Log storage writing files into a directory of an `embedded-sdmmc` FAT volume.
+///
+/// Works with any `embedded-sdmmc` [`BlockDevice`], e.g. an `SdCard` on an SPI
+/// bus. Each log file is created in `ReadWriteCreateOrTruncate` mode in the
+/// given directory, which must stay open while the storage is in use.
+///
+/// # Example
+///
+/// ```ignore
+/// use embedded_sdmmc::{SdCard, VolumeIdx, VolumeManager};
+/// use ms4525do::binlog::{BinLogWriter, LogConfig, SdmmcStorage};
+///
+/// let volume_mgr = VolumeManager::new(SdCard::new(spi, delay), time_source);
+/// let volume = volume_mgr.open_raw_volume(VolumeIdx(0))?;
+/// let root = volume_mgr.open_root_dir(volume)?;
+///
+/// let mut log = BinLogWriter::new(SdmmcStorage::new(&volume_mgr, root), LogConfig::default())?;
+/// ```
+pub struct SdmmcStorage<
+    'a,
+    D,
+    T,
+    const MAX_DIRS: usize,
+    const MAX_FILES: usize,
+    const MAX_VOLUMES: usize,
+> where
+    D: BlockDevice,
+    T: TimeSource,
+{
+    volume_mgr: &'a VolumeManager<D, T, MAX_DIRS, MAX_FILES, MAX_VOLUMES>,
+    directory: RawDirectory,
+    file: Option<RawFile>,
+}
+
+impl<'a, D, T, const MAX_DIRS: usize, const MAX_FILES: usize, const MAX_VOLUMES: usize>
+    SdmmcStorage<'a, D, T, MAX_DIRS, MAX_FILES, MAX_VOLUMES>
+where
+    D: BlockDevice,
+    T: TimeSource,
+{
+    /// Creates a storage writing log files into an open directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `volume_mgr` - Volume manager of the SD card
+    /// * `directory` - Open directory to create the log files in
+    pub fn new(
+        volume_mgr: &'a VolumeManager<D, T, MAX_DIRS, MAX_FILES, MAX_VOLUMES>,
+        directory: RawDirectory,
+    ) -> Self {
+        Self {
+            volume_mgr,
+            directory,
+            file: None,
+        }
+    }
+
+    /// Returns the directory the log files are created in.
+    pub fn directory(&self) -> RawDirectory {
+        self.directory
+    }
+
+    fn file(&self) -> Result<RawFile, Error<D::Error>> {
+        self.file.ok_or(Error::BadHandle)
+    }
+}
+
+impl<D, T, const MAX_DIRS: usize, const MAX_FILES: usize, const MAX_VOLUMES: usize> LogStorage
+    for SdmmcStorage<'_, D, T, MAX_DIRS, MAX_FILES, MAX_VOLUMES>
+where
+    D: BlockDevice,
+    T: TimeSource,
+{
+    type Error = Error<D::Error>;
+
+    fn create(&mut self, name: &str) -> Result<(), Self::Error> {
+        self.close()?;
+        let file = self.volume_mgr.open_file_in_dir(
+            self.directory,
+            name,
+            Mode::ReadWriteCreateOrTruncate,
+        )?;
+        self.file = Some(file);
+        Ok(())
+    }
+
+    fn write(&mut self, data: &[u8]) -> Result<(), Self::Error> {
+        self.volume_mgr.write(self.file()?, data)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.volume_mgr.flush_file(self.file()?)
+    }
+
+    fn close(&mut self) -> Result<(), Self::Error> {
+        match self.file.take() {
+            Some(file) => self.volume_mgr.close_file(file),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::binlog::{
+        decode_records, BinLogWriter, FlushPolicy, LogConfig, LogRecord, RECORD_SIZE,
+    };
+    use crate::transfer::Ms4525doTransfer;
+    use core::cell::RefCell;
+    use embedded_sdmmc::{Block, BlockCount, BlockIdx, Timestamp, VolumeIdx};
+
+    extern crate std;
+    use std::vec;
+    use std::vec::Vec;
+
+    /// Blocks of the FAT16 partition: boot sector, two FATs, root directory, data
+    const RESERVED_BLOCKS: u32 = 1;
+    const FAT_BLOCKS: u32 = 17;
+    const ROOT_DIR_BLOCKS: u32 = 32;
+    /// Just above the 4085 clusters embedded-sdmmc requires for FAT16
+    const CLUSTERS: u32 = 4200;
+    const PARTITION_BLOCKS: u32 = RESERVED_BLOCKS + 2 * FAT_BLOCKS + ROOT_DIR_BLOCKS + CLUSTERS;
+
+    /// SD card held in memory, formatted with one FAT16 partition.
+    struct MemoryBlockDevice {
+        blocks: RefCell<Vec<Block>>,
+    }
+
+    impl MemoryBlockDevice {
+        fn new() -> Self {
+            let mut blocks = vec![Block::new(); 1 + PARTITION_BLOCKS as usize];
+
+            // Master boot record with one FAT16 (LBA) partition from block 1
+            let mbr = &mut blocks[0].contents;
+            mbr[446 + 4] = 0x0E;
+            mbr[446 + 8..446 + 12].copy_from_slice(&1u32.to_le_bytes());
+            mbr[446 + 12..446 + 16].copy_from_slice(&PARTITION_BLOCKS.to_le_bytes());
+            mbr[510..512].copy_from_slice(&[0x55, 0xAA]);
+
+            // Boot sector: 512-byte blocks, one block per cluster
+            let bpb = &mut blocks[1].contents;
+            bpb[0..3].copy_from_slice(&[0xEB, 0x3C, 0x90]);
+            bpb[11..13].copy_from_slice(&512u16.to_le_bytes());
+            bpb[13] = 1;
+            bpb[14..16].copy_from_slice(&(RESERVED_BLOCKS as u16).to_le_bytes());
+            bpb[16] = 2;
+            bpb[17..19].copy_from_slice(&(ROOT_DIR_BLOCKS as u16 * 16).to_le_bytes());
+            bpb[19..21].copy_from_slice(&(PARTITION_BLOCKS as u16).to_le_bytes());
+            bpb[21] = 0xF8;
+            bpb[22..24].copy_from_slice(&(FAT_BLOCKS as u16).to_le_bytes());
+            bpb[43..54].copy_from_slice(b"AIRSPEED   ");
+            bpb[510..512].copy_from_slice(&[0x55, 0xAA]);
+
+            // Media descriptor and end-of-chain marker in both FATs
+            for fat in 0..2 {
+                let first = 1 + RESERVED_BLOCKS + fat * FAT_BLOCKS;
+                blocks[first as usize].contents[0..4].copy_from_slice(&[0xF8, 0xFF, 0xFF, 0xFF]);
+            }
+
+            Self {
+                blocks: RefCell::new(blocks),
+            }
+        }
+    }
+
+    impl BlockDevice for MemoryBlockDevice {
+        type Error = ();
+
+        fn read(&self, blocks: &mut [Block], start_block_idx: BlockIdx) -> Result<(), ()> {
+            let start = start_block_idx.0 as usize;
+            let stored = self.blocks.borrow();
+            blocks.clone_from_slice(stored.get(start..start + blocks.len()).ok_or(())?);
+            Ok(())
+        }
+
+        fn write(&self, blocks: &[Block], start_block_idx: BlockIdx) -> Result<(), ()> {
+            let start = start_block_idx.0 as usize;
+            let mut stored = self.blocks.borrow_mut();
+            stored
+                .get_mut(start..start + blocks.len())
+                .ok_or(())?
+                .clone_from_slice(blocks);
+            Ok(())
+        }
+
+        fn num_blocks(&self) -> Result<BlockCount, ()> {
+            Ok(BlockCount(self.blocks.borrow().len() as u32))
+        }
+    }
+
+    struct FixedTime;
+
+    impl TimeSource for FixedTime {
+        fn get_timestamp(&self) -> Timestamp {
+            Timestamp {
+                year_since_1970: 56,
+                zero_indexed_month: 0,
+                zero_indexed_day: 0,
+                hours: 0,
+                minutes: 0,
+                seconds: 0,
+            }
+        }
+    }
+
+    const FRAMES: [[u8; 4]; 2] = [[0x20, 0x00, 0x5F, 0xE0], [0xA0, 0x00, 0x5F, 0xE0]];
+
+    /// Reads a whole file from the volume
+    fn read_file<D: BlockDevice, T: TimeSource>(
+        volume_mgr: &VolumeManager<D, T>,
+        directory: RawDirectory,
+        name: &str,
+    ) -> Vec<u8> {
+        let file = volume_mgr
+            .open_file_in_dir(directory, name, Mode::ReadOnly)
+            .unwrap();
+        let mut contents = Vec::new();
+        let mut buf = [0u8; 100];
+        while !volume_mgr.file_eof(file).unwrap() {
+            let n = volume_mgr.read(file, &mut buf).unwrap();
+            contents.extend_from_slice(&buf[..n]);
+        }
+        volume_mgr.close_file(file).unwrap();
+        contents
+    }
+
+    #[test]
+    fn test_writer_on_fat_volume() {
+        let volume_mgr = VolumeManager::new(MemoryBlockDevice::new(), FixedTime);
+        let volume = volume_mgr.open_raw_volume(VolumeIdx(0)).unwrap();
+        let root = volume_mgr.open_root_dir(volume).unwrap();
+
+        let config = LogConfig {
+            max_file_size: 30 * RECORD_SIZE as u32,
+            flush_policy: FlushPolicy::EveryNRecords(8),
+        };
+        let mut writer = BinLogWriter::new(SdmmcStorage::new(&volume_mgr, root), config).unwrap();
+        for i in 0..50 {
+            writer
+                .write_record(&LogRecord::from_frames(
+                    i,
+                    FRAMES,
+                    &Ms4525doTransfer::default(),
+                ))
+                .unwrap();
+        }
+        assert_eq!(writer.file_index(), 1);
+        writer.close().unwrap();
+
+        let first = read_file(&volume_mgr, root, "LOG00000.BIN");
+        let second = read_file(&volume_mgr, root, "LOG00001.BIN");
+        assert_eq!(first.len(), 30 * RECORD_SIZE);
+        assert_eq!(second.len(), 20 * RECORD_SIZE);
+
+        let timestamps: Vec<_> = decode_records(&first)
+            .chain(decode_records(&second))
+            .map(|r| r.unwrap().timestamp_us)
+            .collect();
+        assert_eq!(timestamps, (0..50).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_create_truncates() {
+        let volume_mgr = VolumeManager::new(MemoryBlockDevice::new(), FixedTime);
+        let volume = volume_mgr.open_raw_volume(VolumeIdx(0)).unwrap();
+        let root = volume_mgr.open_root_dir(volume).unwrap();
+        let mut storage = SdmmcStorage::new(&volume_mgr, root);
+
+        assert!(matches!(storage.write(&[1, 2, 3]), Err(Error::BadHandle)));
+
+        storage.create("LOG00000.BIN").unwrap();
+        storage.write(&[1, 2, 3]).unwrap();
+        storage.create("LOG00000.BIN").unwrap();
+        storage.write(&[4]).unwrap();
+        storage.close().unwrap();
+
+        assert_eq!(read_file(&volume_mgr, root, "LOG00000.BIN"), [4]);
+    }
+}
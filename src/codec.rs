@@ -0,0 +1,532 @@
+//! Versioned `postcard` and CBOR encoding of readings and configuration.
+//!
+//! Every encoded frame starts with a one-byte format version followed by the
+//! payload, so that receivers and log readers can tell which layout a frame
+//! was written with. All helpers work on caller-provided buffers and never
+//! allocate.
+//!
+//! Frames written with the current or an older format version are decoded;
+//! only frames from a newer version are rejected.
+//!
+//! - **postcard** (feature `postcard`): compact, schema-less encoding of any
+//!   type implementing `serde::Serialize`. Because postcard is not
+//!   self-describing, [`POSTCARD_VERSION`] is bumped whenever a field is
+//!   added. [`from_postcard`] decodes every accepted version with the current
+//!   layout of the type; for a type whose layout changed, use
+//!   [`from_postcard_versioned`] to decode older frames with the old layout.
+//! - **CBOR** (feature `minicbor`): self-describing encoding of [`Reading`],
+//!   [`Status`] and [`Ms4525doError`]. Readings are encoded as maps with
+//!   integer keys; unknown keys are skipped when decoding, so frames stay
+//!   decodable in both directions as fields are added. [`CBOR_VERSION`] is
+//!   only bumped for incompatible changes.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use ms4525do::codec::{from_postcard, to_postcard};
+//! use ms4525do::Reading;
+//!
+//! let reading = Reading::from(sensor.read_data(&mut delay)?);
+//!
+//! let mut buf = [0u8; 16];
+//! let len = to_postcard(&reading, &mut buf)?;
+//! radio.send(&buf[..len]);
+//!
+//! let decoded: Reading = from_postcard(&buf[..len])?;
+//! ```
+
+#[cfg(feature = "minicbor")]
+use crate::common::{Reading, Status};
+#[cfg(feature = "minicbor")]
+use crate::Ms4525doError;
+
+#[cfg(feature = "defmt")]
+use defmt::Format;
+
+/// Current format version of postcard frames
+#[cfg(feature = "postcard")]
+pub const POSTCARD_VERSION: u8 = 1;
+
+/// Current format version of CBOR frames
+#[cfg(feature = "minicbor")]
+pub const CBOR_VERSION: u8 = 1;
+
+/// Errors that can occur while encoding or decoding a frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+pub enum CodecError {
+    /// The output buffer is too small for the encoded frame.
+    BufferTooSmall,
+    /// The frame was written with a newer or unknown format version.
+    UnsupportedVersion(u8),
+    /// The frame is empty, truncated or does not match the expected type.
+    Malformed,
+}
+
+impl core::fmt::Display for CodecError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            CodecError::BufferTooSmall => write!(f, "Output buffer too small"),
+            CodecError::UnsupportedVersion(version) => {
+                write!(f, "Unsupported frame version: {}", version)
+            }
+            CodecError::Malformed => write!(f, "Malformed frame"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CodecError {}
+
+/// Returns the format version of an encoded frame.
+///
+/// # Errors
+///
+/// * `CodecError::Malformed` - The frame is empty
+pub fn frame_version(frame: &[u8]) -> Result<u8, CodecError> {
+    frame.first().copied().ok_or(CodecError::Malformed)
+}
+
+/// Splits a frame into its version and payload, accepting versions from 1 up
+/// to `current_version`.
+fn payload(frame: &[u8], current_version: u8) -> Result<(u8, &[u8]), CodecError> {
+    match frame_version(frame)? {
+        version @ 1.. if version <= current_version => Ok((version, &frame[1..])),
+        version => Err(CodecError::UnsupportedVersion(version)),
+    }
+}
+
+/// Encodes a value as a versioned postcard frame.
+///
+/// # Arguments
+///
+/// * `value` - Value to encode
+/// * `buf` - Output buffer
+///
+/// # Returns
+///
+/// Number of bytes written to `buf`
+///
+/// # Errors
+///
+/// * `CodecError::BufferTooSmall` - The frame does not fit into `buf`
+#[cfg(feature = "postcard")]
+pub fn to_postcard<T>(value: &T, buf: &mut [u8]) -> Result<usize, CodecError>
+where
+    T: serde::Serialize + ?Sized,
+{
+    let (version, rest) = buf.split_first_mut().ok_or(CodecError::BufferTooSmall)?;
+    *version = POSTCARD_VERSION;
+    let used = postcard::to_slice(value, rest).map_err(|e| match e {
+        postcard::Error::SerializeBufferFull => CodecError::BufferTooSmall,
+        _ => CodecError::Malformed,
+    })?;
+    Ok(1 + used.len())
+}
+
+/// Decodes a value from a versioned postcard frame.
+///
+/// Frames up to [`POSTCARD_VERSION`] are decoded with the current layout of
+/// `T`, which is only correct if `T` did not change since the frame was
+/// written. Use [`from_postcard_versioned`] otherwise.
+///
+/// # Errors
+///
+/// * `CodecError::UnsupportedVersion` - The frame was written with a newer version
+/// * `CodecError::Malformed` - The payload does not decode as `T`
+#[cfg(feature = "postcard")]
+pub fn from_postcard<'a, T>(frame: &'a [u8]) -> Result<T, CodecError>
+where
+    T: serde::Deserialize<'a>,
+{
+    from_postcard_versioned(frame, |_, payload| from_postcard_payload(payload))
+}
+
+/// Decodes a value from a versioned postcard frame, with a decoder for frames
+/// written by older versions.
+///
+/// Frames of the current [`POSTCARD_VERSION`] are decoded as `T`. Older frames
+/// are passed to `decode_older` with their version and payload, which
+/// typically decodes the old layout with [`from_postcard_payload`] and converts
+/// it to `T`.
+///
+/// # Arguments
+///
+/// * `frame` - Encoded frame
+/// * `decode_older` - Decoder for the payload of frames older than [`POSTCARD_VERSION`]
+///
+/// # Errors
+///
+/// * `CodecError::UnsupportedVersion` - The frame was written with a newer version
+/// * `CodecError::Malformed` - The payload does not decode as `T`
+///
+/// # Example
+///
+/// ```ignore
+/// let reading: ReadingV2 = from_postcard_versioned(frame, |version, payload| match version {
+///     1 => from_postcard_payload::<ReadingV1>(payload).map(ReadingV2::from),
+///     _ => Err(CodecError::UnsupportedVersion(version)),
+/// })?;
+/// ```
+#[cfg(feature = "postcard")]
+pub fn from_postcard_versioned<'a, T, F>(frame: &'a [u8], decode_older: F) -> Result<T, CodecError>
+where
+    T: serde::Deserialize<'a>,
+    F: FnOnce(u8, &'a [u8]) -> Result<T, CodecError>,
+{
+    decode_postcard(frame, POSTCARD_VERSION, decode_older)
+}
+
+/// Decodes an unversioned postcard payload, e.g. in a decoder passed to
+/// [`from_postcard_versioned`].
+///
+/// # Errors
+///
+/// * `CodecError::Malformed` - The payload does not decode as `T`
+#[cfg(feature = "postcard")]
+pub fn from_postcard_payload<'a, T>(payload: &'a [u8]) -> Result<T, CodecError>
+where
+    T: serde::Deserialize<'a>,
+{
+    postcard::from_bytes(payload).map_err(|_| CodecError::Malformed)
+}
+
+/// Dispatches a postcard frame on its version, with `current_version` as the
+/// newest accepted version.
+#[cfg(feature = "postcard")]
+fn decode_postcard<'a, T, F>(
+    frame: &'a [u8],
+    current_version: u8,
+    decode_older: F,
+) -> Result<T, CodecError>
+where
+    T: serde::Deserialize<'a>,
+    F: FnOnce(u8, &'a [u8]) -> Result<T, CodecError>,
+{
+    match payload(frame, current_version)? {
+        (version, payload) if version == current_version => from_postcard_payload(payload),
+        (version, payload) => decode_older(version, payload),
+    }
+}
+
+/// Encodes a value as a versioned CBOR frame.
+///
+/// # Arguments
+///
+/// * `value` - Value to encode
+/// * `buf` - Output buffer
+///
+/// # Returns
+///
+/// Number of bytes written to `buf`
+///
+/// # Errors
+///
+/// * `CodecError::BufferTooSmall` - The frame does not fit into `buf`
+#[cfg(feature = "minicbor")]
+pub fn to_cbor<T>(value: &T, buf: &mut [u8]) -> Result<usize, CodecError>
+where
+    T: minicbor::Encode<()>,
+{
+    let (version, rest) = buf.split_first_mut().ok_or(CodecError::BufferTooSmall)?;
+    *version = CBOR_VERSION;
+    let mut cursor = minicbor::encode::write::Cursor::new(rest);
+    minicbor::encode(value, &mut cursor).map_err(|_| CodecError::BufferTooSmall)?;
+    Ok(1 + cursor.position())
+}
+
+/// Decodes a value from a versioned CBOR frame.
+///
+/// Frames up to [`CBOR_VERSION`] are accepted. Fields added since a frame was
+/// written are missing from its map, so `Decode` implementations must treat
+/// them as optional.
+///
+/// # Errors
+///
+/// * `CodecError::UnsupportedVersion` - The frame was written with a newer version
+/// * `CodecError::Malformed` - The payload does not decode as `T`
+#[cfg(feature = "minicbor")]
+pub fn from_cbor<'b, T>(frame: &'b [u8]) -> Result<T, CodecError>
+where
+    T: minicbor::Decode<'b, ()>,
+{
+    decode_cbor(frame, CBOR_VERSION)
+}
+
+/// Decodes a CBOR frame, with `current_version` as the newest accepted version.
+#[cfg(feature = "minicbor")]
+fn decode_cbor<'b, T>(frame: &'b [u8], current_version: u8) -> Result<T, CodecError>
+where
+    T: minicbor::Decode<'b, ()>,
+{
+    let (_, payload) = payload(frame, current_version)?;
+    minicbor::decode(payload).map_err(|_| CodecError::Malformed)
+}
+
+#[cfg(feature = "minicbor")]
+mod cbor {
+    use super::*;
+    use minicbor::decode::{self, Decoder};
+    use minicbor::encode::{self, Encoder, Write};
+    use minicbor::{Decode, Encode};
+
+    /// Map keys of an encoded [`Reading`]
+    const KEY_DIFFERENTIAL_PRESSURE_PA: u32 = 0;
+    const KEY_TEMPERATURE_C: u32 = 1;
+
+    impl<C> Encode<C> for Reading {
+        fn encode<W: Write>(
+            &self,
+            e: &mut Encoder<W>,
+            _ctx: &mut C,
+        ) -> Result<(), encode::Error<W::Error>> {
+            e.map(2)?
+                .u32(KEY_DIFFERENTIAL_PRESSURE_PA)?
+                .f32(self.differential_pressure_pa)?
+                .u32(KEY_TEMPERATURE_C)?
+                .f32(self.temperature_c)?
+                .ok()
+        }
+    }
+
+    impl<'b, C> Decode<'b, C> for Reading {
+        fn decode(d: &mut Decoder<'b>, _ctx: &mut C) -> Result<Self, decode::Error> {
+            let mut differential_pressure_pa = None;
+            let mut temperature_c = None;
+
+            let mut remaining = d.map()?;
+            loop {
+                match remaining {
+                    Some(0) => break,
+                    Some(n) => remaining = Some(n - 1),
+                    None if d.datatype()? == minicbor::data::Type::Break => {
+                        // Consume the break byte ending an indefinite-length map
+                        d.set_position(d.position() + 1);
+                        break;
+                    }
+                    None => {}
+                }
+
+                match d.u32()? {
+                    KEY_DIFFERENTIAL_PRESSURE_PA => differential_pressure_pa = Some(d.f32()?),
+                    KEY_TEMPERATURE_C => temperature_c = Some(d.f32()?),
+                    _ => d.skip()?,
+                }
+            }
+
+            Ok(Reading {
+                differential_pressure_pa: differential_pressure_pa
+                    .ok_or_else(|| decode::Error::missing_value(KEY_DIFFERENTIAL_PRESSURE_PA))?,
+                temperature_c: temperature_c
+                    .ok_or_else(|| decode::Error::missing_value(KEY_TEMPERATURE_C))?,
+            })
+        }
+    }
+
+    impl<C> Encode<C> for Status {
+        fn encode<W: Write>(
+            &self,
+            e: &mut Encoder<W>,
+            _ctx: &mut C,
+        ) -> Result<(), encode::Error<W::Error>> {
+            e.u8(*self as u8)?.ok()
+        }
+    }
+
+    impl<'b, C> Decode<'b, C> for Status {
+        fn decode(d: &mut Decoder<'b>, _ctx: &mut C) -> Result<Self, decode::Error> {
+            match d.u8()? {
+                value @ 0..=3 => Ok(Status::from(value)),
+                _ => Err(decode::Error::message("invalid status")),
+            }
+        }
+    }
+
+    impl<C> Encode<C> for Ms4525doError {
+        fn encode<W: Write>(
+            &self,
+            e: &mut Encoder<W>,
+            _ctx: &mut C,
+        ) -> Result<(), encode::Error<W::Error>> {
+            e.u8(self.code())?.ok()
+        }
+    }
+
+    impl<'b, C> Decode<'b, C> for Ms4525doError {
+        fn decode(d: &mut Decoder<'b>, _ctx: &mut C) -> Result<Self, decode::Error> {
+            Ms4525doError::from_code(d.u8()?).ok_or_else(|| decode::Error::message("invalid error"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Ms4525doError, Reading, Status};
+
+    const READING: Reading = Reading {
+        differential_pressure_pa: 52.5,
+        temperature_c: -12.25,
+    };
+
+    #[test]
+    fn test_frame_version() {
+        assert_eq!(frame_version(&[]), Err(CodecError::Malformed));
+        assert_eq!(frame_version(&[7, 0]), Ok(7));
+
+        assert_eq!(payload(&[1, 9], 2), Ok((1, &[9][..])));
+        assert_eq!(payload(&[2, 9], 2), Ok((2, &[9][..])));
+        assert_eq!(payload(&[3, 9], 2), Err(CodecError::UnsupportedVersion(3)));
+        assert_eq!(payload(&[0, 9], 2), Err(CodecError::UnsupportedVersion(0)));
+    }
+
+    #[cfg(feature = "postcard")]
+    #[test]
+    fn test_postcard_round_trip() {
+        let mut buf = [0u8; 16];
+
+        let len = to_postcard(&READING, &mut buf).unwrap();
+        assert_eq!(buf[0], POSTCARD_VERSION);
+        assert_eq!(from_postcard::<Reading>(&buf[..len]), Ok(READING));
+
+        let error = Ms4525doError::InvalidStatus(Status::StaleData);
+        let len = to_postcard(&error, &mut buf).unwrap();
+        assert_eq!(from_postcard::<Ms4525doError>(&buf[..len]), Ok(error));
+
+        assert_eq!(
+            to_postcard(&READING, &mut buf[..4]),
+            Err(CodecError::BufferTooSmall)
+        );
+
+        buf[0] = POSTCARD_VERSION + 1;
+        assert_eq!(
+            from_postcard::<Reading>(&buf[..len]),
+            Err(CodecError::UnsupportedVersion(POSTCARD_VERSION + 1))
+        );
+    }
+
+    #[cfg(feature = "postcard")]
+    #[test]
+    fn test_postcard_older_version() {
+        use serde::{Deserialize, Serialize};
+
+        /// Layout written by version 1
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct ConfigV1 {
+            samples: u16,
+        }
+
+        /// Layout after a field was added in version 2
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct ConfigV2 {
+            samples: u16,
+            interval_ms: u32,
+        }
+
+        fn decode_older(version: u8, payload: &[u8]) -> Result<ConfigV2, CodecError> {
+            match version {
+                1 => from_postcard_payload::<ConfigV1>(payload).map(|v1| ConfigV2 {
+                    samples: v1.samples,
+                    interval_ms: 5,
+                }),
+                _ => Err(CodecError::UnsupportedVersion(version)),
+            }
+        }
+
+        let mut buf = [0u8; 16];
+        let len = to_postcard(&ConfigV1 { samples: 20 }, &mut buf).unwrap();
+        assert_eq!(buf[0], 1);
+        assert_eq!(
+            decode_postcard(&buf[..len], 2, decode_older),
+            Ok(ConfigV2 {
+                samples: 20,
+                interval_ms: 5
+            })
+        );
+
+        let config = ConfigV2 {
+            samples: 10,
+            interval_ms: 2,
+        };
+        let len = to_postcard(&config, &mut buf).unwrap();
+        buf[0] = 2;
+        assert_eq!(decode_postcard(&buf[..len], 2, decode_older), Ok(config));
+
+        buf[0] = 3;
+        assert_eq!(
+            decode_postcard(&buf[..len], 2, decode_older),
+            Err(CodecError::UnsupportedVersion(3))
+        );
+    }
+
+    #[cfg(feature = "minicbor")]
+    #[test]
+    fn test_cbor_round_trip() {
+        let mut buf = [0u8; 32];
+
+        let len = to_cbor(&READING, &mut buf).unwrap();
+        assert_eq!(buf[0], CBOR_VERSION);
+        assert_eq!(from_cbor::<Reading>(&buf[..len]), Ok(READING));
+
+        for error in [
+            Ms4525doError::I2cError,
+            Ms4525doError::InvalidStatus(Status::Reserved),
+            Ms4525doError::StaleDataMismatch,
+            Ms4525doError::SensorStuck,
+        ] {
+            let len = to_cbor(&error, &mut buf).unwrap();
+            assert_eq!(from_cbor::<Ms4525doError>(&buf[..len]), Ok(error));
+        }
+
+        let len = to_cbor(&Status::FaultDetected, &mut buf).unwrap();
+        assert_eq!(from_cbor::<Status>(&buf[..len]), Ok(Status::FaultDetected));
+
+        assert_eq!(
+            to_cbor(&READING, &mut buf[..4]),
+            Err(CodecError::BufferTooSmall)
+        );
+    }
+
+    #[cfg(feature = "minicbor")]
+    #[test]
+    fn test_cbor_skips_unknown_fields() {
+        // A reading written by a newer version with an extra field (key 7)
+        let mut buf = [0u8; 32];
+        buf[0] = CBOR_VERSION;
+        let mut cursor = minicbor::encode::write::Cursor::new(&mut buf[1..]);
+        minicbor::Encoder::new(&mut cursor)
+            .map(3)
+            .unwrap()
+            .u32(1)
+            .unwrap()
+            .f32(READING.temperature_c)
+            .unwrap()
+            .u32(7)
+            .unwrap()
+            .str("extra")
+            .unwrap()
+            .u32(0)
+            .unwrap()
+            .f32(READING.differential_pressure_pa)
+            .unwrap();
+        let len = 1 + cursor.position();
+
+        assert_eq!(from_cbor::<Reading>(&buf[..len]), Ok(READING));
+    }
+
+    #[cfg(feature = "minicbor")]
+    #[test]
+    fn test_cbor_older_version() {
+        let mut buf = [0u8; 32];
+        let len = to_cbor(&READING, &mut buf).unwrap();
+
+        // A version 1 frame still decodes after the version was bumped
+        assert_eq!(decode_cbor::<Reading>(&buf[..len], 2), Ok(READING));
+
+        buf[0] = 3;
+        assert_eq!(
+            decode_cbor::<Reading>(&buf[..len], 2),
+            Err(CodecError::UnsupportedVersion(3))
+        );
+    }
+}
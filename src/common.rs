@@ -3,10 +3,18 @@
 //! This module contains data structures, constants, parsing functions, and
 //! calculations that are shared between the blocking and async implementations.
 
+use crate::atmosphere;
+use crate::units::{Celsius, MetersPerSecond, Pascals};
+use crate::Ms4525doError;
+
 #[cfg(feature = "defmt")]
-use defmt::Format;
+use defmt::{info, Format};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 /// 7-bit I2C address for MS4525DO sensor
+#[cfg(any(feature = "blocking", feature = "async"))]
 pub const MS4525DO_ADDR: u8 = 0x28;
 
 /// Size of data packet read from sensor (4 bytes)
@@ -16,6 +24,7 @@ pub const DATA_SIZE: usize = 4;
 pub const PSI_TO_PA: f32 = 6894.76;
 
 /// Measurement request command
+#[cfg(any(feature = "blocking", feature = "async"))]
 pub const READ_MR: u8 = 0x00;
 
 /// Mask for extracting bridge (pressure) data from first byte
@@ -29,6 +38,7 @@ pub const TEMPERATURE_MASK: u8 = 0b1110_0000;
 /// The status is encoded in the top 2 bits of the first data byte.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "defmt", derive(Format))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Status {
     /// Normal Operation - Valid data packet
     NormalOperation = 0b00,
@@ -52,6 +62,205 @@ impl From<u8> for Status {
     }
 }
 
+/// How the two data packets of the double-read validation are fetched.
+///
+/// By default ([`FetchMode::Transaction`]) both packets are fetched in a
+/// single `I2c::transaction`, so the bus is not released between them and no
+/// other traffic can land between the packets. [`FetchMode::SeparateReads`]
+/// is the fallback for HALs that cannot mix directions in one transaction:
+/// each packet is fetched with its own `I2c::read`, with a START and STOP
+/// condition around each read, and other traffic can land between them.
+///
+/// Both modes send the measurement request as a transaction of its own and
+/// release the bus during the conversion. [`FetchMode::Pipelined`], used by
+/// the `new_shared` constructors, also puts the measurement request in the
+/// fetch transaction: the request between the two packets starts the
+/// conversion that the next `read_data` fetches, as the PX4 driver does.
+/// After the first reading, every reading is one transaction and needs no
+/// conversion delay, but it was converted just after the previous fetch:
+/// its age is the interval between the calls, which should be longer than
+/// the conversion time. A reading that fails validation sends a new request
+/// on the next attempt.
+///
+/// # Transaction Timing
+///
+/// A read-only repeated start cannot be expressed with `embedded-hal`, which
+/// merges adjacent operations of the same direction into one 8-byte read. The
+/// reads are therefore separated by a measurement request: `Read`,
+/// `Write(READ_MR)`, `Read`, with a repeated start before each operation.
+/// The request starts a new conversion, and the second packet is only the
+/// stale copy of the first if the sensor acknowledges the second read before
+/// that conversion completes. The gap is a repeated start and the address
+/// byte, 10 SCL periods, against a conversion time of about 0.5 ms: the
+/// fetch needs an SCL clock above 20 kHz and a HAL that does not pause
+/// between the operations of a transaction. On a slower bus the second packet
+/// is fresh and every read fails with [`Ms4525doError::InvalidStatus`]; use
+/// [`FetchMode::SeparateReads`] there.
+///
+/// The conversion started by that request is never read: the next reading
+/// sends a measurement request of its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum FetchMode {
+    /// Both reads in one I2C transaction, separated by a measurement request (default)
+    #[default]
+    Transaction,
+    /// Two separate I2C reads, each with its own START and STOP condition
+    SeparateReads,
+    /// Both reads in one I2C transaction, whose measurement request is the one for the next reading
+    Pipelined,
+}
+
+/// Classification of bridge data against the valid output band.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum RangeStatus {
+    /// Within the valid output band
+    InRange = 0b00,
+    /// Below the output band (negative over-pressure: port 2, the static port, too high)
+    SaturatedLow = 0b01,
+    /// Above the output band (positive over-pressure: port 1, the total port, too high)
+    SaturatedHigh = 0b10,
+    /// All-zeros or all-ones output of a disconnected or broken bridge
+    Disconnected = 0b11,
+}
+
+impl From<u8> for RangeStatus {
+    fn from(value: u8) -> Self {
+        match value & 0b11 {
+            0b00 => RangeStatus::InRange,
+            0b01 => RangeStatus::SaturatedLow,
+            0b10 => RangeStatus::SaturatedHigh,
+            0b11 => RangeStatus::Disconnected,
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl RangeStatus {
+    /// Classifies 14-bit bridge data.
+    ///
+    /// # Arguments
+    ///
+    /// * `bridge_data` - 14-bit raw pressure value from sensor
+    /// * `output_band` - (minimum, maximum) valid output counts, see
+    ///   [`crate::transfer::TransferFunction::output_band`]
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ms4525do::RangeStatus;
+    /// use ms4525do::transfer::OutputType;
+    ///
+    /// let band = OutputType::A.count_range();
+    /// assert_eq!(RangeStatus::classify(8192, band), RangeStatus::InRange);
+    /// assert_eq!(RangeStatus::classify(15000, band), RangeStatus::SaturatedHigh);
+    /// assert_eq!(RangeStatus::classify(0x3FFF, band), RangeStatus::Disconnected);
+    /// ```
+    pub fn classify(bridge_data: u16, output_band: (u16, u16)) -> Self {
+        let (min, max) = output_band;
+        if bridge_data == 0x0000 || bridge_data == 0x3FFF {
+            RangeStatus::Disconnected
+        } else if bridge_data < min {
+            RangeStatus::SaturatedLow
+        } else if bridge_data > max {
+            RangeStatus::SaturatedHigh
+        } else {
+            RangeStatus::InRange
+        }
+    }
+}
+
+/// Checks bridge data against the valid output band.
+///
+/// # Errors
+///
+/// * `Ms4525doError::DataOutOfRange` - The data is saturated or shows a disconnected bridge
+pub fn check_range(bridge_data: u16, output_band: (u16, u16)) -> Result<(), Ms4525doError> {
+    match RangeStatus::classify(bridge_data, output_band) {
+        RangeStatus::InRange => Ok(()),
+        range => {
+            #[cfg(feature = "defmt")]
+            info!("Bridge data out of range: {} ({:?})", bridge_data, range);
+
+            #[cfg(all(not(feature = "defmt"), feature = "log"))]
+            log::info!("Bridge data out of range: {} ({:?})", bridge_data, range);
+
+            Err(Ms4525doError::DataOutOfRange(range))
+        }
+    }
+}
+
+/// A validated differential pressure and temperature measurement.
+///
+/// Converts to and from the `(differential_pressure_pa, temperature_c)` tuple
+/// returned by `read_data`.
+///
+/// Every driver uses the datasheet sign convention: the differential pressure
+/// is positive when port 1 (the total pressure port of a pitot) is at the
+/// higher pressure, so forward flight reads positive.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Reading {
+    /// Differential pressure in Pascals, positive when port 1 is at the higher pressure
+    pub differential_pressure_pa: f32,
+    /// Temperature in degrees Celsius
+    pub temperature_c: f32,
+}
+
+impl Reading {
+    /// Calculates the airspeed for this reading, see [`calculate_airspeed`].
+    pub fn airspeed(&self) -> f32 {
+        calculate_airspeed(self.differential_pressure_pa, self.temperature_c)
+    }
+
+    /// Calculates the signed airspeed for this reading, see [`calculate_airspeed_signed`].
+    pub fn airspeed_signed(&self) -> f32 {
+        calculate_airspeed_signed(self.differential_pressure_pa, self.temperature_c)
+    }
+
+    /// Returns the differential pressure as a typed value.
+    pub fn pressure(&self) -> Pascals {
+        Pascals(self.differential_pressure_pa)
+    }
+
+    /// Returns the temperature as a typed value.
+    pub fn temperature(&self) -> Celsius {
+        Celsius(self.temperature_c)
+    }
+
+    /// Calculates the true airspeed for this reading, see [`calculate_true_airspeed`].
+    ///
+    /// # Arguments
+    ///
+    /// * `static_pressure_pa` - Static (ambient) pressure in Pascals
+    pub fn true_airspeed(&self, static_pressure_pa: f32) -> f32 {
+        calculate_true_airspeed(
+            self.differential_pressure_pa,
+            self.temperature_c,
+            static_pressure_pa,
+        )
+    }
+}
+
+impl From<(f32, f32)> for Reading {
+    fn from((differential_pressure_pa, temperature_c): (f32, f32)) -> Self {
+        Self {
+            differential_pressure_pa,
+            temperature_c,
+        }
+    }
+}
+
+impl From<Reading> for (f32, f32) {
+    fn from(reading: Reading) -> Self {
+        (reading.differential_pressure_pa, reading.temperature_c)
+    }
+}
+
 /// Extracts the 14-bit bridge (pressure) data from a 4-byte sensor reading.
 ///
 /// The pressure data is stored in bits 0-5 of byte 0 (MSB) and all of byte 1 (LSB).
@@ -86,10 +295,85 @@ pub fn read_temperature(data: &[u8]) -> u16 {
     (((data[2] as u16) << 8) | ((data[3] & TEMPERATURE_MASK) as u16)) >> 5
 }
 
+/// Validates a pair of consecutive 4-byte sensor readings.
+///
+/// This is the validation half of the double-read strategy used by `read_data`:
+/// 1. Rejects readings where either packet reports a sensor fault
+/// 2. Validates status progression: NormalOperation → StaleData
+/// 3. Ensures pressure and temperature consistency between reads
+///
+/// # Arguments
+///
+/// * `data_1` - First 4-byte packet read after the measurement request
+/// * `data_2` - Second 4-byte packet read immediately afterwards
+///
+/// # Returns
+///
+/// * `Ok((u16, u16))` - Tuple of (bridge_data, temperature_counts)
+/// * `Err(Ms4525doError)` - Error if the packets fail validation
+///
+/// # Errors
+///
+/// * `Ms4525doError::FaultDetected` - Sensor reports fault status
+/// * `Ms4525doError::InvalidStatus` - Unexpected status code
+/// * `Ms4525doError::StaleDataMismatch` - Data inconsistency between reads
+pub fn validate_frames(data_1: &[u8], data_2: &[u8]) -> Result<(u16, u16), Ms4525doError> {
+    // Parse status from both reads
+    let status_1 = Status::from(data_1[0] >> 6);
+    let status_2 = Status::from(data_2[0] >> 6);
+
+    // Check for sensor fault
+    if status_1 == Status::FaultDetected || status_2 == Status::FaultDetected {
+        return Err(Ms4525doError::FaultDetected);
+    }
+
+    // Validate expected status progression: Normal → Stale
+    // This ensures we're getting fresh data followed by the same stale data
+    if status_1 != Status::NormalOperation || status_2 != Status::StaleData {
+        #[cfg(feature = "defmt")]
+        info!("Invalid status sequence: {:?} -> {:?}", status_1, status_2);
+
+        #[cfg(all(not(feature = "defmt"), feature = "log"))]
+        log::info!("Invalid status sequence: {:?} -> {:?}", status_1, status_2);
+
+        return Err(Ms4525doError::InvalidStatus(status_1));
+    }
+
+    // Extract pressure and temperature from both reads
+    let bridge_data_1 = extract_bridge_data(data_1);
+    let bridge_data_2 = extract_bridge_data(data_2);
+    let temperature_1 = read_temperature(data_1);
+    let temperature_2 = read_temperature(data_2);
+
+    // Validate data consistency between reads
+    if bridge_data_1 != bridge_data_2 || temperature_1 != temperature_2 {
+        #[cfg(feature = "defmt")]
+        info!(
+            "Data mismatch: pressure {} != {}, temp {} != {}",
+            bridge_data_1, bridge_data_2, temperature_1, temperature_2
+        );
+
+        #[cfg(all(not(feature = "defmt"), feature = "log"))]
+        log::info!(
+            "Data mismatch: pressure {} != {}, temp {} != {}",
+            bridge_data_1,
+            bridge_data_2,
+            temperature_1,
+            temperature_2
+        );
+
+        return Err(Ms4525doError::StaleDataMismatch);
+    }
+
+    Ok((bridge_data_1, temperature_1))
+}
+
 /// Converts raw bridge data to differential pressure in Pascals.
 ///
 /// Uses the transfer function specified in the MS4525DO datasheet for the
-/// ±1 PSI differential pressure range (001PD variant).
+/// ±1 PSI differential pressure range (001PD variant). The result is positive
+/// when port 1 is at the higher pressure: 1638 counts (10% of full scale) is
+/// -1 PSI and 14745 counts (90%) is +1 PSI.
 ///
 /// # Arguments
 ///
@@ -99,8 +383,8 @@ pub fn read_temperature(data: &[u8]) -> u16 {
 ///
 /// Differential pressure in Pascals
 pub fn calculate_pressure_differential_pa(bridge_data: u16) -> f32 {
-    // Transfer function: P = -((bridge / 16383 * 0.1 - 1) / 0.8 * 2) PSI
-    let diff_press_psi = -((bridge_data as f32 - 0.1 * 16383.0) * 2.0 / (0.8 * 16383.0) - 1.0);
+    // Transfer function: P = (bridge - 0.1 * 16383) * 2 / (0.8 * 16383) - 1 PSI
+    let diff_press_psi = (bridge_data as f32 - 0.1 * 16383.0) * 2.0 / (0.8 * 16383.0) - 1.0;
     diff_press_psi * PSI_TO_PA
 }
 
@@ -126,6 +410,10 @@ pub fn calculate_temperature_deg_c(temperature_counts: u16) -> f32 {
 /// where ρ (air density) is calculated using the ideal gas law assuming
 /// standard atmospheric pressure at sea level (101325 Pa).
 ///
+/// Away from sea level this under-reads true airspeed; use
+/// [`calculate_true_airspeed`] with a measured static pressure, or
+/// [`calculate_airspeed_with_density`], instead.
+///
 /// # Arguments
 ///
 /// * `pressure_pa` - Differential pressure in Pascals
@@ -146,12 +434,97 @@ pub fn calculate_temperature_deg_c(temperature_counts: u16) -> f32 {
 /// println!("Airspeed: {:.2} m/s", airspeed);
 /// ```
 pub fn calculate_airspeed(pressure_pa: f32, temp_c: f32) -> f32 {
-    let temp_k = temp_c + 273.15;
-    // Calculate air density using ideal gas law: ρ = P / (R * T)
-    // P = 101325 Pa (standard pressure), R = 287.05 J/(kg·K) (specific gas constant for air)
-    let air_density = 101325.0 / (287.05 * temp_k);
+    calculate_true_airspeed(pressure_pa, temp_c, atmosphere::SEA_LEVEL_PRESSURE_PA)
+}
+
+/// Calculates airspeed, keeping the sign of the differential pressure.
+///
+/// [`calculate_airspeed`] uses the magnitude of ΔP, so a negative pressure
+/// (reversed pitot tubes, or a tailwind while hovering) reads as forward
+/// flight. This variant returns a negative airspeed for negative ΔP instead.
+/// See [`crate::pitot`] for detecting reversed tubes.
+///
+/// # Arguments
+///
+/// * `pressure_pa` - Differential pressure in Pascals
+/// * `temp_c` - Temperature in degrees Celsius
+///
+/// # Returns
+///
+/// Signed airspeed in meters per second (m/s)
+///
+/// # Example
+///
+/// ```
+/// use ms4525do::{calculate_airspeed, calculate_airspeed_signed};
+///
+/// assert_eq!(calculate_airspeed_signed(-50.0, 20.0), -calculate_airspeed(50.0, 20.0));
+/// ```
+pub fn calculate_airspeed_signed(pressure_pa: f32, temp_c: f32) -> f32 {
+    libm::copysignf(calculate_airspeed(pressure_pa, temp_c), pressure_pa)
+}
+
+/// Typed equivalent of [`calculate_airspeed`].
+///
+/// # Arguments
+///
+/// * `pressure` - Differential pressure
+/// * `temperature` - Temperature
+///
+/// # Returns
+///
+/// Airspeed, assuming sea-level static pressure
+///
+/// # Example
+///
+/// ```
+/// use ms4525do::airspeed;
+/// use ms4525do::units::{Celsius, Pascals};
+///
+/// let speed = airspeed(Pascals(50.0), Celsius(20.0));
+/// println!("Airspeed: {:.1} kt", speed.knots());
+/// ```
+pub fn airspeed(pressure: Pascals, temperature: Celsius) -> MetersPerSecond {
+    MetersPerSecond(calculate_airspeed(pressure.0, temperature.0))
+}
+
+/// Calculates true airspeed from differential pressure, temperature and static pressure.
+///
+/// The air density is computed from the ideal gas law using the measured
+/// static pressure, see [`atmosphere::air_density`].
+///
+/// # Arguments
+///
+/// * `pressure_pa` - Differential pressure in Pascals
+/// * `temp_c` - Outside air temperature in degrees Celsius
+/// * `static_pressure_pa` - Static (ambient) pressure in Pascals
+///
+/// # Returns
+///
+/// True airspeed in meters per second (m/s)
+pub fn calculate_true_airspeed(pressure_pa: f32, temp_c: f32, static_pressure_pa: f32) -> f32 {
+    calculate_airspeed_with_density(
+        pressure_pa,
+        atmosphere::air_density(static_pressure_pa, temp_c),
+    )
+}
+
+/// Calculates airspeed from differential pressure and a known air density.
+///
+/// Passing the ISA sea-level density ([`atmosphere::sea_level_density`])
+/// gives equivalent airspeed.
+///
+/// # Arguments
+///
+/// * `pressure_pa` - Differential pressure in Pascals
+/// * `density` - Air density in kg/m³
+///
+/// # Returns
+///
+/// Airspeed in meters per second (m/s)
+pub fn calculate_airspeed_with_density(pressure_pa: f32, density: f32) -> f32 {
     // Bernoulli equation for airspeed
-    libm::sqrtf(2.0 * pressure_pa.abs() / air_density)
+    libm::sqrtf(2.0 * pressure_pa.abs() / density)
 }
 
 #[cfg(test)]
@@ -166,6 +539,23 @@ mod tests {
         assert_eq!(Status::from(0b11), Status::FaultDetected);
     }
 
+    #[test]
+    fn test_range_status() {
+        let band = (1638, 14745);
+        assert_eq!(RangeStatus::classify(1638, band), RangeStatus::InRange);
+        assert_eq!(RangeStatus::classify(14745, band), RangeStatus::InRange);
+        assert_eq!(RangeStatus::classify(1637, band), RangeStatus::SaturatedLow);
+        assert_eq!(
+            RangeStatus::classify(14746, band),
+            RangeStatus::SaturatedHigh
+        );
+        assert_eq!(RangeStatus::classify(0, band), RangeStatus::Disconnected);
+        assert_eq!(
+            check_range(16383, band),
+            Err(Ms4525doError::DataOutOfRange(RangeStatus::Disconnected))
+        );
+    }
+
     #[test]
     fn test_extract_bridge_data() {
         let data = [0x3F, 0xFF, 0x80, 0x00];
@@ -180,6 +570,29 @@ mod tests {
         assert_eq!(temp, 0x0407, "Incorrect temperature extraction");
     }
 
+    #[test]
+    fn test_validate_frames() {
+        let fresh = [0x20, 0x00, 0x5F, 0xE0];
+        let stale = [0xA0, 0x00, 0x5F, 0xE0];
+        assert_eq!(validate_frames(&fresh, &stale), Ok((0x2000, 0x02FF)));
+
+        let fault = [0xE0, 0x00, 0x5F, 0xE0];
+        assert_eq!(
+            validate_frames(&fresh, &fault),
+            Err(Ms4525doError::FaultDetected)
+        );
+        assert_eq!(
+            validate_frames(&stale, &stale),
+            Err(Ms4525doError::InvalidStatus(Status::StaleData))
+        );
+
+        let changed = [0xA0, 0x01, 0x5F, 0xE0];
+        assert_eq!(
+            validate_frames(&fresh, &changed),
+            Err(Ms4525doError::StaleDataMismatch)
+        );
+    }
+
     #[test]
     fn test_calculate_pressure_differential_pa() {
         let bridge_data = 8192; // Mid-range
@@ -189,6 +602,10 @@ mod tests {
             "Pressure calculation incorrect: {}",
             pressure_pa
         );
+
+        // Datasheet sign: 10% of full scale is -1 PSI, 90% is +1 PSI
+        assert!((calculate_pressure_differential_pa(1638) + PSI_TO_PA).abs() < 1.0);
+        assert!((calculate_pressure_differential_pa(14745) - PSI_TO_PA).abs() < 1.0);
     }
 
     #[test]
@@ -221,4 +638,140 @@ mod tests {
             airspeed
         );
     }
+
+    #[test]
+    fn test_calculate_true_airspeed() {
+        // 2500 m MSL site: the sea-level assumption under-reads TAS by ~14%
+        let static_pa = atmosphere::isa_pressure_pa(2_500.0);
+        let tas = calculate_true_airspeed(200.0, 0.0, static_pa);
+        let sea_level = calculate_airspeed(200.0, 0.0);
+        assert!(tas / sea_level > 1.13 && tas / sea_level < 1.18);
+
+        let density = atmosphere::air_density(static_pa, 0.0);
+        assert_eq!(calculate_airspeed_with_density(200.0, density), tas);
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_fetch_modes() {
+        use crate::blocking::Ms4525do;
+        use crate::mock::{frames, NoDelay, SensorMock};
+
+        // Both packets in one transaction by default
+        let mut sensor = Ms4525do::new(SensorMock::new([Some(frames(8192, 767))]));
+        assert_eq!(sensor.fetch_mode(), FetchMode::Transaction);
+        assert!(sensor.read_data(&mut NoDelay).is_ok());
+        let i2c = sensor.release();
+        assert_eq!(i2c.shapes(), ["W", "RWR"]);
+        assert_eq!(i2c.statuses(), [Status::NormalOperation, Status::StaleData]);
+        i2c.assert_finished();
+
+        let mut sensor = Ms4525do::new(SensorMock::new([Some(frames(8192, 767))]));
+        sensor.set_fetch_mode(FetchMode::SeparateReads);
+        assert!(sensor.read_data(&mut NoDelay).is_ok());
+        let i2c = sensor.release();
+        assert_eq!(i2c.shapes(), ["W", "R", "R"]);
+        assert_eq!(i2c.statuses(), [Status::NormalOperation, Status::StaleData]);
+        i2c.assert_finished();
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_transaction_fetch_bus_clock() {
+        use crate::blocking::Ms4525do;
+        use crate::mock::{frames, NoDelay, SensorMock};
+        use crate::retry::RetryPolicy;
+
+        // 10 SCL periods from the request to the second packet: 400 µs at 25 kHz
+        let i2c = SensorMock::new([Some(frames(8192, 767))]).with_bus_clock(25_000);
+        let mut sensor = Ms4525do::new(i2c);
+        assert!(sensor.read_data(&mut NoDelay).is_ok());
+        let i2c = sensor.release();
+        assert_eq!(i2c.statuses(), [Status::NormalOperation, Status::StaleData]);
+
+        // 500 µs at 20 kHz: the conversion started by the request has completed
+        let i2c = SensorMock::new([Some(frames(8192, 767))]).with_bus_clock(20_000);
+        let mut sensor = Ms4525do::new(i2c);
+        sensor.set_retry_policy(RetryPolicy::none());
+        assert_eq!(
+            sensor.read_data(&mut NoDelay),
+            Err(Ms4525doError::InvalidStatus(Status::NormalOperation))
+        );
+        let i2c = sensor.release();
+        assert_eq!(i2c.statuses(), [Status::NormalOperation; 2]);
+
+        // Separate reads do not depend on the bus clock
+        let i2c = SensorMock::new([Some(frames(8192, 767))]).with_bus_clock(10_000);
+        let mut sensor = Ms4525do::new(i2c);
+        sensor.set_fetch_mode(FetchMode::SeparateReads);
+        assert!(sensor.read_data(&mut NoDelay).is_ok());
+        let i2c = sensor.release();
+        assert_eq!(i2c.statuses(), [Status::NormalOperation, Status::StaleData]);
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_pipelined_fetch() {
+        use crate::blocking::Ms4525do;
+        use crate::mock::{frames, NoDelay, SensorMock};
+
+        // The request between the packets is the one for the next reading
+        let mut sensor = Ms4525do::new_shared(SensorMock::new([Some(frames(8192, 767))]));
+        assert_eq!(sensor.fetch_mode(), FetchMode::Pipelined);
+        assert!(sensor.read_data(&mut NoDelay).is_ok());
+        assert!(sensor.read_data(&mut NoDelay).is_ok());
+        let i2c = sensor.release();
+        assert_eq!(i2c.shapes(), ["W", "RWR", "RWR"]);
+        assert_eq!(
+            i2c.statuses(),
+            [
+                Status::NormalOperation,
+                Status::StaleData,
+                Status::NormalOperation,
+                Status::StaleData
+            ]
+        );
+        i2c.assert_finished();
+
+        // A reading that fails validation sends a new request on the retry
+        let [fresh, _] = frames(8192, 767);
+        let [_, converted] = frames(8193, 767);
+        let i2c = SensorMock::new([Some([fresh, converted]), Some(frames(8192, 767))]);
+        let mut sensor = Ms4525do::new_shared(i2c);
+        assert!(sensor.read_data(&mut NoDelay).is_ok());
+        let i2c = sensor.release();
+        assert_eq!(i2c.shapes(), ["W", "RWR", "W", "RWR"]);
+        i2c.assert_finished();
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_fetch_modes_async() {
+        use crate::async_api::Ms4525do;
+        use crate::mock::{frames, SensorMock};
+        use futures_executor::block_on;
+
+        let mut sensor = Ms4525do::new(SensorMock::new([Some(frames(8192, 767))]));
+        assert_eq!(sensor.fetch_mode(), FetchMode::Transaction);
+        assert!(block_on(sensor.read_data()).is_ok());
+        let i2c = sensor.release();
+        assert_eq!(i2c.shapes(), ["W", "RWR"]);
+        assert_eq!(i2c.statuses(), [Status::NormalOperation, Status::StaleData]);
+        i2c.assert_finished();
+
+        let mut sensor = Ms4525do::new(SensorMock::new([Some(frames(8192, 767))]));
+        sensor.set_fetch_mode(FetchMode::SeparateReads);
+        assert!(block_on(sensor.read_data()).is_ok());
+        let i2c = sensor.release();
+        assert_eq!(i2c.shapes(), ["W", "R", "R"]);
+        assert_eq!(i2c.statuses(), [Status::NormalOperation, Status::StaleData]);
+        i2c.assert_finished();
+
+        let mut sensor = Ms4525do::new_shared(SensorMock::new([Some(frames(8192, 767))]));
+        assert!(block_on(sensor.read_data()).is_ok());
+        assert!(block_on(sensor.read_data()).is_ok());
+        let i2c = sensor.release();
+        assert_eq!(i2c.shapes(), ["W", "RWR", "RWR"]);
+        i2c.assert_finished();
+    }
 }
@@ -1,13 +1,17 @@
 //! Error types for MS4525DO sensor operations.
 
-use crate::common::Status;
+use crate::common::{RangeStatus, Status};
 
 #[cfg(feature = "defmt")]
 use defmt::Format;
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 /// Errors that can occur during MS4525DO sensor operations.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "defmt", derive(Format))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Ms4525doError {
     /// I2C communication error occurred.
     ///
@@ -28,11 +32,15 @@ pub enum Ms4525doError {
     /// Contains the unexpected `Status` value that was received.
     InvalidStatus(Status),
 
-    /// Internal buffer allocation failed.
+    /// Bridge data is outside the valid output band of the sensor.
+    ///
+    /// The output is only valid within the band of the configured output type
+    /// (10% to 90% of full scale for Type A parts). Readings outside it mean:
+    /// - Over-pressure (`SaturatedHigh` / `SaturatedLow`)
+    /// - A disconnected or broken bridge (`Disconnected`)
     ///
-    /// This error should not occur in normal operation as the buffer size
-    /// is fixed at 4 bytes. If this occurs, it may indicate memory corruption.
-    DataOutOfRange,
+    /// Contains the classification of the reading.
+    DataOutOfRange(RangeStatus),
 
     /// Sensor detected a fault condition.
     ///
@@ -54,6 +62,47 @@ pub enum Ms4525doError {
     ///
     /// Try reading again after a short delay.
     StaleDataMismatch,
+
+    /// Sensor keeps returning the same frame.
+    ///
+    /// Reported by the stuck-sensor detection (see [`crate::stuck`]) when the
+    /// raw counts stay below the sensor's noise floor for too long while the
+    /// status still reads `NormalOperation`. This usually means an internal
+    /// lockup; power cycle the sensor.
+    SensorStuck,
+}
+
+impl Ms4525doError {
+    /// Returns the compact one-byte code used by the binary log and codec formats.
+    ///
+    /// The variant is stored in the low nibble; for `InvalidStatus` the status
+    /// value, and for `DataOutOfRange` the range status, is stored in bits 4-5.
+    /// Code 0 is reserved for "no error".
+    #[cfg(any(feature = "binlog", feature = "minicbor"))]
+    pub(crate) fn code(&self) -> u8 {
+        match self {
+            Ms4525doError::I2cError => 1,
+            Ms4525doError::InvalidStatus(status) => 2 | ((*status as u8) << 4),
+            Ms4525doError::DataOutOfRange(range) => 3 | ((*range as u8) << 4),
+            Ms4525doError::FaultDetected => 4,
+            Ms4525doError::StaleDataMismatch => 5,
+            Ms4525doError::SensorStuck => 6,
+        }
+    }
+
+    /// Maps a code produced by [`Ms4525doError::code`] back to its error.
+    #[cfg(any(feature = "binlog", feature = "minicbor"))]
+    pub(crate) fn from_code(code: u8) -> Option<Self> {
+        match code & 0x0F {
+            1 => Some(Ms4525doError::I2cError),
+            2 => Some(Ms4525doError::InvalidStatus(Status::from(code >> 4))),
+            3 => Some(Ms4525doError::DataOutOfRange(RangeStatus::from(code >> 4))),
+            4 => Some(Ms4525doError::FaultDetected),
+            5 => Some(Ms4525doError::StaleDataMismatch),
+            6 => Some(Ms4525doError::SensorStuck),
+            _ => None,
+        }
+    }
 }
 
 impl core::fmt::Display for Ms4525doError {
@@ -65,8 +114,8 @@ impl core::fmt::Display for Ms4525doError {
             Ms4525doError::InvalidStatus(status) => {
                 write!(f, "Invalid sensor status: {:?}", status)
             }
-            Ms4525doError::DataOutOfRange => {
-                write!(f, "Internal buffer allocation failed")
+            Ms4525doError::DataOutOfRange(range) => {
+                write!(f, "Bridge data out of range: {:?}", range)
             }
             Ms4525doError::FaultDetected => {
                 write!(f, "Sensor fault detected")
@@ -74,6 +123,9 @@ impl core::fmt::Display for Ms4525doError {
             Ms4525doError::StaleDataMismatch => {
                 write!(f, "Data validation failed between consecutive reads")
             }
+            Ms4525doError::SensorStuck => {
+                write!(f, "Sensor output frozen")
+            }
         }
     }
 }
@@ -0,0 +1,234 @@
+//! Fixed-point pressure, temperature and airspeed pipeline.
+//!
+//! Integer-only equivalents of [`crate::calculate_airspeed`] and the
+//! MS4525DO 001PD transfer functions for targets without an FPU, such as
+//! Cortex-M0+ and RISC-V parts. Raw counts are converted to centipascals and
+//! centidegrees Celsius, and airspeed is computed in cm/s with an integer
+//! square root.
+//!
+//! Every result is rounded to the nearest unit and is within the documented
+//! bounds ([`PRESSURE_MAX_ERROR_CPA`], [`TEMPERATURE_MAX_ERROR_CDEG`],
+//! [`AIRSPEED_MAX_ERROR_CM_S`]) of the `f32` path for every possible raw
+//! input, which is verified exhaustively by the tests.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use ms4525do::fixed::FixedReading;
+//! use ms4525do::validate_frames;
+//!
+//! let [data_1, data_2] = sensor.read_raw_frames(&mut delay)?;
+//! let (bridge_data, temperature) = validate_frames(&data_1, &data_2)?;
+//!
+//! let reading = FixedReading::from_counts(bridge_data, temperature);
+//! let airspeed_cm_s = reading.airspeed_cm_s();
+//! ```
+
+#[cfg(feature = "defmt")]
+use defmt::Format;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Maximum difference from `calculate_pressure_differential_pa`, in centipascals
+pub const PRESSURE_MAX_ERROR_CPA: i32 = 1;
+
+/// Maximum difference from `calculate_temperature_deg_c`, in centidegrees
+pub const TEMPERATURE_MAX_ERROR_CDEG: i32 = 1;
+
+/// Maximum difference from [`crate::calculate_airspeed`] for raw sensor inputs, in cm/s
+pub const AIRSPEED_MAX_ERROR_CM_S: u32 = 1;
+
+/// Absolute zero offset, in centidegrees
+const KELVIN_OFFSET_CDEG: i64 = 27_315;
+
+/// Divides and rounds to the nearest integer, with ties away from zero.
+fn div_round(numerator: i64, denominator: i64) -> i64 {
+    if numerator >= 0 {
+        (numerator + denominator / 2) / denominator
+    } else {
+        (numerator - denominator / 2) / denominator
+    }
+}
+
+/// Integer square root, rounded down.
+///
+/// Uses the bit-by-bit method, which needs no division or multiplication.
+pub fn isqrt(value: u64) -> u32 {
+    let mut remainder = value;
+    let mut root = 0u64;
+    let mut bit = 1u64 << 62;
+    while bit > remainder {
+        bit >>= 2;
+    }
+    while bit != 0 {
+        if remainder >= root + bit {
+            remainder -= root + bit;
+            root = (root >> 1) + bit;
+        } else {
+            root >>= 1;
+        }
+        bit >>= 2;
+    }
+    root as u32
+}
+
+/// Converts raw bridge data to differential pressure in centipascals.
+///
+/// Integer equivalent of the MS4525DO 001PD transfer function,
+/// `P = 6894.76 Pa * (2 * counts - 16383) / 13106.4`.
+///
+/// # Arguments
+///
+/// * `bridge_data` - 14-bit raw pressure value from sensor
+///
+/// # Returns
+///
+/// Differential pressure in centipascals (0.01 Pa)
+pub fn pressure_centipascals(bridge_data: u16) -> i32 {
+    let numerator = 6_894_760 * (2 * bridge_data as i64 - 16_383);
+    div_round(numerator, 131_064) as i32
+}
+
+/// Converts raw temperature data to centidegrees Celsius.
+///
+/// Integer equivalent of `T = 200 * counts / 2047 - 50`.
+///
+/// # Arguments
+///
+/// * `temperature_counts` - 11-bit raw temperature value from sensor
+///
+/// # Returns
+///
+/// Temperature in centidegrees Celsius (0.01 °C)
+pub fn temperature_centidegrees(temperature_counts: u16) -> i32 {
+    div_round(20_000 * temperature_counts as i64, 2_047) as i32 - 5_000
+}
+
+/// Calculates airspeed in cm/s from fixed-point pressure and temperature.
+///
+/// Integer equivalent of [`crate::calculate_airspeed`], using
+/// `v² = 2 * |ΔP| * 287.05 * T / 101325`.
+///
+/// # Arguments
+///
+/// * `pressure_cpa` - Differential pressure in centipascals
+/// * `temperature_cdeg` - Temperature in centidegrees Celsius
+///
+/// # Returns
+///
+/// Airspeed in cm/s, rounded to the nearest integer
+pub fn airspeed_cm_s(pressure_cpa: i32, temperature_cdeg: i32) -> u32 {
+    let temperature_k_cdeg = (temperature_cdeg as i64 + KELVIN_OFFSET_CDEG).max(0) as u64;
+    // (cm/s)² = cPa * 28705 * T[cK] / (101325 * 100 / 2)
+    let numerator = pressure_cpa.unsigned_abs() as u64 * 28_705 * temperature_k_cdeg;
+    let square = (numerator + 5_066_250 / 2) / 5_066_250;
+
+    let root = isqrt(square);
+    // Round to nearest: (root + 0.5)² = root² + root + 0.25
+    if square - root as u64 * root as u64 > root as u64 {
+        root + 1
+    } else {
+        root
+    }
+}
+
+/// A differential pressure and temperature measurement in fixed-point units.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FixedReading {
+    /// Differential pressure in centipascals
+    pub pressure_cpa: i32,
+    /// Temperature in centidegrees Celsius
+    pub temperature_cdeg: i32,
+}
+
+impl FixedReading {
+    /// Converts validated raw counts, as returned by [`crate::validate_frames`].
+    pub fn from_counts(bridge_data: u16, temperature_counts: u16) -> Self {
+        Self {
+            pressure_cpa: pressure_centipascals(bridge_data),
+            temperature_cdeg: temperature_centidegrees(temperature_counts),
+        }
+    }
+
+    /// Calculates the airspeed for this reading, see [`airspeed_cm_s`].
+    pub fn airspeed_cm_s(&self) -> u32 {
+        airspeed_cm_s(self.pressure_cpa, self.temperature_cdeg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calculate_airspeed;
+    use crate::common::{calculate_pressure_differential_pa, calculate_temperature_deg_c};
+
+    #[test]
+    fn test_isqrt() {
+        for value in 0..10_000u64 {
+            let root = isqrt(value) as u64;
+            assert!(root * root <= value && (root + 1) * (root + 1) > value);
+        }
+        assert_eq!(isqrt(u64::MAX), u32::MAX);
+    }
+
+    #[test]
+    fn test_pressure_exhaustive() {
+        for counts in 0..16_384u16 {
+            let float_cpa = calculate_pressure_differential_pa(counts) * 100.0;
+            let fixed_cpa = pressure_centipascals(counts);
+            assert!(
+                (fixed_cpa as f32 - float_cpa).abs() <= PRESSURE_MAX_ERROR_CPA as f32,
+                "{} counts: {} != {}",
+                counts,
+                fixed_cpa,
+                float_cpa
+            );
+        }
+    }
+
+    #[test]
+    fn test_temperature_exhaustive() {
+        for counts in 0..2_048u16 {
+            let float_cdeg = calculate_temperature_deg_c(counts) * 100.0;
+            let fixed_cdeg = temperature_centidegrees(counts);
+            assert!(
+                (fixed_cdeg as f32 - float_cdeg).abs() <= TEMPERATURE_MAX_ERROR_CDEG as f32,
+                "{} counts: {} != {}",
+                counts,
+                fixed_cdeg,
+                float_cdeg
+            );
+        }
+    }
+
+    #[test]
+    fn test_airspeed_exhaustive() {
+        let pressures: [(i32, f32); 16_384] = core::array::from_fn(|counts| {
+            let counts = counts as u16;
+            (
+                pressure_centipascals(counts),
+                calculate_pressure_differential_pa(counts),
+            )
+        });
+
+        for temperature_counts in 0..2_048u16 {
+            let fixed_cdeg = temperature_centidegrees(temperature_counts);
+            let float_c = calculate_temperature_deg_c(temperature_counts);
+            for &(fixed_cpa, float_pa) in pressures.iter() {
+                let float_cm_s = calculate_airspeed(float_pa, float_c) * 100.0;
+                let fixed_cm_s = airspeed_cm_s(fixed_cpa, fixed_cdeg);
+                assert!(
+                    (fixed_cm_s as f32 - float_cm_s).abs() <= AIRSPEED_MAX_ERROR_CM_S as f32,
+                    "{} Pa, {} °C: {} != {}",
+                    float_pa,
+                    float_c,
+                    fixed_cm_s,
+                    float_cm_s
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,324 @@
+//! Sensor health monitoring and statistics counters.
+//!
+//! Each driver keeps a [`Health`] record that is updated on every read
+//! attempt, including the attempts retried inside `read_data`. It counts
+//! successful reads and every [`Ms4525doError`] variant, tracks consecutive
+//! failures and the longest error streak, and remembers when the last good
+//! reading was taken. [`Health::status`] summarizes these as a
+//! [`HealthStatus`] using configurable [`HealthThresholds`].
+//!
+//! The record is a plain `Copy` value, so a snapshot can be taken at any time
+//! without touching the bus, e.g. to send it in telemetry or to raise
+//! pre-flight warnings.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use ms4525do::health::HealthStatus;
+//!
+//! let health = *sensor.health();
+//! if sensor.health_status() != HealthStatus::Healthy {
+//!     warn!("Airspeed sensor: {} consecutive failures", health.consecutive_failures);
+//! }
+//! ```
+
+use crate::common::Status;
+use crate::Ms4525doError;
+
+#[cfg(feature = "defmt")]
+use defmt::Format;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Summary of the sensor health.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum HealthStatus {
+    /// Readings are fresh and errors are rare
+    Healthy,
+    /// Readings are still coming in, but errors or stale reads are frequent
+    Degraded,
+    /// No usable readings: the sensor should not be trusted
+    Failed,
+}
+
+/// Thresholds used to derive a [`HealthStatus`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct HealthThresholds {
+    /// Consecutive failed attempts at which the sensor is degraded
+    pub degraded_consecutive_failures: u32,
+    /// Consecutive failed attempts at which the sensor has failed
+    pub failed_consecutive_failures: u32,
+    /// Stale-read ratio (0.0 to 1.0) above which the sensor is degraded
+    pub degraded_stale_ratio: f32,
+    /// Age of the last good reading above which the sensor is degraded, in milliseconds
+    pub degraded_age_ms: u64,
+    /// Age of the last good reading above which the sensor has failed, in milliseconds
+    pub failed_age_ms: u64,
+}
+
+impl Default for HealthThresholds {
+    /// Degraded after 3 failures, 20% stale reads or 200 ms without data;
+    /// failed after 10 failures or 1 s without data.
+    fn default() -> Self {
+        Self {
+            degraded_consecutive_failures: 3,
+            failed_consecutive_failures: 10,
+            degraded_stale_ratio: 0.2,
+            degraded_age_ms: 200,
+            failed_age_ms: 1_000,
+        }
+    }
+}
+
+/// Number of failed attempts per [`Ms4525doError`] variant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ErrorCounts {
+    /// `I2cError`
+    pub i2c: u32,
+    /// `InvalidStatus`
+    pub invalid_status: u32,
+    /// `DataOutOfRange`
+    pub data_out_of_range: u32,
+    /// `FaultDetected`
+    pub fault_detected: u32,
+    /// `StaleDataMismatch`
+    pub stale_data_mismatch: u32,
+    /// `SensorStuck`
+    pub sensor_stuck: u32,
+}
+
+impl ErrorCounts {
+    /// Returns the total number of errors.
+    pub fn total(&self) -> u32 {
+        self.i2c
+            .wrapping_add(self.invalid_status)
+            .wrapping_add(self.data_out_of_range)
+            .wrapping_add(self.fault_detected)
+            .wrapping_add(self.stale_data_mismatch)
+            .wrapping_add(self.sensor_stuck)
+    }
+}
+
+/// Health statistics of one sensor.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Health {
+    /// Thresholds used by [`Health::status`]
+    pub thresholds: HealthThresholds,
+    /// Number of successful read attempts
+    pub successes: u32,
+    /// Number of failed read attempts per error variant
+    pub errors: ErrorCounts,
+    /// Number of attempts rejected as stale (`StaleDataMismatch` or `InvalidStatus(StaleData)`)
+    pub stale_reads: u32,
+    /// Number of failed attempts since the last successful one
+    pub consecutive_failures: u32,
+    /// Longest run of consecutive failed attempts
+    pub longest_error_streak: u32,
+    /// Time of the last successful attempt, in milliseconds, if a clock is available
+    pub last_good_ms: Option<u64>,
+}
+
+impl Health {
+    /// Creates an empty record with the given thresholds.
+    pub fn new(thresholds: HealthThresholds) -> Self {
+        Self {
+            thresholds,
+            ..Self::default()
+        }
+    }
+
+    /// Records the outcome of one read attempt.
+    ///
+    /// # Arguments
+    ///
+    /// * `result` - Result of the attempt
+    /// * `now_ms` - Current time in milliseconds, or `None` without a clock
+    pub fn record<T>(&mut self, result: &Result<T, Ms4525doError>, now_ms: Option<u64>) {
+        match result {
+            Ok(_) => {
+                self.successes = self.successes.wrapping_add(1);
+                self.consecutive_failures = 0;
+                if now_ms.is_some() {
+                    self.last_good_ms = now_ms;
+                }
+            }
+            Err(e) => {
+                self.record_error(e);
+                self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+                self.longest_error_streak =
+                    self.longest_error_streak.max(self.consecutive_failures);
+            }
+        }
+    }
+
+    fn record_error(&mut self, error: &Ms4525doError) {
+        let counter = match error {
+            Ms4525doError::I2cError => &mut self.errors.i2c,
+            Ms4525doError::InvalidStatus(_) => &mut self.errors.invalid_status,
+            Ms4525doError::DataOutOfRange(_) => &mut self.errors.data_out_of_range,
+            Ms4525doError::FaultDetected => &mut self.errors.fault_detected,
+            Ms4525doError::StaleDataMismatch => &mut self.errors.stale_data_mismatch,
+            Ms4525doError::SensorStuck => &mut self.errors.sensor_stuck,
+        };
+        *counter = counter.wrapping_add(1);
+
+        if matches!(
+            error,
+            Ms4525doError::StaleDataMismatch | Ms4525doError::InvalidStatus(Status::StaleData)
+        ) {
+            self.stale_reads = self.stale_reads.wrapping_add(1);
+        }
+    }
+
+    /// Returns the total number of read attempts.
+    pub fn attempts(&self) -> u32 {
+        self.successes.wrapping_add(self.errors.total())
+    }
+
+    /// Returns the fraction of attempts rejected as stale, from 0.0 to 1.0.
+    pub fn stale_ratio(&self) -> f32 {
+        ratio(self.stale_reads, self.attempts())
+    }
+
+    /// Returns the fraction of attempts that failed, from 0.0 to 1.0.
+    pub fn failure_ratio(&self) -> f32 {
+        ratio(self.errors.total(), self.attempts())
+    }
+
+    /// Returns the time since the last good reading in milliseconds.
+    ///
+    /// # Returns
+    ///
+    /// `None` if no good reading has been timestamped yet
+    pub fn ms_since_last_good(&self, now_ms: u64) -> Option<u64> {
+        self.last_good_ms.map(|t| now_ms.saturating_sub(t))
+    }
+
+    /// Summarizes the statistics.
+    ///
+    /// The age of the last good reading is only checked when `now_ms` is
+    /// given. A sensor that has never produced a good reading is failed once
+    /// it has had `failed_consecutive_failures` attempts, like any other.
+    ///
+    /// # Arguments
+    ///
+    /// * `now_ms` - Current time in milliseconds, or `None` without a clock
+    pub fn status(&self, now_ms: Option<u64>) -> HealthStatus {
+        let t = &self.thresholds;
+        let age_ms = now_ms.and_then(|now| self.ms_since_last_good(now));
+
+        if self.consecutive_failures >= t.failed_consecutive_failures
+            || age_ms.is_some_and(|age| age > t.failed_age_ms)
+        {
+            HealthStatus::Failed
+        } else if self.consecutive_failures >= t.degraded_consecutive_failures
+            || age_ms.is_some_and(|age| age > t.degraded_age_ms)
+            || self.stale_ratio() > t.degraded_stale_ratio
+        {
+            HealthStatus::Degraded
+        } else {
+            HealthStatus::Healthy
+        }
+    }
+
+    /// Clears all counters, keeping the thresholds.
+    pub fn reset(&mut self) {
+        *self = Self::new(self.thresholds);
+    }
+}
+
+fn ratio(count: u32, total: u32) -> f32 {
+    if total == 0 {
+        0.0
+    } else {
+        count as f32 / total as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RangeStatus;
+
+    #[test]
+    fn test_counters() {
+        let mut health = Health::default();
+        health.record(&Ok(()), Some(10));
+        health.record::<()>(&Err(Ms4525doError::I2cError), Some(20));
+        health.record::<()>(&Err(Ms4525doError::StaleDataMismatch), Some(30));
+        health.record::<()>(
+            &Err(Ms4525doError::InvalidStatus(Status::StaleData)),
+            Some(40),
+        );
+        health.record(&Ok(()), Some(50));
+        health.record::<()>(
+            &Err(Ms4525doError::DataOutOfRange(RangeStatus::Disconnected)),
+            Some(60),
+        );
+
+        assert_eq!(health.successes, 2);
+        assert_eq!(
+            health.errors,
+            ErrorCounts {
+                i2c: 1,
+                invalid_status: 1,
+                data_out_of_range: 1,
+                fault_detected: 0,
+                stale_data_mismatch: 1,
+                sensor_stuck: 0,
+            }
+        );
+        assert_eq!(health.attempts(), 6);
+        assert_eq!(health.stale_reads, 2);
+        assert_eq!(health.consecutive_failures, 1);
+        assert_eq!(health.longest_error_streak, 3);
+        assert_eq!(health.ms_since_last_good(70), Some(20));
+        assert!((health.stale_ratio() - 1.0 / 3.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_status() {
+        let mut health = Health::default();
+        assert_eq!(health.status(None), HealthStatus::Healthy);
+
+        for _ in 0..10 {
+            health.record(&Ok(()), Some(0));
+        }
+        assert_eq!(health.status(Some(100)), HealthStatus::Healthy);
+        assert_eq!(health.status(Some(500)), HealthStatus::Degraded);
+        assert_eq!(health.status(Some(2_000)), HealthStatus::Failed);
+
+        for _ in 0..3 {
+            health.record::<()>(&Err(Ms4525doError::FaultDetected), None);
+        }
+        assert_eq!(health.status(None), HealthStatus::Degraded);
+        for _ in 0..7 {
+            health.record::<()>(&Err(Ms4525doError::FaultDetected), None);
+        }
+        assert_eq!(health.status(None), HealthStatus::Failed);
+
+        health.reset();
+        assert_eq!(health.status(Some(2_000)), HealthStatus::Healthy);
+    }
+
+    #[test]
+    fn test_stale_ratio_degrades() {
+        let mut health = Health::default();
+        for _ in 0..3 {
+            health.record(&Ok(()), None);
+            health.record::<()>(&Err(Ms4525doError::StaleDataMismatch), None);
+        }
+        health.record(&Ok(()), None);
+        assert_eq!(health.consecutive_failures, 0);
+        assert_eq!(health.status(None), HealthStatus::Degraded);
+    }
+}
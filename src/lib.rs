@@ -56,6 +56,19 @@
 //! - `std`: Enable std support (for desktop/server environments)
 //! - `defmt`: Enable defmt logging for embedded debugging
 //! - `log`: Enable log facade for flexible logging
+//! - `ms5525dso`: Enable the MS5525DSO driver (blocking and/or async, following `blocking`/`async`)
+//! - `sdp`: Enable the Sensirion SDP3x / SDP8xx driver (blocking and/or async, following `blocking`/`async`)
+//! - `binlog`: Enable the compact binary log format and buffered SD-card log writer
+//! - `sdmmc`: Enable the `embedded-sdmmc` FAT volume backend for the binary log writer (implies `binlog`)
+//! - `serde`: Derive `Serialize`/`Deserialize` for readings, errors and configuration types
+//! - `postcard`: Enable versioned `postcard` encoding helpers (implies `serde`)
+//! - `minicbor`: Enable versioned CBOR encoding helpers
+//! - `replay`: Enable record-and-replay I2C transports for reproducing field issues (implies `std`)
+//! - `bus-recovery`: Enable I2C bus recovery for a sensor stuck holding SDA low
+//! - `sampler`: Enable the Embassy periodic sampler publishing to `embassy-sync` channels (implies `async`)
+//! - `stream`: Enable `into_stream` on the async driver, returning a `futures_core::Stream` of timestamped readings (implies `async`)
+//! - `spektrum`: Enable Spektrum X-Bus / SRXL2 airspeed telemetry formatting
+//! - `ulog`: Enable PX4 ULog writer and reader for airspeed topics (implies `std`)
 //!
 //! ## Sensor Details
 //!
@@ -65,14 +78,30 @@
 //! - 11-bit temperature resolution
 //! - ±1 PSI measurement range (001PD variant)
 //! - Operating temperature: -50°C to +150°C
+//!
+//! Honeywell TruStability HSC/SSC/ABP and All Sensors DLVR sensors use the same
+//! frame format and are supported through the [`transfer`] module. All drivers
+//! implement the [`sensor`] traits, so downstream code can be sensor-agnostic.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 #![forbid(unsafe_code)]
 #![warn(missing_docs)]
 
 // Module declarations
+pub mod atmosphere;
 mod common;
 mod error;
+pub mod fixed;
+pub mod health;
+pub mod pitot;
+pub mod retry;
+pub mod self_test;
+pub mod sensor;
+pub mod stuck;
+pub mod timing;
+pub mod transfer;
+pub mod uncertainty;
+pub mod units;
 
 #[cfg(feature = "async")]
 pub mod async_api;
@@ -80,8 +109,51 @@ pub mod async_api;
 #[cfg(feature = "blocking")]
 pub mod blocking;
 
+#[cfg(feature = "binlog")]
+pub mod binlog;
+
+#[cfg(feature = "ms5525dso")]
+pub mod ms5525dso;
+
+#[cfg(any(feature = "postcard", feature = "minicbor"))]
+pub mod codec;
+
+#[cfg(feature = "bus-recovery")]
+pub mod recovery;
+
+#[cfg(feature = "replay")]
+pub mod replay;
+
+#[cfg(feature = "sampler")]
+pub mod sampler;
+
+#[cfg(feature = "sdp")]
+pub mod sdp;
+
+#[cfg(any(feature = "blocking", feature = "async"))]
+pub mod shared_bus;
+
+#[cfg(feature = "spektrum")]
+pub mod spektrum;
+
+#[cfg(feature = "stream")]
+pub mod stream;
+
+#[cfg(all(
+    test,
+    any(feature = "blocking", feature = "async", feature = "bus-recovery")
+))]
+mod mock;
+
+#[cfg(feature = "ulog")]
+pub mod ulog;
+
 // Re-export public types and functions
-pub use common::{calculate_airspeed, Status};
+pub use common::{
+    airspeed, calculate_airspeed, calculate_airspeed_signed, calculate_airspeed_with_density,
+    calculate_true_airspeed, check_range, validate_frames, FetchMode, RangeStatus, Reading, Status,
+    DATA_SIZE,
+};
 pub use error::Ms4525doError;
 
 // For backwards compatibility and convenience, re-export the default API at the root level
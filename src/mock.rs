@@ -0,0 +1,388 @@
+//! Test doubles shared by the driver tests.
+
+extern crate std;
+
+#[cfg(any(feature = "blocking", feature = "async"))]
+use crate::common::{Status, DATA_SIZE, MS4525DO_ADDR};
+#[cfg(any(feature = "blocking", feature = "async"))]
+use embedded_hal::i2c::{ErrorKind, ErrorType, Operation};
+#[cfg(any(feature = "blocking", feature = "async"))]
+use std::{collections::VecDeque, string::String, vec::Vec};
+
+#[cfg(all(feature = "ms5525dso", any(feature = "blocking", feature = "async")))]
+use crate::ms5525dso::{crc4, CMD_ADC_READ, CMD_PROM_READ, MS5525DSO_ADDR, PROM_WORDS};
+#[cfg(all(feature = "sdp", any(feature = "blocking", feature = "async")))]
+use crate::sdp::{crc8, CMD_SOFT_RESET, CMD_STOP_CONTINUOUS, GENERAL_CALL_ADDR};
+
+/// Delay provider that returns immediately.
+#[cfg(any(feature = "blocking", feature = "bus-recovery"))]
+pub(crate) struct NoDelay;
+
+#[cfg(any(feature = "blocking", feature = "bus-recovery"))]
+impl embedded_hal::delay::DelayNs for NoDelay {
+    fn delay_ns(&mut self, _ns: u32) {}
+}
+
+/// Delay provider that returns immediately and adds up the requested time.
+#[cfg(all(feature = "sdp", feature = "blocking"))]
+#[derive(Default)]
+pub(crate) struct TotalDelay {
+    pub(crate) ns: u64,
+}
+
+#[cfg(all(feature = "sdp", feature = "blocking"))]
+impl embedded_hal::delay::DelayNs for TotalDelay {
+    fn delay_ns(&mut self, ns: u32) {
+        self.ns += u64::from(ns);
+    }
+}
+
+/// Fresh and stale packets of one reading for the given bridge and temperature counts
+#[cfg(any(feature = "blocking", feature = "async"))]
+pub(crate) fn frames(bridge: u16, temperature: u16) -> [[u8; DATA_SIZE]; 2] {
+    let data = [
+        (bridge >> 8) as u8,
+        bridge as u8,
+        (temperature >> 3) as u8,
+        (temperature << 5) as u8,
+    ];
+    let mut stale = data;
+    stale[0] |= 0x80;
+    [data, stale]
+}
+
+/// Conversion time of the simulated MS4525DO, in nanoseconds
+#[cfg(any(feature = "blocking", feature = "async"))]
+const CONVERSION_NS: u64 = 500_000;
+
+/// MS4525DO converting the next scripted reading on each measurement request.
+///
+/// Every write is a measurement request that starts a conversion. A read
+/// returns the fresh packet if a conversion completed since the last read,
+/// and the stale packet otherwise. A `None` reading NACKs its measurement
+/// request. A request sent after a read in the same transaction, as in the
+/// `Read, Write, Read` fetch transaction, converts the same pressure again.
+///
+/// Time advances with the bits clocked out at the bus clock: the packet of a
+/// read is chosen when the sensor acknowledges its address. A conversion still
+/// running at the end of a transaction has completed at the start of the
+/// next one, as the drivers wait 2 ms after each request.
+#[cfg(any(feature = "blocking", feature = "async"))]
+pub(crate) struct SensorMock {
+    readings: VecDeque<Option<[[u8; DATA_SIZE]; 2]>>,
+    current: Option<[[u8; DATA_SIZE]; 2]>,
+    bit_ns: u64,
+    conversion_end_ns: Option<u64>,
+    fresh: bool,
+    shapes: Vec<String>,
+    statuses: Vec<Status>,
+}
+
+#[cfg(any(feature = "blocking", feature = "async"))]
+impl SensorMock {
+    pub(crate) fn new(readings: impl IntoIterator<Item = Option<[[u8; DATA_SIZE]; 2]>>) -> Self {
+        Self {
+            readings: readings.into_iter().collect(),
+            current: None,
+            bit_ns: 10_000,
+            conversion_end_ns: None,
+            fresh: false,
+            shapes: Vec::new(),
+            statuses: Vec::new(),
+        }
+    }
+
+    /// Clocks the bus at `scl_hz` instead of 100 kHz
+    #[cfg(feature = "blocking")]
+    pub(crate) fn with_bus_clock(mut self, scl_hz: u32) -> Self {
+        self.bit_ns = 1_000_000_000 / u64::from(scl_hz);
+        self
+    }
+
+    /// Returns the operations of every acknowledged transaction, e.g. `["W", "RWR"]`
+    pub(crate) fn shapes(&self) -> &[String] {
+        &self.shapes
+    }
+
+    /// Returns the status of every packet read so far
+    pub(crate) fn statuses(&self) -> &[Status] {
+        &self.statuses
+    }
+
+    /// Panics unless every scripted reading has been requested
+    pub(crate) fn assert_finished(&self) {
+        assert!(
+            self.readings.is_empty(),
+            "{} readings not requested",
+            self.readings.len()
+        );
+    }
+
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), ErrorKind> {
+        assert_eq!(address, MS4525DO_ADDR);
+        if self.conversion_end_ns.take().is_some() {
+            self.fresh = true;
+        }
+        let mut shape = String::new();
+        // START or repeated start and the address byte before each operation
+        let mut now_ns = 0;
+        for operation in operations {
+            now_ns += 10 * self.bit_ns;
+            match operation {
+                Operation::Write(bytes) => {
+                    if !shape.contains('R') {
+                        self.current = self.readings.pop_front().expect("no reading left");
+                        self.current.ok_or(ErrorKind::Other)?;
+                    }
+                    shape.push('W');
+                    now_ns += 9 * self.bit_ns * bytes.len() as u64;
+                    self.conversion_end_ns = Some(now_ns + CONVERSION_NS);
+                }
+                Operation::Read(buffer) => {
+                    shape.push('R');
+                    let packets = self.current.ok_or(ErrorKind::Other)?;
+                    if self.conversion_end_ns.is_some_and(|end| now_ns >= end) {
+                        self.conversion_end_ns = None;
+                        self.fresh = true;
+                    }
+                    let packet = packets[usize::from(!core::mem::take(&mut self.fresh))];
+                    self.statuses.push(Status::from(packet[0] >> 6));
+                    buffer.copy_from_slice(&packet);
+                    now_ns += 9 * self.bit_ns * buffer.len() as u64;
+                }
+            }
+        }
+        self.shapes.push(shape);
+        Ok(())
+    }
+}
+
+#[cfg(any(feature = "blocking", feature = "async"))]
+impl ErrorType for SensorMock {
+    type Error = ErrorKind;
+}
+
+#[cfg(any(feature = "blocking", feature = "async"))]
+impl embedded_hal::i2c::I2c for SensorMock {
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        SensorMock::transaction(self, address, operations)
+    }
+}
+
+#[cfg(feature = "async")]
+impl embedded_hal_async::i2c::I2c for SensorMock {
+    async fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        SensorMock::transaction(self, address, operations)
+    }
+}
+
+/// MS5525DSO with a valid PROM and fixed ADC results.
+///
+/// A conversion command latches D1 or D2 for the next ADC read. Without a
+/// finished conversion the ADC reads 0, as on the sensor.
+#[cfg(all(feature = "ms5525dso", any(feature = "blocking", feature = "async")))]
+pub(crate) struct Ms5525Mock {
+    /// PROM words, with the CRC of the calibration given to `new`
+    pub(crate) prom: [u16; PROM_WORDS],
+    /// Ignore conversion commands, as if every conversion were interrupted
+    pub(crate) stalled: bool,
+    d1: u32,
+    d2: u32,
+    adc: u32,
+    commands: Vec<u8>,
+}
+
+#[cfg(all(feature = "ms5525dso", any(feature = "blocking", feature = "async")))]
+impl Ms5525Mock {
+    pub(crate) fn new(c: [u16; 6], d1: u32, d2: u32) -> Self {
+        let mut prom = [0x0012, c[0], c[1], c[2], c[3], c[4], c[5], 0x5A00];
+        prom[7] |= crc4(&prom) as u16;
+        Self {
+            prom,
+            stalled: false,
+            d1,
+            d2,
+            adc: 0,
+            commands: Vec::new(),
+        }
+    }
+
+    /// Returns every command byte written so far
+    pub(crate) fn commands(&self) -> &[u8] {
+        &self.commands
+    }
+
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), ErrorKind> {
+        assert_eq!(address, MS5525DSO_ADDR);
+        for operation in operations {
+            match operation {
+                Operation::Write(bytes) => {
+                    let command = bytes[0];
+                    self.commands.push(command);
+                    match command {
+                        0x40..=0x48 if !self.stalled => self.adc = self.d1,
+                        0x50..=0x58 if !self.stalled => self.adc = self.d2,
+                        _ => {}
+                    }
+                }
+                Operation::Read(buffer) => match self.commands.last() {
+                    Some(&CMD_ADC_READ) => {
+                        buffer.copy_from_slice(&self.adc.to_be_bytes()[1..]);
+                        self.adc = 0;
+                    }
+                    Some(&command) if command >= CMD_PROM_READ => {
+                        let word = self.prom[usize::from(command - CMD_PROM_READ) / 2];
+                        buffer.copy_from_slice(&word.to_be_bytes());
+                    }
+                    _ => return Err(ErrorKind::Other),
+                },
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(feature = "ms5525dso", any(feature = "blocking", feature = "async")))]
+impl ErrorType for Ms5525Mock {
+    type Error = ErrorKind;
+}
+
+#[cfg(all(feature = "ms5525dso", feature = "blocking"))]
+impl embedded_hal::i2c::I2c for Ms5525Mock {
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        Ms5525Mock::transaction(self, address, operations)
+    }
+}
+
+#[cfg(all(feature = "ms5525dso", feature = "async"))]
+impl embedded_hal_async::i2c::I2c for Ms5525Mock {
+    async fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        Ms5525Mock::transaction(self, address, operations)
+    }
+}
+
+/// SDP3x / SDP8xx returning a fixed measurement while in continuous mode.
+///
+/// Reads are NACKed until a start command, and again after a stop command or
+/// a general call reset.
+#[cfg(all(feature = "sdp", any(feature = "blocking", feature = "async")))]
+pub(crate) struct SdpMock {
+    address: u8,
+    words: [u16; 3],
+    measuring: bool,
+    writes: Vec<(u8, Vec<u8>)>,
+    reads: Vec<usize>,
+}
+
+#[cfg(all(feature = "sdp", any(feature = "blocking", feature = "async")))]
+impl SdpMock {
+    /// Sensor at `address` measuring the pressure, temperature and scale factor `words`
+    pub(crate) fn new(address: u8, words: [u16; 3]) -> Self {
+        Self {
+            address,
+            words,
+            measuring: false,
+            writes: Vec::new(),
+            reads: Vec::new(),
+        }
+    }
+
+    /// Returns the address and bytes of every write so far
+    pub(crate) fn writes(&self) -> &[(u8, Vec<u8>)] {
+        &self.writes
+    }
+
+    /// Returns the length of every read so far
+    pub(crate) fn reads(&self) -> &[usize] {
+        &self.reads
+    }
+
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), ErrorKind> {
+        for operation in operations {
+            match operation {
+                Operation::Write(bytes) => {
+                    self.writes.push((address, bytes.to_vec()));
+                    if address == GENERAL_CALL_ADDR {
+                        if bytes[..] == [CMD_SOFT_RESET] {
+                            self.measuring = false;
+                        }
+                        continue;
+                    }
+                    assert_eq!(address, self.address);
+                    match u16::from_be_bytes([bytes[0], bytes[1]]) {
+                        CMD_STOP_CONTINUOUS => self.measuring = false,
+                        0x3603 | 0x3608 | 0x3615 | 0x361E => self.measuring = true,
+                        command => panic!("unexpected command {:#06x}", command),
+                    }
+                }
+                Operation::Read(buffer) => {
+                    assert_eq!(address, self.address);
+                    if !self.measuring {
+                        return Err(ErrorKind::Other);
+                    }
+                    self.reads.push(buffer.len());
+                    for (chunk, word) in buffer.chunks_mut(3).zip(self.words) {
+                        chunk[..2].copy_from_slice(&word.to_be_bytes());
+                        chunk[2] = crc8(&chunk[..2]);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(feature = "sdp", any(feature = "blocking", feature = "async")))]
+impl ErrorType for SdpMock {
+    type Error = ErrorKind;
+}
+
+#[cfg(all(feature = "sdp", feature = "blocking"))]
+impl embedded_hal::i2c::I2c for SdpMock {
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        SdpMock::transaction(self, address, operations)
+    }
+}
+
+#[cfg(all(feature = "sdp", feature = "async"))]
+impl embedded_hal_async::i2c::I2c for SdpMock {
+    async fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        SdpMock::transaction(self, address, operations)
+    }
+}
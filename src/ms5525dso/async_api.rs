@@ -0,0 +1,273 @@
+//! Asynchronous (non-blocking) API for MS5525DSO sensor communication.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use ms4525do::ms5525dso::async_api::Ms5525dso;
+//! use embassy_time::{Duration, Timer};
+//!
+//! let mut sensor = Ms5525dso::new(i2c);
+//! sensor.init().await?;
+//!
+//! loop {
+//!     match sensor.read_data().await {
+//!         Ok(reading) => println!("Airspeed: {} m/s", reading.airspeed()),
+//!         Err(e) => println!("Error: {:?}", e),
+//!     }
+//!     Timer::after(Duration::from_millis(20)).await;
+//! }
+//! ```
+
+use super::*;
+use crate::sensor::async_api::DifferentialPressureSensor;
+use embassy_time::{Duration, Timer};
+use embedded_hal_async::i2c::I2c;
+
+/// MS5525DSO sensor driver with async I2C communication.
+///
+/// # Type Parameters
+///
+/// * `I2C` - The I2C peripheral type implementing `embedded_hal_async::i2c::I2c`
+pub struct Ms5525dso<I2C> {
+    i2c: I2C,
+    address: u8,
+    range: Ms5525Range,
+    oversampling: Oversampling,
+    second_order: bool,
+    calibration: Option<Calibration>,
+}
+
+impl<I2C> Ms5525dso<I2C>
+where
+    I2C: I2c,
+{
+    /// Creates a new MS5525DSO-pp001DS sensor instance with the default I2C address (0x76).
+    ///
+    /// Call [`Ms5525dso::init`] before reading data.
+    pub fn new(i2c: I2C) -> Self {
+        Self::new_with_config(i2c, MS5525DSO_ADDR, Ms5525Range::default())
+    }
+
+    /// Creates a new sensor instance with a custom I2C address and pressure range.
+    ///
+    /// # Arguments
+    ///
+    /// * `i2c` - The I2C peripheral for communication with the sensor
+    /// * `address` - 7-bit I2C address (0x76 or 0x77, depending on CSB)
+    /// * `range` - Pressure range variant of the part
+    pub fn new_with_config(i2c: I2C, address: u8, range: Ms5525Range) -> Self {
+        Self {
+            i2c,
+            address,
+            range,
+            oversampling: Oversampling::default(),
+            second_order: false,
+            calibration: None,
+        }
+    }
+
+    /// Sets the ADC oversampling ratio used for both conversions.
+    pub fn set_oversampling(&mut self, oversampling: Oversampling) {
+        self.oversampling = oversampling;
+    }
+
+    /// Enables or disables second-order temperature compensation (disabled by default).
+    ///
+    /// The correction uses MS5607 coefficients, see [`compensate`].
+    pub fn set_second_order_compensation(&mut self, enabled: bool) {
+        self.second_order = enabled;
+    }
+
+    /// Returns the calibration loaded by [`Ms5525dso::init`], if any.
+    pub fn calibration(&self) -> Option<&Calibration> {
+        self.calibration.as_ref()
+    }
+
+    /// Resets the sensor and loads its calibration coefficients.
+    ///
+    /// # Errors
+    ///
+    /// * `Ms5525dsoError::I2cError` - I2C communication failure
+    /// * `Ms5525dsoError::PromCrcMismatch` - The PROM fails its CRC4 check
+    pub async fn init(&mut self) -> Result<Calibration, Ms5525dsoError> {
+        self.calibration = None;
+        self.reset().await?;
+        let calibration = Calibration::from_prom(&self.read_prom().await?)?;
+        self.calibration = Some(calibration);
+        Ok(calibration)
+    }
+
+    /// Sends the reset command and waits for the PROM to reload.
+    ///
+    /// # Errors
+    ///
+    /// * `Ms5525dsoError::I2cError` - I2C communication failure
+    pub async fn reset(&mut self) -> Result<(), Ms5525dsoError> {
+        self.i2c
+            .write(self.address, &[CMD_RESET])
+            .await
+            .map_err(|_| Ms5525dsoError::I2cError)?;
+        Timer::after(Duration::from_millis(RESET_DELAY_MS as u64)).await;
+        Ok(())
+    }
+
+    /// Reads all eight raw PROM words without validating them.
+    ///
+    /// # Errors
+    ///
+    /// * `Ms5525dsoError::I2cError` - I2C communication failure
+    pub async fn read_prom(&mut self) -> Result<[u16; PROM_WORDS], Ms5525dsoError> {
+        let mut prom = [0u16; PROM_WORDS];
+        for (i, word) in prom.iter_mut().enumerate() {
+            let mut buf = [0u8; 2];
+            self.i2c
+                .write_read(self.address, &[CMD_PROM_READ + 2 * i as u8], &mut buf)
+                .await
+                .map_err(|_| Ms5525dsoError::I2cError)?;
+            *word = u16::from_be_bytes(buf);
+        }
+        Ok(prom)
+    }
+
+    /// Runs the pressure (D1) and temperature (D2) conversions.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok((u32, u32))` - Raw 24-bit (D1, D2) conversion results
+    /// * `Err(Ms5525dsoError)` - Error if communication fails or a conversion was not ready
+    pub async fn read_raw(&mut self) -> Result<(u32, u32), Ms5525dsoError> {
+        let d1 = self.convert(CMD_CONVERT_D1).await?;
+        let d2 = self.convert(CMD_CONVERT_D2).await?;
+        Ok((d1, d2))
+    }
+
+    /// Reads compensated pressure and temperature from the sensor.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Reading)` - Differential pressure in Pascals and temperature in °C
+    /// * `Err(Ms5525dsoError)` - Error if communication fails or data is invalid
+    ///
+    /// # Errors
+    ///
+    /// * `Ms5525dsoError::NotInitialized` - [`Ms5525dso::init`] has not succeeded yet
+    /// * `Ms5525dsoError::I2cError` - I2C communication failure
+    /// * `Ms5525dsoError::ConversionNotReady` - The ADC returned no result
+    pub async fn read_data(&mut self) -> Result<Reading, Ms5525dsoError> {
+        let calibration = self.calibration.ok_or(Ms5525dsoError::NotInitialized)?;
+        let (d1, d2) = self.read_raw().await?;
+        let compensated = compensate(
+            &calibration,
+            &self.range.q_coefficients(),
+            d1,
+            d2,
+            self.second_order,
+        );
+        Ok(to_reading(&compensated))
+    }
+
+    /// Consumes the sensor driver and returns the underlying I2C peripheral.
+    pub fn release(self) -> I2C {
+        self.i2c
+    }
+
+    async fn convert(&mut self, command: u8) -> Result<u32, Ms5525dsoError> {
+        self.i2c
+            .write(
+                self.address,
+                &[command + self.oversampling.command_offset()],
+            )
+            .await
+            .map_err(|_| Ms5525dsoError::I2cError)?;
+        Timer::after(Duration::from_micros(
+            self.oversampling.conversion_time_us() as u64,
+        ))
+        .await;
+
+        let mut buf = [0u8; 3];
+        self.i2c
+            .write_read(self.address, &[CMD_ADC_READ], &mut buf)
+            .await
+            .map_err(|_| Ms5525dsoError::I2cError)?;
+
+        match u32::from_be_bytes([0, buf[0], buf[1], buf[2]]) {
+            0 => Err(Ms5525dsoError::ConversionNotReady),
+            value => Ok(value),
+        }
+    }
+}
+
+impl<I2C> DifferentialPressureSensor for Ms5525dso<I2C>
+where
+    I2C: I2c,
+{
+    type Error = Ms5525dsoError;
+
+    async fn read_pressure(&mut self) -> Result<Reading, Ms5525dsoError> {
+        self.read_data().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::Ms5525Mock;
+    use futures_executor::block_on;
+
+    const C: [u16; 6] = [36402, 39473, 40393, 29804, 29267, 26291];
+    const D1: u32 = 8_000_000;
+    const D2: u32 = 3_800_000;
+
+    #[test]
+    fn test_init_and_read_data() {
+        let mut sensor = Ms5525dso::new(Ms5525Mock::new(C, D1, D2));
+        sensor.set_oversampling(Oversampling::Osr256);
+        assert_eq!(
+            block_on(sensor.read_data()),
+            Err(Ms5525dsoError::NotInitialized)
+        );
+        assert_eq!(block_on(sensor.init()).unwrap().c, C);
+
+        let reading = block_on(sensor.read_data()).unwrap();
+        let expected = compensate(
+            &Calibration { c: C },
+            &Ms5525Range::Pp001ds.q_coefficients(),
+            D1,
+            D2,
+            false,
+        );
+        assert_eq!(reading, to_reading(&expected));
+
+        let i2c = sensor.release();
+        let commands = i2c.commands();
+        assert_eq!(commands[0], CMD_RESET);
+        assert_eq!(
+            commands[commands.len() - 4..],
+            [0x40, CMD_ADC_READ, 0x50, CMD_ADC_READ]
+        );
+    }
+
+    #[test]
+    fn test_init_rejects_corrupt_prom() {
+        let mut i2c = Ms5525Mock::new(C, D1, D2);
+        i2c.prom[5] ^= 0x0001;
+        let mut sensor = Ms5525dso::new(i2c);
+        assert_eq!(
+            block_on(sensor.init()),
+            Err(Ms5525dsoError::PromCrcMismatch)
+        );
+        assert_eq!(sensor.calibration(), None);
+    }
+
+    #[test]
+    fn test_conversion_not_ready() {
+        let mut i2c = Ms5525Mock::new(C, D1, D2);
+        i2c.stalled = true;
+        let mut sensor = Ms5525dso::new(i2c);
+        block_on(sensor.init()).unwrap();
+        assert_eq!(
+            block_on(sensor.read_data()),
+            Err(Ms5525dsoError::ConversionNotReady)
+        );
+    }
+}
@@ -0,0 +1,422 @@
+//! Driver for the MS5525DSO differential pressure sensor.
+//!
+//! The MS5525DSO is the other common pitot sensor next to the MS4525DO. It
+//! uses a 24-bit ADC with selectable oversampling and six factory calibration
+//! coefficients (C1-C6) stored in a CRC4-protected PROM. This module holds the
+//! calibration and compensation maths shared by the blocking and async
+//! drivers, which return the same [`Reading`] type as the MS4525DO so
+//! downstream airspeed code works with either sensor.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use ms4525do::ms5525dso::blocking::Ms5525dso;
+//!
+//! let mut sensor = Ms5525dso::new(i2c);
+//! sensor.init(&mut delay)?;
+//!
+//! let reading = sensor.read_data(&mut delay)?;
+//! println!("Airspeed: {} m/s", reading.airspeed());
+//! ```
+
+#[cfg(feature = "async")]
+pub mod async_api;
+
+#[cfg(feature = "blocking")]
+pub mod blocking;
+
+use crate::common::{Reading, PSI_TO_PA};
+
+#[cfg(feature = "defmt")]
+use defmt::Format;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// 7-bit I2C address with the CSB pin pulled low
+pub const MS5525DSO_ADDR: u8 = 0x76;
+
+/// 7-bit I2C address with the CSB pin pulled high
+pub const MS5525DSO_ADDR_ALT: u8 = 0x77;
+
+/// Reset command
+pub const CMD_RESET: u8 = 0x1E;
+
+/// Start pressure (D1) conversion; add the oversampling offset
+pub const CMD_CONVERT_D1: u8 = 0x40;
+
+/// Start temperature (D2) conversion; add the oversampling offset
+pub const CMD_CONVERT_D2: u8 = 0x50;
+
+/// Read the 24-bit ADC result
+pub const CMD_ADC_READ: u8 = 0x00;
+
+/// Read the first PROM word; word `n` is at `CMD_PROM_READ + 2 * n`
+pub const CMD_PROM_READ: u8 = 0xA0;
+
+/// Number of 16-bit PROM words
+pub const PROM_WORDS: usize = 8;
+
+/// Time to wait after a reset before the PROM can be read, in milliseconds
+pub const RESET_DELAY_MS: u32 = 3;
+
+/// Pressure resolution of the compensated output, in PSI per count
+pub const PRESSURE_LSB_PSI: f32 = 0.0001;
+
+/// Errors that can occur during MS5525DSO sensor operations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Ms5525dsoError {
+    /// I2C communication error occurred.
+    I2cError,
+
+    /// The PROM contents do not match their CRC4 checksum.
+    ///
+    /// The calibration coefficients cannot be trusted. Try resetting the
+    /// sensor and reading the PROM again.
+    PromCrcMismatch,
+
+    /// The ADC returned 0, meaning the conversion was not finished or was
+    /// interrupted by another command.
+    ConversionNotReady,
+
+    /// `read_data` was called before `init` loaded the calibration.
+    NotInitialized,
+}
+
+impl core::fmt::Display for Ms5525dsoError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Ms5525dsoError::I2cError => write!(f, "I2C communication error"),
+            Ms5525dsoError::PromCrcMismatch => write!(f, "PROM CRC mismatch"),
+            Ms5525dsoError::ConversionNotReady => write!(f, "ADC conversion not ready"),
+            Ms5525dsoError::NotInitialized => write!(f, "Sensor not initialized"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Ms5525dsoError {}
+
+/// ADC oversampling ratio.
+///
+/// Higher ratios reduce noise at the cost of longer conversion times.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Oversampling {
+    /// OSR 256 (0.54 ms conversion)
+    Osr256,
+    /// OSR 512 (1.06 ms conversion)
+    Osr512,
+    /// OSR 1024 (2.08 ms conversion)
+    Osr1024,
+    /// OSR 2048 (4.13 ms conversion)
+    Osr2048,
+    /// OSR 4096 (8.22 ms conversion)
+    #[default]
+    Osr4096,
+}
+
+impl Oversampling {
+    /// Returns the offset added to the conversion commands.
+    pub fn command_offset(&self) -> u8 {
+        match self {
+            Oversampling::Osr256 => 0x00,
+            Oversampling::Osr512 => 0x02,
+            Oversampling::Osr1024 => 0x04,
+            Oversampling::Osr2048 => 0x06,
+            Oversampling::Osr4096 => 0x08,
+        }
+    }
+
+    /// Returns the time to wait for a conversion to finish, in microseconds.
+    ///
+    /// Datasheet maximum conversion time plus a small margin.
+    pub fn conversion_time_us(&self) -> u32 {
+        match self {
+            Oversampling::Osr256 => 600,
+            Oversampling::Osr512 => 1_170,
+            Oversampling::Osr1024 => 2_280,
+            Oversampling::Osr2048 => 4_540,
+            Oversampling::Osr4096 => 9_040,
+        }
+    }
+}
+
+/// Range-specific exponents of the compensation equations.
+///
+/// Each MS5525DSO pressure range uses its own set of power-of-two scale
+/// factors Q1-Q6, listed in the datasheet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct QCoefficients {
+    /// Pressure sensitivity exponent
+    pub q1: u8,
+    /// Pressure offset exponent
+    pub q2: u8,
+    /// Temperature coefficient of sensitivity exponent
+    pub q3: u8,
+    /// Temperature coefficient of offset exponent
+    pub q4: u8,
+    /// Reference temperature exponent
+    pub q5: u8,
+    /// Temperature coefficient of temperature exponent
+    pub q6: u8,
+}
+
+/// MS5525DSO pressure range variant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Ms5525Range {
+    /// MS5525DSO-pp001DS, ±1 PSI
+    #[default]
+    Pp001ds,
+    /// MS5525DSO-pp002DS, ±2 PSI
+    Pp002ds,
+    /// MS5525DSO-pp005DS, ±5 PSI
+    Pp005ds,
+    /// Any other variant, with Q coefficients taken from its datasheet
+    Custom(QCoefficients),
+}
+
+impl Ms5525Range {
+    /// Returns the compensation exponents of this range.
+    pub fn q_coefficients(&self) -> QCoefficients {
+        let q = |q1, q2, q3, q4, q5, q6| QCoefficients {
+            q1,
+            q2,
+            q3,
+            q4,
+            q5,
+            q6,
+        };
+        match self {
+            Ms5525Range::Pp001ds => q(15, 17, 7, 5, 7, 21),
+            Ms5525Range::Pp002ds => q(14, 16, 8, 6, 7, 22),
+            Ms5525Range::Pp005ds => q(16, 17, 6, 5, 7, 22),
+            Ms5525Range::Custom(q) => *q,
+        }
+    }
+}
+
+/// Factory calibration coefficients read from the PROM.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Calibration {
+    /// C1-C6, in datasheet order
+    pub c: [u16; 6],
+}
+
+impl Calibration {
+    /// Validates the raw PROM words and extracts the calibration coefficients.
+    ///
+    /// # Arguments
+    ///
+    /// * `prom` - All eight PROM words, as read from the sensor
+    ///
+    /// # Errors
+    ///
+    /// * `Ms5525dsoError::PromCrcMismatch` - The PROM fails its CRC4 check
+    pub fn from_prom(prom: &[u16; PROM_WORDS]) -> Result<Self, Ms5525dsoError> {
+        if crc4(prom) != (prom[7] & 0x000F) as u8 {
+            return Err(Ms5525dsoError::PromCrcMismatch);
+        }
+        let mut c = [0u16; 6];
+        c.copy_from_slice(&prom[1..7]);
+        Ok(Self { c })
+    }
+}
+
+/// Computes the CRC4 of the PROM, as described in application note AN520.
+///
+/// The checksum covers all eight words with the low nibble of word 7 (where
+/// the CRC itself is stored) treated as zero.
+///
+/// # Returns
+///
+/// 4-bit CRC value
+pub fn crc4(prom: &[u16; PROM_WORDS]) -> u8 {
+    let mut words = *prom;
+    words[7] &= 0xFF00;
+
+    let mut remainder: u16 = 0;
+    for count in 0..16 {
+        let word = words[count >> 1];
+        remainder ^= if count & 1 == 1 {
+            word & 0x00FF
+        } else {
+            word >> 8
+        };
+        for _ in 0..8 {
+            remainder = if remainder & 0x8000 != 0 {
+                (remainder << 1) ^ 0x3000
+            } else {
+                remainder << 1
+            };
+        }
+    }
+    ((remainder >> 12) & 0x000F) as u8
+}
+
+/// Compensated sensor output in datasheet units.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+pub struct Compensated {
+    /// Pressure in units of [`PRESSURE_LSB_PSI`]
+    pub pressure: i64,
+    /// Temperature in hundredths of a degree Celsius
+    pub temperature: i64,
+}
+
+/// Runs the datasheet compensation on raw ADC results.
+///
+/// The first-order equations are those of the MS5525DSO datasheet. Its
+/// datasheet specifies no second-order correction, so the optional one uses
+/// the low-temperature terms of the MS5607 datasheet (T2 = dT²/2³¹,
+/// OFF2 = 61·(TEMP−2000)²/2⁴, SENS2 = 2·(TEMP−2000)² below 20°C, plus
+/// 15·(TEMP+1500)² and 8·(TEMP+1500)² below -15°C). They are not
+/// characterized for the MS5525DSO and only apply if enabled.
+///
+/// # Arguments
+///
+/// * `calibration` - Calibration coefficients from the PROM
+/// * `q` - Compensation exponents of the sensor's pressure range
+/// * `d1` - Raw 24-bit pressure conversion result
+/// * `d2` - Raw 24-bit temperature conversion result
+/// * `second_order` - Whether to apply second-order temperature compensation
+///
+/// # Returns
+///
+/// Compensated pressure and temperature in datasheet units
+pub fn compensate(
+    calibration: &Calibration,
+    q: &QCoefficients,
+    d1: u32,
+    d2: u32,
+    second_order: bool,
+) -> Compensated {
+    let [c1, c2, c3, c4, c5, c6] = calibration.c.map(i64::from);
+    let (d1, d2) = (d1 as i64, d2 as i64);
+
+    let pow2 = |n: u8| 1i64 << n;
+
+    let dt = d2 - c5 * pow2(q.q5);
+    let mut temperature = 2000 + dt * c6 / pow2(q.q6);
+    let mut offset = c2 * pow2(q.q2) + c4 * dt / pow2(q.q4);
+    let mut sensitivity = c1 * pow2(q.q1) + c3 * dt / pow2(q.q3);
+
+    if second_order && temperature < 2000 {
+        let low = (temperature - 2000) * (temperature - 2000);
+        let t2 = dt * dt / pow2(31);
+        let mut off2 = 61 * low / pow2(4);
+        let mut sens2 = 2 * low;
+        if temperature < -1500 {
+            let very_low = (temperature + 1500) * (temperature + 1500);
+            off2 += 15 * very_low;
+            sens2 += 8 * very_low;
+        }
+        temperature -= t2;
+        offset -= off2;
+        sensitivity -= sens2;
+    }
+
+    let pressure = (d1 * sensitivity / pow2(21) - offset) / pow2(15);
+    Compensated {
+        pressure,
+        temperature,
+    }
+}
+
+/// Converts compensated output into a [`Reading`].
+pub fn to_reading(compensated: &Compensated) -> Reading {
+    Reading {
+        differential_pressure_pa: compensated.pressure as f32 * PRESSURE_LSB_PSI * PSI_TO_PA,
+        temperature_c: compensated.temperature as f32 / 100.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// PROM with a valid CRC (low nibble of word 7 set via `crc4`)
+    fn prom(c: [u16; 6]) -> [u16; PROM_WORDS] {
+        let mut prom = [0x0012, c[0], c[1], c[2], c[3], c[4], c[5], 0x5A00];
+        prom[7] |= crc4(&prom) as u16;
+        prom
+    }
+
+    #[test]
+    fn test_crc4_detects_corruption() {
+        let mut words = prom([36402, 39473, 40393, 29804, 29267, 26291]);
+        assert!(Calibration::from_prom(&words).is_ok());
+
+        words[3] ^= 0x0100;
+        assert_eq!(
+            Calibration::from_prom(&words),
+            Err(Ms5525dsoError::PromCrcMismatch)
+        );
+    }
+
+    #[test]
+    fn test_crc4_reference() {
+        // Example PROM from application note AN520
+        let prom = [
+            0x3132, 0x3334, 0x3536, 0x3738, 0x3940, 0x4142, 0x4344, 0x4500,
+        ];
+        assert_eq!(crc4(&prom), 0xB);
+    }
+
+    #[test]
+    fn test_compensate_reference() {
+        // Datasheet example of the MS5611, which uses the same first-order
+        // equations with Q = (15, 16, 8, 7, 8, 23)
+        let calibration = Calibration {
+            c: [40127, 36924, 23317, 23282, 33464, 28312],
+        };
+        let q = QCoefficients {
+            q1: 15,
+            q2: 16,
+            q3: 8,
+            q4: 7,
+            q5: 8,
+            q6: 23,
+        };
+        let result = compensate(&calibration, &q, 9085466, 8569150, false);
+        assert_eq!(result.temperature, 2007);
+        assert_eq!(result.pressure, 100009);
+
+        // Above 20°C the second-order correction has no effect
+        assert_eq!(compensate(&calibration, &q, 9085466, 8569150, true), result);
+    }
+
+    #[test]
+    fn test_second_order_below_20c() {
+        let calibration = Calibration {
+            c: [40127, 36924, 23317, 23282, 33464, 28312],
+        };
+        let q = Ms5525Range::Pp001ds.q_coefficients();
+        // D2 well below the reference temperature
+        let d2 = (33464u32 << 7) - 200_000;
+        let first = compensate(&calibration, &q, 8_000_000, d2, false);
+        let second = compensate(&calibration, &q, 8_000_000, d2, true);
+        assert!(first.temperature < 2000);
+        assert!(second.temperature <= first.temperature);
+        assert_ne!(second.pressure, first.pressure);
+    }
+
+    #[test]
+    fn test_to_reading() {
+        let reading = to_reading(&Compensated {
+            pressure: 10_000,
+            temperature: 2150,
+        });
+        assert!((reading.differential_pressure_pa - PSI_TO_PA).abs() < 0.01);
+        assert!((reading.temperature_c - 21.5).abs() < 1e-6);
+    }
+}
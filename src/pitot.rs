@@ -0,0 +1,262 @@
+//! Reversed pitot tube detection and correction.
+//!
+//! With the total and static tubes swapped, a pitot reports a negative
+//! differential pressure in forward flight. Because [`crate::calculate_airspeed`]
+//! uses the magnitude of ΔP, this looks like normal flight. The
+//! [`ReversedPitotDetector`] watches for sustained negative ΔP while the
+//! vehicle is known to be moving forward (e.g. from GNSS ground speed) and
+//! raises a "tubes reversed" diagnostic, with optional automatic correction.
+//!
+//! Use [`crate::calculate_airspeed_signed`] to keep the sign of ΔP, for
+//! example to see a tailwind while hovering.
+//!
+//! # Example
+//!
+//! ```
+//! use ms4525do::pitot::{PitotDiagnostic, ReversedPitotConfig, ReversedPitotDetector};
+//!
+//! let mut detector = ReversedPitotDetector::new(ReversedPitotConfig {
+//!     auto_swap: true,
+//!     ..ReversedPitotConfig::default()
+//! });
+//!
+//! let mut diagnostic = PitotDiagnostic::Normal;
+//! for _ in 0..100 {
+//!     // -150 Pa at 15 m/s ground speed
+//!     diagnostic = detector.update(-150.0, 15.0);
+//! }
+//! assert_eq!(diagnostic, PitotDiagnostic::TubesReversed);
+//! assert_eq!(detector.correct(-150.0), 150.0);
+//! ```
+
+#[cfg(feature = "defmt")]
+use defmt::{warn, Format};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Result of the reversed pitot check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum PitotDiagnostic {
+    /// No sign of reversed tubes
+    Normal,
+    /// Negative ΔP while moving forward, not yet sustained long enough
+    Suspect,
+    /// Sustained negative ΔP while moving forward: the tubes are reversed
+    TubesReversed,
+}
+
+/// Configuration of the [`ReversedPitotDetector`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ReversedPitotConfig {
+    /// Minimum forward ground speed for a sample to count, in m/s
+    pub min_ground_speed_m_s: f32,
+    /// ΔP must be below the negative of this value for a sample to count, in Pascals
+    pub negative_threshold_pa: f32,
+    /// Number of consecutive qualifying samples before the tubes are reported reversed
+    pub required_samples: u16,
+    /// Negate ΔP in [`ReversedPitotDetector::correct`] once the tubes are reported reversed
+    pub auto_swap: bool,
+}
+
+impl Default for ReversedPitotConfig {
+    /// 8 m/s ground speed, -20 Pa, 50 samples (1 s at 50 Hz), no auto-swap.
+    fn default() -> Self {
+        Self {
+            min_ground_speed_m_s: 8.0,
+            negative_threshold_pa: 20.0,
+            required_samples: 50,
+            auto_swap: false,
+        }
+    }
+}
+
+/// Detects pitot tubes that have been connected the wrong way round.
+#[derive(Debug, Clone)]
+pub struct ReversedPitotDetector {
+    config: ReversedPitotConfig,
+    count: u16,
+    reversed: bool,
+}
+
+impl ReversedPitotDetector {
+    /// Creates a detector with the given configuration.
+    pub fn new(config: ReversedPitotConfig) -> Self {
+        Self {
+            config,
+            count: 0,
+            reversed: false,
+        }
+    }
+
+    /// Returns the detector configuration.
+    pub fn config(&self) -> &ReversedPitotConfig {
+        &self.config
+    }
+
+    /// Feeds a new sample to the detector.
+    ///
+    /// Samples taken below the minimum ground speed neither count towards nor
+    /// against detection, so hovering in a tailwind is not flagged. Once
+    /// reported, the reversed state is latched until [`Self::reset`].
+    ///
+    /// # Arguments
+    ///
+    /// * `pressure_pa` - Uncorrected differential pressure in Pascals
+    /// * `ground_speed_m_s` - Forward ground speed, e.g. from GNSS, in m/s
+    ///
+    /// # Returns
+    ///
+    /// The current diagnostic
+    pub fn update(&mut self, pressure_pa: f32, ground_speed_m_s: f32) -> PitotDiagnostic {
+        if !self.reversed && ground_speed_m_s >= self.config.min_ground_speed_m_s {
+            if pressure_pa < -self.config.negative_threshold_pa {
+                self.count = self.count.saturating_add(1);
+            } else {
+                self.count = 0;
+            }
+
+            if self.count >= self.config.required_samples {
+                self.reversed = true;
+
+                #[cfg(feature = "defmt")]
+                warn!(
+                    "Pitot tubes reversed: {} Pa at {} m/s",
+                    pressure_pa, ground_speed_m_s
+                );
+
+                #[cfg(all(not(feature = "defmt"), feature = "log"))]
+                log::warn!(
+                    "Pitot tubes reversed: {} Pa at {} m/s",
+                    pressure_pa,
+                    ground_speed_m_s
+                );
+            }
+        }
+        self.diagnostic()
+    }
+
+    /// Returns the current diagnostic without feeding a sample.
+    pub fn diagnostic(&self) -> PitotDiagnostic {
+        if self.reversed {
+            PitotDiagnostic::TubesReversed
+        } else if self.count > 0 {
+            PitotDiagnostic::Suspect
+        } else {
+            PitotDiagnostic::Normal
+        }
+    }
+
+    /// Returns true once the tubes have been reported reversed.
+    pub fn is_reversed(&self) -> bool {
+        self.reversed
+    }
+
+    /// Applies the auto-swap correction to a differential pressure.
+    ///
+    /// # Returns
+    ///
+    /// The negated pressure if the tubes are reported reversed and
+    /// `auto_swap` is enabled, otherwise the pressure unchanged
+    pub fn correct(&self, pressure_pa: f32) -> f32 {
+        if self.reversed && self.config.auto_swap {
+            -pressure_pa
+        } else {
+            pressure_pa
+        }
+    }
+
+    /// Clears the detection state, e.g. after the tubes have been fixed.
+    pub fn reset(&mut self) {
+        self.count = 0;
+        self.reversed = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detector(auto_swap: bool) -> ReversedPitotDetector {
+        ReversedPitotDetector::new(ReversedPitotConfig {
+            required_samples: 3,
+            auto_swap,
+            ..ReversedPitotConfig::default()
+        })
+    }
+
+    #[test]
+    fn test_detects_sustained_reversal() {
+        let mut detector = detector(false);
+        assert_eq!(detector.update(-100.0, 15.0), PitotDiagnostic::Suspect);
+        assert_eq!(detector.update(-100.0, 15.0), PitotDiagnostic::Suspect);
+        assert_eq!(
+            detector.update(-100.0, 15.0),
+            PitotDiagnostic::TubesReversed
+        );
+
+        // Latched, and no correction without auto-swap
+        assert_eq!(detector.update(100.0, 15.0), PitotDiagnostic::TubesReversed);
+        assert_eq!(detector.correct(-100.0), -100.0);
+
+        detector.reset();
+        assert_eq!(detector.diagnostic(), PitotDiagnostic::Normal);
+    }
+
+    #[test]
+    fn test_ignores_hover_and_glitches() {
+        let mut detector = detector(true);
+        // Tailwind while hovering
+        for _ in 0..10 {
+            assert_eq!(detector.update(-100.0, 0.5), PitotDiagnostic::Normal);
+        }
+        // Interrupted negative readings in forward flight
+        detector.update(-100.0, 15.0);
+        detector.update(-100.0, 15.0);
+        assert_eq!(detector.update(80.0, 15.0), PitotDiagnostic::Normal);
+        assert_eq!(detector.correct(-100.0), -100.0);
+    }
+
+    #[test]
+    fn test_auto_swap() {
+        let mut detector = detector(true);
+        for _ in 0..3 {
+            detector.update(-100.0, 15.0);
+        }
+        assert!(detector.is_reversed());
+        assert_eq!(detector.correct(-100.0), 100.0);
+    }
+
+    #[test]
+    fn test_raw_ms4525do_counts() {
+        use crate::transfer::{Ms4525doTransfer, TransferFunction};
+        use crate::Reading;
+
+        // Port 1 at the higher pressure reads above mid-scale (8191.5 counts)
+        let forward = Reading {
+            differential_pressure_pa: Ms4525doTransfer::default().pressure_pa(8342),
+            temperature_c: 20.0,
+        };
+        assert!(forward.differential_pressure_pa > 150.0);
+        assert!(forward.airspeed_signed() > 15.0);
+
+        let mut detector = detector(false);
+        for _ in 0..10 {
+            assert_eq!(
+                detector.update(forward.differential_pressure_pa, 15.0),
+                PitotDiagnostic::Normal
+            );
+        }
+
+        // Swapped tubes read below mid-scale
+        let reversed = Ms4525doTransfer::default().pressure_pa(8041);
+        for _ in 0..3 {
+            detector.update(reversed, 15.0);
+        }
+        assert!(detector.is_reversed());
+    }
+}
@@ -0,0 +1,658 @@
+//! I2C bus recovery for a device stuck holding SDA low.
+//!
+//! If the controller resets or browns out in the middle of a read, the
+//! sensor may still be shifting out a `0` bit and hold SDA low indefinitely.
+//! Every later transaction then fails with an arbitration or bus error until
+//! the sensor is power cycled. The standard recovery (I2C specification,
+//! section 3.1.16) is to clock SCL until the device releases SDA (at most 9
+//! pulses) and then generate a STOP condition.
+//!
+//! [`BusRecovery`] performs that sequence on the SCL/SDA pins driven as
+//! GPIOs. [`RecoveringI2c`] wraps the I2C peripheral, counts consecutive
+//! errors and runs a [`RecoverBus`] hook automatically once a threshold is
+//! reached. The hook is handed the I2C peripheral, so it can take the pins
+//! back as GPIOs and build a new peripheral afterwards, which is
+//! HAL-specific.
+//!
+//! A driver created with `with_bus_recovery` wraps its peripheral in a
+//! [`RecoveringI2c`] and re-initializes the sensor by itself after a read
+//! attempt during which the bus was recovered. A driver created on a
+//! [`RecoveringI2c`] directly leaves that to `reinit_if_recovered`.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use ms4525do::blocking::Ms4525do;
+//! use ms4525do::recovery::BusRecovery;
+//!
+//! let hook = |i2c: I2c| {
+//!     // HAL-specific: free the pins and switch them to open-drain GPIOs
+//!     let (peripheral, scl, sda) = i2c.free();
+//!     let mut recovery = BusRecovery::new(scl.into_open_drain(), sda.into_open_drain(), Delay);
+//!     let recovered = recovery.recover().is_ok();
+//!     let (scl, sda, _) = recovery.release();
+//!     // HAL-specific: give the pins back to a new I2C peripheral
+//!     let i2c = I2c::new(peripheral, scl.into_i2c(), sda.into_i2c());
+//!     if recovered { Ok(i2c) } else { Err(i2c) }
+//! };
+//!
+//! // Recovers the bus after 5 failed transactions in a row, then runs the
+//! // re-init hook
+//! let mut sensor = Ms4525do::new(i2c)
+//!     .with_reinit_hook(power_cycle)
+//!     .with_bus_recovery(hook, 5);
+//! loop {
+//!     let reading = sensor.read_data(&mut delay);
+//! }
+//! ```
+
+#[cfg(any(feature = "blocking", feature = "async"))]
+use crate::stuck::ReinitHook;
+#[cfg(any(feature = "blocking", feature = "async"))]
+use crate::transfer::TransferFunction;
+use crate::Ms4525doError;
+use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::{InputPin, OutputPin};
+use embedded_hal::i2c::{ErrorType, Operation};
+
+#[cfg(feature = "defmt")]
+use defmt::{warn, Format};
+
+/// Maximum number of SCL pulses needed to release SDA (8 data bits and an ACK)
+pub const MAX_RECOVERY_PULSES: u8 = 9;
+
+/// Half of an SCL period at 100 kHz, in microseconds
+pub const HALF_PERIOD_US: u32 = 5;
+
+/// Errors that can occur during bus recovery.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+pub enum RecoveryError {
+    /// A GPIO operation on SCL or SDA failed.
+    PinError,
+
+    /// SDA is still held low after clocking out all pulses.
+    ///
+    /// The device cannot be recovered from the bus; power cycle it.
+    StillStuck,
+}
+
+impl core::fmt::Display for RecoveryError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            RecoveryError::PinError => write!(f, "GPIO pin error"),
+            RecoveryError::StillStuck => write!(f, "SDA still held low"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for RecoveryError {}
+
+/// Clocks a stuck device off the bus using the SCL and SDA pins as GPIOs.
+///
+/// # Type Parameters
+///
+/// * `SCL` - Open-drain output driving the clock line
+/// * `SDA` - Open-drain output that can also be read back
+/// * `D` - Delay provider for the bit timing
+pub struct BusRecovery<SCL, SDA, D> {
+    scl: SCL,
+    sda: SDA,
+    delay: D,
+}
+
+impl<SCL, SDA, D> BusRecovery<SCL, SDA, D>
+where
+    SCL: OutputPin,
+    SDA: OutputPin + InputPin,
+    D: DelayNs,
+{
+    /// Creates a recovery helper from the bus pins.
+    pub fn new(scl: SCL, sda: SDA, delay: D) -> Self {
+        Self { scl, sda, delay }
+    }
+
+    /// Runs the recovery sequence.
+    ///
+    /// Releases SDA, then clocks SCL until SDA reads high (at most
+    /// [`MAX_RECOVERY_PULSES`] times) and finishes with a STOP condition.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(u8)` - Number of SCL pulses that were needed
+    /// * `Err(RecoveryError)` - Error if the pins fail or SDA stays low
+    pub fn recover(&mut self) -> Result<u8, RecoveryError> {
+        self.sda.set_high().map_err(|_| RecoveryError::PinError)?;
+        self.scl.set_high().map_err(|_| RecoveryError::PinError)?;
+        self.delay.delay_us(HALF_PERIOD_US);
+
+        let mut pulses = 0;
+        while !self.sda_is_high()? {
+            if pulses == MAX_RECOVERY_PULSES {
+                return Err(RecoveryError::StillStuck);
+            }
+            self.scl.set_low().map_err(|_| RecoveryError::PinError)?;
+            self.delay.delay_us(HALF_PERIOD_US);
+            self.scl.set_high().map_err(|_| RecoveryError::PinError)?;
+            self.delay.delay_us(HALF_PERIOD_US);
+            pulses += 1;
+        }
+
+        // STOP: SDA rises while SCL is high
+        self.scl.set_low().map_err(|_| RecoveryError::PinError)?;
+        self.delay.delay_us(HALF_PERIOD_US);
+        self.sda.set_low().map_err(|_| RecoveryError::PinError)?;
+        self.delay.delay_us(HALF_PERIOD_US);
+        self.scl.set_high().map_err(|_| RecoveryError::PinError)?;
+        self.delay.delay_us(HALF_PERIOD_US);
+        self.sda.set_high().map_err(|_| RecoveryError::PinError)?;
+        self.delay.delay_us(HALF_PERIOD_US);
+
+        if self.sda_is_high()? {
+            Ok(pulses)
+        } else {
+            Err(RecoveryError::StillStuck)
+        }
+    }
+
+    /// Consumes the helper and returns the pins and delay provider.
+    pub fn release(self) -> (SCL, SDA, D) {
+        (self.scl, self.sda, self.delay)
+    }
+
+    fn sda_is_high(&mut self) -> Result<bool, RecoveryError> {
+        self.sda.is_high().map_err(|_| RecoveryError::PinError)
+    }
+}
+
+/// Hook run by [`RecoveringI2c`] to recover the bus and re-initialize the peripheral.
+///
+/// Implemented for any `FnMut(I2C) -> Result<I2C, I2C>` closure.
+pub trait RecoverBus<I2C> {
+    /// Recovers the bus.
+    ///
+    /// # Arguments
+    ///
+    /// * `i2c` - The I2C peripheral, which owns the SCL and SDA pins
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(I2C)` - The re-initialized peripheral, if the bus was recovered
+    /// * `Err(I2C)` - The peripheral to keep using, if the recovery failed
+    fn recover_bus(&mut self, i2c: I2C) -> Result<I2C, I2C>;
+}
+
+impl<I2C, F: FnMut(I2C) -> Result<I2C, I2C>> RecoverBus<I2C> for F {
+    fn recover_bus(&mut self, i2c: I2C) -> Result<I2C, I2C> {
+        self(i2c)
+    }
+}
+
+/// Counts consecutive I2C errors and decides when to recover the bus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+pub struct RecoveryTrigger {
+    threshold: u8,
+    consecutive_errors: u8,
+}
+
+impl RecoveryTrigger {
+    /// Creates a trigger that fires after `threshold` consecutive I2C errors.
+    pub fn new(threshold: u8) -> Self {
+        Self {
+            threshold: threshold.max(1),
+            consecutive_errors: 0,
+        }
+    }
+
+    /// Records the outcome of a driver read.
+    ///
+    /// Only `I2cError` counts; any other outcome shows the bus is working and
+    /// clears the count.
+    ///
+    /// # Returns
+    ///
+    /// `true` when the bus should be recovered, after which the count restarts
+    pub fn record<T>(&mut self, result: &Result<T, Ms4525doError>) -> bool {
+        self.record_bus_result(matches!(result, Err(Ms4525doError::I2cError)))
+    }
+
+    /// Returns the number of consecutive I2C errors seen.
+    pub fn consecutive_errors(&self) -> u8 {
+        self.consecutive_errors
+    }
+
+    fn record_bus_result(&mut self, failed: bool) -> bool {
+        if !failed {
+            self.consecutive_errors = 0;
+            return false;
+        }
+        self.consecutive_errors = self.consecutive_errors.saturating_add(1);
+        if self.consecutive_errors >= self.threshold {
+            self.consecutive_errors = 0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// I2C wrapper that recovers the bus after repeated errors.
+///
+/// Every failed transaction is passed through to the driver unchanged. Once
+/// `threshold` transactions in a row have failed, the [`RecoverBus`] hook is
+/// run before the error is returned, so the next read uses a recovered bus.
+/// A successful recovery is flagged until [`RecoveringI2c::take_recovered`]
+/// picks it up, which a driver created with `with_bus_recovery` does after
+/// every failed read attempt.
+pub struct RecoveringI2c<I2C, R> {
+    /// `None` only while the hook holds the peripheral
+    i2c: Option<I2C>,
+    hook: R,
+    trigger: RecoveryTrigger,
+    recovered: bool,
+    recoveries: u32,
+    failed_recoveries: u32,
+}
+
+impl<I2C, R: RecoverBus<I2C>> RecoveringI2c<I2C, R> {
+    /// Wraps an I2C peripheral.
+    ///
+    /// # Arguments
+    ///
+    /// * `i2c` - The I2C peripheral
+    /// * `hook` - Recovery hook, typically running [`BusRecovery::recover`]
+    /// * `threshold` - Number of consecutive failed transactions that trigger recovery
+    pub fn new(i2c: I2C, hook: R, threshold: u8) -> Self {
+        Self {
+            i2c: Some(i2c),
+            hook,
+            trigger: RecoveryTrigger::new(threshold),
+            recovered: false,
+            recoveries: 0,
+            failed_recoveries: 0,
+        }
+    }
+
+    /// Returns the number of successful recoveries.
+    pub fn recoveries(&self) -> u32 {
+        self.recoveries
+    }
+
+    /// Returns the number of recoveries whose hook reported failure.
+    pub fn failed_recoveries(&self) -> u32 {
+        self.failed_recoveries
+    }
+
+    /// Returns whether the bus was recovered since the last call.
+    pub fn take_recovered(&mut self) -> bool {
+        core::mem::take(&mut self.recovered)
+    }
+
+    /// Consumes the wrapper and returns the I2C peripheral and hook.
+    pub fn release(self) -> (I2C, R) {
+        (Self::present(self.i2c), self.hook)
+    }
+
+    fn i2c(&mut self) -> &mut I2C {
+        Self::present(self.i2c.as_mut())
+    }
+
+    fn present<P>(i2c: Option<P>) -> P {
+        i2c.expect("I2C peripheral not returned by a panicking recovery hook")
+    }
+
+    fn after_transaction(&mut self, failed: bool) {
+        if !self.trigger.record_bus_result(failed) {
+            return;
+        }
+
+        #[cfg(feature = "defmt")]
+        warn!("I2C bus stuck, running recovery");
+
+        #[cfg(all(not(feature = "defmt"), feature = "log"))]
+        log::warn!("I2C bus stuck, running recovery");
+
+        let i2c = Self::present(self.i2c.take());
+        let i2c = match self.hook.recover_bus(i2c) {
+            Ok(i2c) => {
+                self.recovered = true;
+                self.recoveries = self.recoveries.wrapping_add(1);
+                i2c
+            }
+            Err(i2c) => {
+                self.failed_recoveries = self.failed_recoveries.wrapping_add(1);
+                i2c
+            }
+        };
+        self.i2c = Some(i2c);
+    }
+}
+
+impl<I2C: ErrorType, R> ErrorType for RecoveringI2c<I2C, R> {
+    type Error = I2C::Error;
+}
+
+impl<I2C: embedded_hal::i2c::I2c, R: RecoverBus<I2C>> embedded_hal::i2c::I2c
+    for RecoveringI2c<I2C, R>
+{
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        let result = self.i2c().transaction(address, operations);
+        self.after_transaction(result.is_err());
+        result
+    }
+}
+
+#[cfg(feature = "async")]
+impl<I2C: embedded_hal_async::i2c::I2c, R: RecoverBus<I2C>> embedded_hal_async::i2c::I2c
+    for RecoveringI2c<I2C, R>
+{
+    async fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        let result = self.i2c().transaction(address, operations).await;
+        self.after_transaction(result.is_err());
+        result
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl<I2C, R, T, H> crate::blocking::Ms4525do<RecoveringI2c<I2C, R>, T, H>
+where
+    I2C: embedded_hal::i2c::I2c,
+    R: RecoverBus<I2C>,
+    T: TransferFunction,
+    H: ReinitHook,
+{
+    /// Re-initializes the sensor if the bus was recovered since the last call.
+    ///
+    /// A driver created with [`Ms4525do::with_bus_recovery`](crate::blocking::Ms4525do::with_bus_recovery)
+    /// does this after every failed read attempt; call it after using the
+    /// driver's bus in other ways, or on a driver created on a
+    /// [`RecoveringI2c`] directly. See [`Ms4525do::reinit`](crate::blocking::Ms4525do::reinit).
+    ///
+    /// # Returns
+    ///
+    /// `true` if the sensor was re-initialized
+    pub fn reinit_if_recovered(&mut self) -> bool {
+        let recovered = self.i2c_mut().take_recovered();
+        if recovered {
+            self.reinit();
+        }
+        recovered
+    }
+}
+
+#[cfg(feature = "async")]
+impl<I2C, R, T, H> crate::async_api::Ms4525do<RecoveringI2c<I2C, R>, T, H>
+where
+    I2C: embedded_hal_async::i2c::I2c,
+    R: RecoverBus<I2C>,
+    T: TransferFunction,
+    H: ReinitHook,
+{
+    /// Re-initializes the sensor if the bus was recovered since the last call.
+    ///
+    /// A driver created with [`Ms4525do::with_bus_recovery`](crate::async_api::Ms4525do::with_bus_recovery)
+    /// does this after every failed read attempt; call it after using the
+    /// driver's bus in other ways, or on a driver created on a
+    /// [`RecoveringI2c`] directly. See [`Ms4525do::reinit`](crate::async_api::Ms4525do::reinit).
+    ///
+    /// # Returns
+    ///
+    /// `true` if the sensor was re-initialized
+    pub fn reinit_if_recovered(&mut self) -> bool {
+        let recovered = self.i2c_mut().take_recovered();
+        if recovered {
+            self.reinit();
+        }
+        recovered
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::NoDelay;
+    use core::cell::Cell;
+    use core::convert::Infallible;
+    use embedded_hal::i2c::ErrorKind;
+
+    /// Open-drain bus with a device that holds SDA low for a number of SCL pulses
+    struct StuckBus {
+        scl: Cell<bool>,
+        sda_released: Cell<bool>,
+        /// Remaining falling SCL edges before the device releases SDA; `None` = forever
+        stuck_for: Cell<Option<u8>>,
+        stop_seen: Cell<bool>,
+    }
+
+    impl StuckBus {
+        fn new(stuck_for: Option<u8>) -> Self {
+            Self {
+                scl: Cell::new(true),
+                sda_released: Cell::new(true),
+                stuck_for: Cell::new(stuck_for),
+                stop_seen: Cell::new(false),
+            }
+        }
+
+        fn device_holds_sda(&self) -> bool {
+            self.stuck_for.get() != Some(0)
+        }
+
+        fn sda(&self) -> bool {
+            self.sda_released.get() && !self.device_holds_sda()
+        }
+    }
+
+    struct Scl<'a>(&'a StuckBus);
+    struct Sda<'a>(&'a StuckBus);
+
+    impl embedded_hal::digital::ErrorType for Scl<'_> {
+        type Error = Infallible;
+    }
+
+    impl embedded_hal::digital::ErrorType for Sda<'_> {
+        type Error = Infallible;
+    }
+
+    impl OutputPin for Scl<'_> {
+        fn set_low(&mut self) -> Result<(), Infallible> {
+            let bus = self.0;
+            if bus.scl.get() {
+                // The device shifts out its next bit on the falling edge
+                if let Some(n) = bus.stuck_for.get() {
+                    bus.stuck_for.set(Some(n.saturating_sub(1)));
+                }
+            }
+            bus.scl.set(false);
+            Ok(())
+        }
+
+        fn set_high(&mut self) -> Result<(), Infallible> {
+            self.0.scl.set(true);
+            Ok(())
+        }
+    }
+
+    impl OutputPin for Sda<'_> {
+        fn set_low(&mut self) -> Result<(), Infallible> {
+            self.0.sda_released.set(false);
+            Ok(())
+        }
+
+        fn set_high(&mut self) -> Result<(), Infallible> {
+            let bus = self.0;
+            if bus.scl.get() && !bus.sda() {
+                bus.sda_released.set(true);
+                bus.stop_seen.set(bus.sda());
+            } else {
+                bus.sda_released.set(true);
+            }
+            Ok(())
+        }
+    }
+
+    impl InputPin for Sda<'_> {
+        fn is_high(&mut self) -> Result<bool, Infallible> {
+            Ok(self.0.sda())
+        }
+
+        fn is_low(&mut self) -> Result<bool, Infallible> {
+            Ok(!self.0.sda())
+        }
+    }
+
+    #[test]
+    fn test_recovers_stuck_bus() {
+        let bus = StuckBus::new(Some(5));
+        let mut recovery = BusRecovery::new(Scl(&bus), Sda(&bus), NoDelay);
+        assert_eq!(recovery.recover(), Ok(5));
+        assert!(bus.stop_seen.get());
+        assert!(bus.sda());
+    }
+
+    #[test]
+    fn test_idle_bus_needs_no_pulses() {
+        let bus = StuckBus::new(Some(0));
+        let mut recovery = BusRecovery::new(Scl(&bus), Sda(&bus), NoDelay);
+        assert_eq!(recovery.recover(), Ok(0));
+    }
+
+    #[test]
+    fn test_permanently_stuck_bus() {
+        let bus = StuckBus::new(None);
+        let mut recovery = BusRecovery::new(Scl(&bus), Sda(&bus), NoDelay);
+        assert_eq!(recovery.recover(), Err(RecoveryError::StillStuck));
+    }
+
+    /// I2C peripheral that fails while the simulated bus is stuck
+    struct BusI2c<'a>(&'a StuckBus);
+
+    impl ErrorType for BusI2c<'_> {
+        type Error = ErrorKind;
+    }
+
+    impl embedded_hal::i2c::I2c for BusI2c<'_> {
+        fn transaction(
+            &mut self,
+            _address: u8,
+            _operations: &mut [Operation<'_>],
+        ) -> Result<(), ErrorKind> {
+            if self.0.sda() {
+                Ok(())
+            } else {
+                Err(ErrorKind::Bus)
+            }
+        }
+    }
+
+    /// Recovery hook taking the pins back from the peripheral, like a HAL would
+    fn recover(i2c: BusI2c<'_>) -> Result<BusI2c<'_>, BusI2c<'_>> {
+        let bus = i2c.0;
+        match BusRecovery::new(Scl(bus), Sda(bus), NoDelay).recover() {
+            Ok(_) => Ok(BusI2c(bus)),
+            Err(_) => Err(BusI2c(bus)),
+        }
+    }
+
+    #[test]
+    fn test_automatic_recovery() {
+        use embedded_hal::i2c::I2c;
+
+        let bus = StuckBus::new(Some(7));
+        let mut i2c = RecoveringI2c::new(BusI2c(&bus), recover, 3);
+
+        assert!(i2c.write(0x28, &[0]).is_err());
+        assert!(i2c.write(0x28, &[0]).is_err());
+        assert_eq!(i2c.recoveries(), 0);
+        assert!(!i2c.take_recovered());
+        // The third failure triggers recovery, so the next write succeeds
+        assert!(i2c.write(0x28, &[0]).is_err());
+        assert_eq!(i2c.recoveries(), 1);
+        assert!(i2c.write(0x28, &[0]).is_ok());
+        assert!(i2c.take_recovered());
+        assert!(!i2c.take_recovered());
+    }
+
+    #[test]
+    fn test_failed_recovery_keeps_peripheral() {
+        use embedded_hal::i2c::I2c;
+
+        let bus = StuckBus::new(None);
+        let mut i2c = RecoveringI2c::new(BusI2c(&bus), recover, 2);
+
+        for _ in 0..4 {
+            assert!(i2c.write(0x28, &[0]).is_err());
+        }
+        assert_eq!(i2c.recoveries(), 0);
+        assert_eq!(i2c.failed_recoveries(), 2);
+        assert!(!i2c.take_recovered());
+        let (released, _) = i2c.release();
+        assert!(core::ptr::eq(released.0, &bus));
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_driver_reinit_after_recovery() {
+        use crate::blocking::Ms4525do;
+
+        let bus = StuckBus::new(Some(7));
+        let reinits = Cell::new(0);
+        let mut sensor = Ms4525do::new(BusI2c(&bus))
+            .with_reinit_hook(|| reinits.set(reinits.get() + 1))
+            .with_bus_recovery(recover, 3);
+        sensor.set_retry_policy(crate::retry::RetryPolicy::none());
+
+        for _ in 0..2 {
+            assert_eq!(sensor.read_data(&mut NoDelay), Err(Ms4525doError::I2cError));
+            assert_eq!(reinits.get(), 0);
+        }
+        // The third failed request recovers the bus and re-initializes the sensor
+        assert_eq!(sensor.read_data(&mut NoDelay), Err(Ms4525doError::I2cError));
+        assert_eq!(reinits.get(), 1);
+        assert!(!sensor.reinit_if_recovered());
+        assert_eq!(sensor.release().recoveries(), 1);
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_driver_reinit_if_recovered() {
+        use crate::blocking::Ms4525do;
+
+        let bus = StuckBus::new(Some(7));
+        let reinits = Cell::new(0);
+        let mut sensor = Ms4525do::new(RecoveringI2c::new(BusI2c(&bus), recover, 3))
+            .with_reinit_hook(|| reinits.set(reinits.get() + 1));
+        sensor.set_retry_policy(crate::retry::RetryPolicy::none());
+
+        for _ in 0..2 {
+            assert_eq!(sensor.read_data(&mut NoDelay), Err(Ms4525doError::I2cError));
+            assert!(!sensor.reinit_if_recovered());
+        }
+        // Without `with_bus_recovery` the driver leaves the re-init to the caller
+        assert_eq!(sensor.read_data(&mut NoDelay), Err(Ms4525doError::I2cError));
+        assert_eq!(reinits.get(), 0);
+        assert!(sensor.reinit_if_recovered());
+        assert_eq!(reinits.get(), 1);
+        assert!(!sensor.reinit_if_recovered());
+    }
+
+    #[test]
+    fn test_trigger_counts_only_i2c_errors() {
+        let mut trigger = RecoveryTrigger::new(2);
+        assert!(!trigger.record::<()>(&Err(Ms4525doError::I2cError)));
+        assert!(!trigger.record::<()>(&Err(Ms4525doError::StaleDataMismatch)));
+        assert!(!trigger.record::<()>(&Err(Ms4525doError::I2cError)));
+        assert!(trigger.record::<()>(&Err(Ms4525doError::I2cError)));
+        assert_eq!(trigger.consecutive_errors(), 0);
+    }
+}
@@ -0,0 +1,530 @@
+//! Record-and-replay I2C transport for reproducing field issues in tests.
+//!
+//! [`RecordingI2c`] wraps any blocking or async I2C peripheral and records
+//! every transaction the driver makes: address, direction, bytes, whether the
+//! bus reported an error, and the time relative to the first transaction.
+//! Recordings are saved as plain text, one transaction per line.
+//!
+//! [`ReplayI2c`] plays a recording back. It returns the recorded bytes for
+//! each read and panics as soon as the driver issues a transaction that
+//! differs from the recording, so a test fails at the first divergence.
+//!
+//! # Recording Format
+//!
+//! ```text
+//! # ms4525do i2c recording v1
+//! 0 0x28 W:00
+//! 2104 0x28 R:20005fe0 W:00 R:a0005fe0
+//! 22410 0x28 ERR W:00
+//! ```
+//!
+//! Each line holds the elapsed time in microseconds, the 7-bit address, an
+//! optional `ERR` marker for transactions that failed on the bus, and the
+//! operations of the transaction as `W:<hex>` (write) or `R:<hex>` (read).
+//! The example holds the two packets in one transaction, as fetched by the
+//! default [`FetchMode`](crate::FetchMode); replay a recording with the fetch
+//! mode it was made with.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use ms4525do::blocking::Ms4525do;
+//! use ms4525do::replay::{RecordingI2c, ReplayI2c};
+//!
+//! // In the field: record what the sensor returns
+//! let mut sensor = Ms4525do::new(RecordingI2c::new(i2c));
+//! let _ = sensor.read_data(&mut delay);
+//! let recording = sensor.release().into_recording();
+//! recording.save(std::fs::File::create("airspeed.i2c")?)?;
+//!
+//! // In a unit test: replay it
+//! let replay = ReplayI2c::load(std::fs::File::open("airspeed.i2c")?)?;
+//! let mut sensor = Ms4525do::new(replay);
+//! let result = sensor.read_data(&mut delay);
+//! sensor.release().assert_finished();
+//! ```
+
+use embedded_hal::i2c::{ErrorKind, ErrorType, Operation};
+use std::fmt::Write as _;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::time::Instant;
+
+/// Header line identifying the recording format
+pub const RECORDING_HEADER: &str = "# ms4525do i2c recording v1";
+
+/// Direction of a single I2C operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Controller writes bytes to the device
+    Write,
+    /// Controller reads bytes from the device
+    Read,
+}
+
+/// One operation within a recorded transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedOperation {
+    /// Direction of the operation
+    pub direction: Direction,
+    /// Bytes written, or bytes returned by the device for reads
+    pub bytes: Vec<u8>,
+}
+
+/// One recorded I2C transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedTransaction {
+    /// Time since the first recorded transaction, in microseconds
+    pub elapsed_us: u64,
+    /// 7-bit I2C address
+    pub address: u8,
+    /// Whether the bus reported an error for this transaction
+    pub failed: bool,
+    /// Operations performed within the transaction, in order
+    pub operations: Vec<RecordedOperation>,
+}
+
+/// Ordered list of recorded I2C transactions.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Recording {
+    /// Recorded transactions, oldest first
+    pub transactions: Vec<RecordedTransaction>,
+}
+
+impl Recording {
+    /// Writes the recording in the text format described in the module documentation.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error reported by the writer.
+    pub fn save<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writeln!(writer, "{}", RECORDING_HEADER)?;
+        for transaction in &self.transactions {
+            let mut line = format!("{} 0x{:02x}", transaction.elapsed_us, transaction.address);
+            if transaction.failed {
+                line.push_str(" ERR");
+            }
+            for operation in &transaction.operations {
+                line.push_str(match operation.direction {
+                    Direction::Write => " W:",
+                    Direction::Read => " R:",
+                });
+                for byte in &operation.bytes {
+                    let _ = write!(line, "{:02x}", byte);
+                }
+            }
+            writeln!(writer, "{}", line)?;
+        }
+        Ok(())
+    }
+
+    /// Reads a recording in the text format described in the module documentation.
+    ///
+    /// Blank lines and lines starting with `#` are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidData` for malformed lines, or any error reported by the reader.
+    pub fn load<R: Read>(reader: R) -> io::Result<Self> {
+        let mut transactions = Vec::new();
+        for line in BufReader::new(reader).lines() {
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            transactions.push(parse_transaction(line)?);
+        }
+        Ok(Self { transactions })
+    }
+}
+
+fn parse_transaction(line: &str) -> io::Result<RecordedTransaction> {
+    let invalid = || io::Error::new(io::ErrorKind::InvalidData, format!("bad line: {}", line));
+
+    let mut fields = line.split_whitespace();
+    let elapsed_us = fields
+        .next()
+        .and_then(|f| f.parse().ok())
+        .ok_or_else(invalid)?;
+    let address = fields
+        .next()
+        .and_then(|f| f.strip_prefix("0x"))
+        .and_then(|f| u8::from_str_radix(f, 16).ok())
+        .ok_or_else(invalid)?;
+
+    let mut failed = false;
+    let mut operations = Vec::new();
+    for field in fields {
+        let (direction, hex) = if field == "ERR" {
+            failed = true;
+            continue;
+        } else if let Some(hex) = field.strip_prefix("W:") {
+            (Direction::Write, hex)
+        } else if let Some(hex) = field.strip_prefix("R:") {
+            (Direction::Read, hex)
+        } else {
+            return Err(invalid());
+        };
+
+        if hex.len() % 2 != 0 {
+            return Err(invalid());
+        }
+        let bytes = (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16))
+            .collect::<Result<_, _>>()
+            .map_err(|_| invalid())?;
+        operations.push(RecordedOperation { direction, bytes });
+    }
+
+    Ok(RecordedTransaction {
+        elapsed_us,
+        address,
+        failed,
+        operations,
+    })
+}
+
+/// I2C wrapper that records every transaction passed through it.
+///
+/// Implements the blocking `embedded_hal::i2c::I2c` trait when the wrapped
+/// peripheral does, and the async `embedded_hal_async::i2c::I2c` trait when
+/// the `async` feature is enabled and the wrapped peripheral implements it.
+pub struct RecordingI2c<I2C> {
+    i2c: I2C,
+    start: Option<Instant>,
+    recording: Recording,
+}
+
+impl<I2C> RecordingI2c<I2C> {
+    /// Wraps an I2C peripheral with an empty recording.
+    pub fn new(i2c: I2C) -> Self {
+        Self {
+            i2c,
+            start: None,
+            recording: Recording::default(),
+        }
+    }
+
+    /// Returns the transactions recorded so far.
+    pub fn recording(&self) -> &Recording {
+        &self.recording
+    }
+
+    /// Consumes the wrapper and returns the recording.
+    pub fn into_recording(self) -> Recording {
+        self.recording
+    }
+
+    /// Consumes the wrapper and returns the underlying I2C peripheral and the recording.
+    pub fn release(self) -> (I2C, Recording) {
+        (self.i2c, self.recording)
+    }
+
+    fn elapsed_us(&mut self) -> u64 {
+        let start = *self.start.get_or_insert_with(Instant::now);
+        start.elapsed().as_micros() as u64
+    }
+
+    fn record(&mut self, elapsed_us: u64, address: u8, failed: bool, operations: &[Operation<'_>]) {
+        let operations = operations
+            .iter()
+            .map(|operation| match operation {
+                Operation::Write(bytes) => RecordedOperation {
+                    direction: Direction::Write,
+                    bytes: bytes.to_vec(),
+                },
+                Operation::Read(bytes) => RecordedOperation {
+                    direction: Direction::Read,
+                    bytes: bytes.to_vec(),
+                },
+            })
+            .collect();
+
+        self.recording.transactions.push(RecordedTransaction {
+            elapsed_us,
+            address,
+            failed,
+            operations,
+        });
+    }
+}
+
+impl<I2C: ErrorType> ErrorType for RecordingI2c<I2C> {
+    type Error = I2C::Error;
+}
+
+impl<I2C: embedded_hal::i2c::I2c> embedded_hal::i2c::I2c for RecordingI2c<I2C> {
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        let elapsed_us = self.elapsed_us();
+        let result = self.i2c.transaction(address, operations);
+        self.record(elapsed_us, address, result.is_err(), operations);
+        result
+    }
+}
+
+#[cfg(feature = "async")]
+impl<I2C: embedded_hal_async::i2c::I2c> embedded_hal_async::i2c::I2c for RecordingI2c<I2C> {
+    async fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        let elapsed_us = self.elapsed_us();
+        let result = self.i2c.transaction(address, operations).await;
+        self.record(elapsed_us, address, result.is_err(), operations);
+        result
+    }
+}
+
+/// Error returned by [`ReplayI2c`] for transactions that failed when recorded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReplayError;
+
+impl embedded_hal::i2c::Error for ReplayError {
+    fn kind(&self) -> ErrorKind {
+        ErrorKind::Other
+    }
+}
+
+/// I2C implementation that replays a [`Recording`].
+///
+/// Each transaction is checked against the next recorded one: the address,
+/// the number, direction and length of operations, and the written bytes
+/// must match exactly. Read operations are filled with the recorded bytes.
+/// Transactions recorded as failed return [`ReplayError`].
+///
+/// # Panics
+///
+/// Panics if the driver issues a transaction that differs from the
+/// recording, or more transactions than were recorded.
+pub struct ReplayI2c {
+    recording: Recording,
+    position: usize,
+}
+
+impl ReplayI2c {
+    /// Creates a replay of the given recording.
+    pub fn new(recording: Recording) -> Self {
+        Self {
+            recording,
+            position: 0,
+        }
+    }
+
+    /// Loads a recording saved with [`Recording::save`] and creates a replay of it.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidData` for malformed recordings, or any error reported by the reader.
+    pub fn load<R: Read>(reader: R) -> io::Result<Self> {
+        Recording::load(reader).map(Self::new)
+    }
+
+    /// Returns the number of recorded transactions not yet replayed.
+    pub fn remaining(&self) -> usize {
+        self.recording.transactions.len() - self.position
+    }
+
+    /// Asserts that every recorded transaction has been replayed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if recorded transactions remain.
+    pub fn assert_finished(&self) {
+        assert_eq!(
+            self.remaining(),
+            0,
+            "replay finished with {} recorded transactions left",
+            self.remaining()
+        );
+    }
+
+    fn replay(&mut self, address: u8, operations: &mut [Operation<'_>]) -> Result<(), ReplayError> {
+        let index = self.position;
+        let expected = self.recording.transactions.get(index).unwrap_or_else(|| {
+            panic!(
+                "transaction #{} to 0x{:02x} was not recorded",
+                index, address
+            )
+        });
+
+        assert_eq!(
+            address, expected.address,
+            "transaction #{}: address mismatch",
+            index
+        );
+        assert_eq!(
+            operations.len(),
+            expected.operations.len(),
+            "transaction #{}: operation count mismatch",
+            index
+        );
+
+        for (i, (operation, recorded)) in
+            operations.iter_mut().zip(&expected.operations).enumerate()
+        {
+            match operation {
+                Operation::Write(bytes) => {
+                    assert_eq!(
+                        recorded.direction,
+                        Direction::Write,
+                        "transaction #{}, operation {}: expected a read",
+                        index,
+                        i
+                    );
+                    assert_eq!(
+                        *bytes,
+                        recorded.bytes.as_slice(),
+                        "transaction #{}, operation {}: written bytes differ",
+                        index,
+                        i
+                    );
+                }
+                Operation::Read(buffer) => {
+                    assert_eq!(
+                        recorded.direction,
+                        Direction::Read,
+                        "transaction #{}, operation {}: expected a write",
+                        index,
+                        i
+                    );
+                    assert_eq!(
+                        buffer.len(),
+                        recorded.bytes.len(),
+                        "transaction #{}, operation {}: read length differs",
+                        index,
+                        i
+                    );
+                    buffer.copy_from_slice(&recorded.bytes);
+                }
+            }
+        }
+
+        self.position += 1;
+        if expected.failed {
+            Err(ReplayError)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl ErrorType for ReplayI2c {
+    type Error = ReplayError;
+}
+
+impl embedded_hal::i2c::I2c for ReplayI2c {
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        self.replay(address, operations)
+    }
+}
+
+#[cfg(feature = "async")]
+impl embedded_hal_async::i2c::I2c for ReplayI2c {
+    async fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        self.replay(address, operations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_hal::i2c::I2c;
+
+    const RECORDING: &str = "# ms4525do i2c recording v1
+0 0x28 W:00
+2100 0x28 R:20005fe0 W:00 R:a0005fe0
+20000 0x28 ERR W:00
+";
+
+    #[test]
+    fn test_load_and_save() {
+        let recording = Recording::load(RECORDING.as_bytes()).unwrap();
+        assert_eq!(recording.transactions.len(), 3);
+        assert_eq!(
+            recording.transactions[1].operations[0],
+            RecordedOperation {
+                direction: Direction::Read,
+                bytes: vec![0x20, 0x00, 0x5F, 0xE0],
+            }
+        );
+        assert_eq!(recording.transactions[1].operations.len(), 3);
+        assert!(recording.transactions[2].failed);
+
+        let mut saved = Vec::new();
+        recording.save(&mut saved).unwrap();
+        assert_eq!(String::from_utf8(saved).unwrap(), RECORDING);
+    }
+
+    #[test]
+    fn test_load_rejects_malformed_lines() {
+        assert!(Recording::load("0 0x28 X:00".as_bytes()).is_err());
+        assert!(Recording::load("0 0x28 W:0".as_bytes()).is_err());
+        assert!(Recording::load("0 40 W:00".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_record_replay() {
+        let replay = ReplayI2c::load(RECORDING.as_bytes()).unwrap();
+        let mut i2c = RecordingI2c::new(replay);
+
+        let mut data_1 = [0u8; 4];
+        let mut data_2 = [0u8; 4];
+        i2c.write(0x28, &[0x00]).unwrap();
+        i2c.transaction(
+            0x28,
+            &mut [
+                Operation::Read(&mut data_1),
+                Operation::Write(&[0x00]),
+                Operation::Read(&mut data_2),
+            ],
+        )
+        .unwrap();
+        assert_eq!(data_1, [0x20, 0x00, 0x5F, 0xE0]);
+        assert_eq!(i2c.write(0x28, &[0x00]), Err(ReplayError));
+
+        let (replay, recording) = i2c.release();
+        replay.assert_finished();
+        assert_eq!(recording.transactions.len(), 3);
+        assert_eq!(recording.transactions[1].operations[2].bytes, data_2);
+        assert!(recording.transactions[2].failed);
+    }
+
+    #[test]
+    #[should_panic(expected = "written bytes differ")]
+    fn test_replay_detects_divergence() {
+        let mut replay = ReplayI2c::load(RECORDING.as_bytes()).unwrap();
+        let _ = replay.write(0x28, &[0x01]);
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_replay_read_data() {
+        use crate::blocking::Ms4525do;
+        use crate::mock::NoDelay;
+
+        let mut sensor = Ms4525do::new(ReplayI2c::load(RECORDING.as_bytes()).unwrap());
+        sensor.set_retry_policy(crate::retry::RetryPolicy::none());
+        let (pressure, temp) = sensor.read_data(&mut NoDelay).unwrap();
+        assert!(pressure.is_finite());
+        assert!((temp - 24.9).abs() < 0.1);
+        assert_eq!(
+            sensor.read_data(&mut NoDelay),
+            Err(crate::Ms4525doError::I2cError)
+        );
+        sensor.release().assert_finished();
+    }
+}
@@ -0,0 +1,264 @@
+//! Retry policy for transient read failures.
+//!
+//! The double-read validation in `read_data` occasionally rejects a reading
+//! because the sensor was read mid-conversion. These glitches are harmless
+//! and are best retried inside the driver, while a sensor fault should be
+//! reported immediately. A [`RetryPolicy`] describes which errors are retried,
+//! how often and with what backoff; both drivers apply it in `read_data` and
+//! count the outcome in [`RetryStats`].
+//!
+//! # Example
+//!
+//! ```ignore
+//! use ms4525do::blocking::Ms4525do;
+//! use ms4525do::retry::RetryPolicy;
+//!
+//! let mut sensor = Ms4525do::new(i2c);
+//! sensor.set_retry_policy(RetryPolicy {
+//!     max_attempts: 5,
+//!     ..RetryPolicy::default()
+//! });
+//!
+//! let reading = sensor.read_data(&mut delay)?;
+//! println!("Retries so far: {}", sensor.retry_stats().retries);
+//! ```
+
+use crate::common::Status;
+use crate::Ms4525doError;
+
+#[cfg(feature = "defmt")]
+use defmt::Format;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Retry class of an error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ErrorClass {
+    /// Timing glitch of the double-read validation, expected to clear on the next read
+    Transient,
+    /// I2C bus error, which may or may not clear on the next read
+    Communication,
+    /// Sensor fault or invalid output, never retried
+    Fatal,
+}
+
+impl ErrorClass {
+    /// Classifies a driver error.
+    ///
+    /// * `StaleDataMismatch` and `InvalidStatus(StaleData | NormalOperation)` are transient
+    /// * `I2cError` is a communication error
+    /// * `FaultDetected`, `DataOutOfRange`, `SensorStuck` and other status codes are fatal
+    pub fn of(error: &Ms4525doError) -> Self {
+        match error {
+            Ms4525doError::StaleDataMismatch
+            | Ms4525doError::InvalidStatus(Status::StaleData)
+            | Ms4525doError::InvalidStatus(Status::NormalOperation) => ErrorClass::Transient,
+            Ms4525doError::I2cError => ErrorClass::Communication,
+            Ms4525doError::InvalidStatus(_)
+            | Ms4525doError::DataOutOfRange(_)
+            | Ms4525doError::FaultDetected
+            | Ms4525doError::SensorStuck => ErrorClass::Fatal,
+        }
+    }
+}
+
+/// Retry policy applied by `read_data`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RetryPolicy {
+    /// Maximum number of read attempts, including the first (0 behaves like 1)
+    pub max_attempts: u8,
+    /// Retry transient validation errors
+    pub retry_transient: bool,
+    /// Retry I2C communication errors
+    pub retry_communication: bool,
+    /// Delay before the first retry, in milliseconds
+    pub initial_backoff_ms: u32,
+    /// Upper limit of the exponentially growing backoff, in milliseconds
+    pub max_backoff_ms: u32,
+}
+
+impl Default for RetryPolicy {
+    /// Up to 3 attempts, retrying transient and I2C errors with a 2 ms, 4 ms backoff.
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            retry_transient: true,
+            retry_communication: true,
+            initial_backoff_ms: 2,
+            max_backoff_ms: 20,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that never retries, returning the first error.
+    pub const fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            retry_transient: false,
+            retry_communication: false,
+            initial_backoff_ms: 0,
+            max_backoff_ms: 0,
+        }
+    }
+
+    /// Decides whether a failed attempt should be retried.
+    ///
+    /// # Arguments
+    ///
+    /// * `error` - Error returned by the failed attempt
+    /// * `attempt` - Number of attempts made so far, starting at 1
+    pub fn should_retry(&self, error: &Ms4525doError, attempt: u8) -> bool {
+        if attempt >= self.max_attempts {
+            return false;
+        }
+        match ErrorClass::of(error) {
+            ErrorClass::Transient => self.retry_transient,
+            ErrorClass::Communication => self.retry_communication,
+            ErrorClass::Fatal => false,
+        }
+    }
+
+    /// Returns the delay before the next attempt, doubling after each retry.
+    ///
+    /// # Arguments
+    ///
+    /// * `attempt` - Number of attempts made so far, starting at 1
+    pub fn backoff_ms(&self, attempt: u8) -> u32 {
+        let shift = attempt.saturating_sub(1).min(31);
+        self.initial_backoff_ms
+            .saturating_mul(1 << shift)
+            .min(self.max_backoff_ms)
+    }
+}
+
+/// Counters of read attempts and retries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RetryStats {
+    /// Number of `read_data` calls
+    pub reads: u32,
+    /// Number of attempts beyond the first
+    pub retries: u32,
+    /// Number of reads that succeeded after at least one retry
+    pub recovered: u32,
+    /// Number of reads that returned an error
+    pub failures: u32,
+}
+
+impl RetryStats {
+    /// Records the outcome of one `read_data` call.
+    ///
+    /// # Arguments
+    ///
+    /// * `attempts` - Number of attempts made, starting at 1
+    /// * `success` - Whether the read succeeded
+    pub fn record(&mut self, attempts: u8, success: bool) {
+        let retries = attempts.saturating_sub(1) as u32;
+        self.reads = self.reads.wrapping_add(1);
+        self.retries = self.retries.wrapping_add(retries);
+        if !success {
+            self.failures = self.failures.wrapping_add(1);
+        } else if retries > 0 {
+            self.recovered = self.recovered.wrapping_add(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RangeStatus;
+
+    #[test]
+    fn test_error_classes() {
+        let policy = RetryPolicy::default();
+        assert!(policy.should_retry(&Ms4525doError::StaleDataMismatch, 1));
+        assert!(policy.should_retry(&Ms4525doError::InvalidStatus(Status::StaleData), 1));
+        assert!(policy.should_retry(&Ms4525doError::I2cError, 2));
+        assert!(!policy.should_retry(&Ms4525doError::I2cError, 3));
+        assert!(!policy.should_retry(&Ms4525doError::FaultDetected, 1));
+        assert!(!policy.should_retry(&Ms4525doError::DataOutOfRange(RangeStatus::Disconnected), 1));
+        assert!(!RetryPolicy::none().should_retry(&Ms4525doError::StaleDataMismatch, 1));
+    }
+
+    #[test]
+    fn test_backoff() {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.backoff_ms(1), 2);
+        assert_eq!(policy.backoff_ms(2), 4);
+        assert_eq!(policy.backoff_ms(4), 16);
+        assert_eq!(policy.backoff_ms(5), 20);
+        assert_eq!(policy.backoff_ms(255), 20);
+    }
+
+    #[test]
+    fn test_stats() {
+        let mut stats = RetryStats::default();
+        stats.record(1, true);
+        stats.record(3, true);
+        stats.record(3, false);
+        assert_eq!(
+            stats,
+            RetryStats {
+                reads: 3,
+                retries: 4,
+                recovered: 1,
+                failures: 1,
+            }
+        );
+    }
+
+    /// A mid-conversion read, then a clean retry
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    fn glitch_then_clean() -> crate::mock::SensorMock {
+        use crate::mock::{frames, SensorMock};
+
+        let [fresh, _] = frames(8192, 767);
+        let [_, converted] = frames(8193, 767);
+        SensorMock::new([Some([fresh, converted]), Some(frames(8192, 767))])
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_driver_retries_glitch() {
+        use crate::blocking::Ms4525do;
+        use crate::mock::NoDelay;
+
+        let mut sensor = Ms4525do::new(glitch_then_clean());
+        assert!(sensor.read_data(&mut NoDelay).is_ok());
+        assert_eq!(sensor.retry_stats().retries, 1);
+        assert_eq!(sensor.retry_stats().recovered, 1);
+        assert_eq!(sensor.health().errors.stale_data_mismatch, 1);
+        assert_eq!(sensor.health().successes, 1);
+        assert_eq!(sensor.health().longest_error_streak, 1);
+
+        // The retry sends a new measurement request
+        let i2c = sensor.release();
+        assert_eq!(i2c.shapes(), ["W", "RWR", "W", "RWR"]);
+        i2c.assert_finished();
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_async_driver_retries_glitch() {
+        use crate::async_api::Ms4525do;
+        use futures_executor::block_on;
+
+        let mut sensor = Ms4525do::new(glitch_then_clean());
+        assert!(block_on(sensor.read_data()).is_ok());
+        assert_eq!(sensor.retry_stats().retries, 1);
+        assert_eq!(sensor.retry_stats().recovered, 1);
+        assert_eq!(sensor.health().errors.stale_data_mismatch, 1);
+
+        let i2c = sensor.release();
+        assert_eq!(i2c.shapes(), ["W", "RWR", "W", "RWR"]);
+        i2c.assert_finished();
+    }
+}
@@ -0,0 +1,236 @@
+//! Periodic sampling task for Embassy.
+//!
+//! [`Sampler`] owns an [`async_api::Ms4525do`](crate::async_api::Ms4525do),
+//! reads it at a fixed rate using a drift-free `embassy_time::Ticker` and
+//! publishes every [`Sample`], reading or error, through an `embassy-sync`
+//! [`Watch`](embassy_sync::watch::Watch) or
+//! [`PubSubChannel`](embassy_sync::pubsub::PubSubChannel). Consumers such as
+//! control, logging and telemetry each hold a receiver or subscriber and get
+//! the latest sample without sharing the I2C bus.
+//!
+//! Embassy tasks cannot be generic, so wrap [`Sampler::run`] in a task with
+//! the concrete types of your platform.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+//! use embassy_sync::watch::{Sender, Watch};
+//! use ms4525do::async_api::Ms4525do;
+//! use ms4525do::sampler::{Sample, Sampler};
+//!
+//! static AIRSPEED: Watch<CriticalSectionRawMutex, Sample, 3> = Watch::new();
+//!
+//! #[embassy_executor::task]
+//! async fn airspeed_task(
+//!     mut sampler: Sampler<I2c<'static, Async>, Sender<'static, CriticalSectionRawMutex, Sample, 3>>,
+//! ) -> ! {
+//!     sampler.run().await
+//! }
+//!
+//! let sampler = Sampler::new(Ms4525do::new(i2c), AIRSPEED.sender(), 50);
+//! spawner.spawn(airspeed_task(sampler)).unwrap();
+//!
+//! // In the control task
+//! let mut receiver = AIRSPEED.receiver().unwrap();
+//! // `changed` waits for the next sample, `try_get` returns the latest one
+//! if let Ok(sample) = receiver.changed().await {
+//!     let airspeed = sample.reading.airspeed();
+//! }
+//! ```
+
+use crate::async_api::Ms4525do;
+use crate::timing::TimestampedReading;
+use crate::transfer::{Ms4525doTransfer, TransferFunction};
+use crate::Ms4525doError;
+use embassy_sync::blocking_mutex::raw::RawMutex;
+use embassy_sync::pubsub::{ImmediatePublisher, Publisher};
+use embassy_sync::watch::{DynSender, Sender};
+use embassy_time::{Duration, Ticker};
+use embedded_hal_async::i2c::I2c;
+
+#[cfg(feature = "defmt")]
+use defmt::warn;
+
+/// Outcome of one sampling period, as published by the [`Sampler`].
+pub type Sample = Result<TimestampedReading, Ms4525doError>;
+
+/// Destination for the samples of a [`Sampler`].
+///
+/// Implemented for `embassy-sync` `Watch` senders and `PubSubChannel`
+/// publishers, and for pairs of destinations to publish to both.
+pub trait SamplePublisher {
+    /// Publishes a sample without waiting.
+    ///
+    /// Publishing must never block the sampling loop: a slow consumer misses
+    /// samples rather than delaying the next read.
+    fn publish(&mut self, sample: Sample);
+}
+
+impl<M: RawMutex, const N: usize> SamplePublisher for Sender<'_, M, Sample, N> {
+    fn publish(&mut self, sample: Sample) {
+        self.send(sample);
+    }
+}
+
+impl SamplePublisher for DynSender<'_, Sample> {
+    fn publish(&mut self, sample: Sample) {
+        self.send(sample);
+    }
+}
+
+impl<M: RawMutex, const CAP: usize, const SUBS: usize, const PUBS: usize> SamplePublisher
+    for Publisher<'_, M, Sample, CAP, SUBS, PUBS>
+{
+    fn publish(&mut self, sample: Sample) {
+        self.publish_immediate(sample);
+    }
+}
+
+impl<M: RawMutex, const CAP: usize, const SUBS: usize, const PUBS: usize> SamplePublisher
+    for ImmediatePublisher<'_, M, Sample, CAP, SUBS, PUBS>
+{
+    fn publish(&mut self, sample: Sample) {
+        self.publish_immediate(sample);
+    }
+}
+
+impl<A: SamplePublisher, B: SamplePublisher> SamplePublisher for (A, B) {
+    fn publish(&mut self, sample: Sample) {
+        self.0.publish(sample);
+        self.1.publish(sample);
+    }
+}
+
+/// Reads a sensor at a fixed rate and publishes every sample.
+///
+/// # Type Parameters
+///
+/// * `I2C` - The I2C peripheral type implementing `embedded_hal_async::i2c::I2c`
+/// * `P` - Where samples are published, see [`SamplePublisher`]
+/// * `T` - The transfer function of the sensor
+pub struct Sampler<I2C, P, T = Ms4525doTransfer> {
+    sensor: Ms4525do<I2C, T>,
+    publisher: P,
+    period: Duration,
+}
+
+impl<I2C, P, T> Sampler<I2C, P, T>
+where
+    I2C: I2c,
+    P: SamplePublisher,
+    T: TransferFunction,
+{
+    /// Creates a sampler running at `rate_hz` (at least 1 Hz).
+    ///
+    /// # Arguments
+    ///
+    /// * `sensor` - The configured sensor driver
+    /// * `publisher` - Where samples are published
+    /// * `rate_hz` - Sampling rate, e.g. 50 for 50 Hz
+    pub fn new(sensor: Ms4525do<I2C, T>, publisher: P, rate_hz: u32) -> Self {
+        Self::new_with_period(sensor, publisher, Duration::from_hz(rate_hz.max(1) as u64))
+    }
+
+    /// Creates a sampler with an explicit sampling period.
+    pub fn new_with_period(sensor: Ms4525do<I2C, T>, publisher: P, period: Duration) -> Self {
+        Self {
+            sensor,
+            publisher,
+            period,
+        }
+    }
+
+    /// Returns the sampling period.
+    pub fn period(&self) -> Duration {
+        self.period
+    }
+
+    /// Returns the sensor driver, e.g. to read its health statistics.
+    pub fn sensor(&self) -> &Ms4525do<I2C, T> {
+        &self.sensor
+    }
+
+    /// Returns the sensor driver mutably, e.g. to change its retry policy.
+    pub fn sensor_mut(&mut self) -> &mut Ms4525do<I2C, T> {
+        &mut self.sensor
+    }
+
+    /// Takes and publishes a single timestamped sample.
+    ///
+    /// # Returns
+    ///
+    /// The published sample
+    pub async fn sample(&mut self) -> Sample {
+        let sample = self.sensor.read_data_timestamped().await;
+
+        if let Err(_e) = &sample {
+            #[cfg(feature = "defmt")]
+            warn!("Sampler read error: {:?}", _e);
+
+            #[cfg(all(not(feature = "defmt"), feature = "log"))]
+            log::warn!("Sampler read error: {:?}", _e);
+        }
+
+        self.publisher.publish(sample);
+        sample
+    }
+
+    /// Samples forever at the configured rate.
+    ///
+    /// The `Ticker` schedules each read relative to the previous deadline
+    /// rather than to the end of the previous read, so the rate does not drift
+    /// with the read duration or retries. If a read overruns its period, the
+    /// next one starts immediately.
+    pub async fn run(&mut self) -> ! {
+        let mut ticker = Ticker::every(self.period);
+        loop {
+            // Errors are published to the consumers like readings
+            let _ = self.sample().await;
+            ticker.next().await;
+        }
+    }
+
+    /// Consumes the sampler and returns the sensor driver and publisher.
+    pub fn release(self) -> (Ms4525do<I2C, T>, P) {
+        (self.sensor, self.publisher)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Reading;
+    use embassy_sync::blocking_mutex::raw::NoopRawMutex;
+    use embassy_sync::pubsub::{PubSubChannel, WaitResult};
+    use embassy_sync::watch::Watch;
+
+    const SAMPLE: Sample = Ok(TimestampedReading {
+        reading: Reading {
+            differential_pressure_pa: 120.0,
+            temperature_c: 21.5,
+        },
+        request_us: 1_000,
+        fetch_us: 3_200,
+    });
+
+    #[test]
+    fn test_publish_to_watch_and_pubsub() {
+        let watch: Watch<NoopRawMutex, Sample, 2> = Watch::new();
+        let channel: PubSubChannel<NoopRawMutex, Sample, 4, 2, 1> = PubSubChannel::new();
+        let mut control = watch.receiver().unwrap();
+        let mut logger = channel.subscriber().unwrap();
+
+        let mut publisher = (watch.sender(), channel.immediate_publisher());
+        publisher.publish(SAMPLE);
+        publisher.publish(Err(Ms4525doError::I2cError));
+
+        // The watch keeps only the latest sample, the channel queues both
+        assert_eq!(control.try_changed(), Some(Err(Ms4525doError::I2cError)));
+        assert_eq!(logger.try_next_message(), Some(WaitResult::Message(SAMPLE)));
+        assert_eq!(
+            logger.try_next_message(),
+            Some(WaitResult::Message(Err(Ms4525doError::I2cError)))
+        );
+    }
+}
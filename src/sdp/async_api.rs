@@ -0,0 +1,249 @@
+//! Asynchronous (non-blocking) API for SDP3x / SDP8xx sensor communication.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use ms4525do::sdp::async_api::Sdp;
+//! use ms4525do::sdp::{ContinuousMode, SdpModel};
+//!
+//! let mut sensor = Sdp::new(i2c, SdpModel::Sdp810Range500);
+//! sensor.start_continuous(ContinuousMode::default()).await?;
+//!
+//! loop {
+//!     match sensor.read_data().await {
+//!         Ok(reading) => println!("Airspeed: {} m/s", reading.airspeed()),
+//!         Err(e) => println!("Error: {:?}", e),
+//!     }
+//!     Timer::after(Duration::from_millis(20)).await;
+//! }
+//! ```
+
+use super::*;
+use crate::sensor::async_api::DifferentialPressureSensor;
+use embassy_time::{Duration, Timer};
+use embedded_hal_async::i2c::I2c;
+
+/// SDP3x / SDP8xx sensor driver with async I2C communication.
+///
+/// # Type Parameters
+///
+/// * `I2C` - The I2C peripheral type implementing `embedded_hal_async::i2c::I2c`
+pub struct Sdp<I2C> {
+    i2c: I2C,
+    address: u8,
+    mode: Option<ContinuousMode>,
+    scale_factor: Option<u16>,
+}
+
+impl<I2C> Sdp<I2C>
+where
+    I2C: I2c,
+{
+    /// Creates a new sensor instance at the default address of the given part.
+    ///
+    /// Call [`Sdp::start_continuous`] before reading data.
+    pub fn new(i2c: I2C, model: SdpModel) -> Self {
+        Self::new_with_address(i2c, model.address())
+    }
+
+    /// Creates a new sensor instance with a custom I2C address.
+    ///
+    /// # Arguments
+    ///
+    /// * `i2c` - The I2C peripheral for communication with the sensor
+    /// * `address` - 7-bit I2C address
+    pub fn new_with_address(i2c: I2C, address: u8) -> Self {
+        Self {
+            i2c,
+            address,
+            mode: None,
+            scale_factor: None,
+        }
+    }
+
+    /// Returns the active continuous measurement mode, if measuring.
+    pub fn mode(&self) -> Option<ContinuousMode> {
+        self.mode
+    }
+
+    /// Returns the scale factor read from the sensor, if it has been read.
+    pub fn scale_factor(&self) -> Option<u16> {
+        self.scale_factor
+    }
+
+    /// Resets all sensors on the bus that support the I2C general call.
+    ///
+    /// The reset is sent to the general call address, so other devices on
+    /// the same bus may reset as well.
+    ///
+    /// # Errors
+    ///
+    /// * `SdpError::I2cError` - I2C communication failure
+    pub async fn soft_reset(&mut self) -> Result<(), SdpError> {
+        self.i2c
+            .write(GENERAL_CALL_ADDR, &[CMD_SOFT_RESET])
+            .await
+            .map_err(|_| SdpError::I2cError)?;
+        self.mode = None;
+        Timer::after(Duration::from_millis(RESET_DELAY_MS as u64)).await;
+        Ok(())
+    }
+
+    /// Starts continuous measurement and waits for the first result.
+    ///
+    /// # Errors
+    ///
+    /// * `SdpError::I2cError` - I2C communication failure
+    pub async fn start_continuous(&mut self, mode: ContinuousMode) -> Result<(), SdpError> {
+        self.i2c
+            .write(self.address, &mode.command().to_be_bytes())
+            .await
+            .map_err(|_| SdpError::I2cError)?;
+        self.mode = Some(mode);
+        Timer::after(Duration::from_millis(START_DELAY_MS as u64)).await;
+        Ok(())
+    }
+
+    /// Stops continuous measurement.
+    ///
+    /// # Errors
+    ///
+    /// * `SdpError::I2cError` - I2C communication failure
+    pub async fn stop_continuous(&mut self) -> Result<(), SdpError> {
+        self.i2c
+            .write(self.address, &CMD_STOP_CONTINUOUS.to_be_bytes())
+            .await
+            .map_err(|_| SdpError::I2cError)?;
+        self.mode = None;
+        Timer::after(Duration::from_micros(STOP_DELAY_US as u64)).await;
+        Ok(())
+    }
+
+    /// Reads the differential pressure scale factor from the sensor.
+    ///
+    /// The value is cached, so later calls to [`Sdp::read_data`] read only
+    /// the pressure and temperature words.
+    ///
+    /// # Errors
+    ///
+    /// * `SdpError::NotMeasuring` - Continuous measurement has not been started
+    /// * `SdpError::I2cError` - I2C communication failure
+    /// * `SdpError::CrcMismatch` - A data word fails its CRC-8 check
+    /// * `SdpError::InvalidScaleFactor` - The sensor reported a scale factor of zero
+    pub async fn read_scale_factor(&mut self) -> Result<u16, SdpError> {
+        let raw = self.read_raw(MEASUREMENT_SIZE).await?;
+        let scale_factor = raw.scale_factor.ok_or(SdpError::InvalidScaleFactor)?;
+        self.scale_factor = Some(scale_factor);
+        Ok(scale_factor)
+    }
+
+    /// Reads the latest differential pressure and temperature measurement.
+    ///
+    /// The scale factor is read along with the first measurement.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Reading)` - Differential pressure in Pascals and temperature in °C
+    /// * `Err(SdpError)` - Error if communication fails or data is invalid
+    ///
+    /// # Errors
+    ///
+    /// * `SdpError::NotMeasuring` - Continuous measurement has not been started
+    /// * `SdpError::I2cError` - I2C communication failure
+    /// * `SdpError::CrcMismatch` - A data word fails its CRC-8 check
+    /// * `SdpError::InvalidScaleFactor` - The sensor reported a scale factor of zero
+    pub async fn read_data(&mut self) -> Result<Reading, SdpError> {
+        let len = match self.scale_factor {
+            Some(_) => SHORT_MEASUREMENT_SIZE,
+            None => MEASUREMENT_SIZE,
+        };
+        let raw = self.read_raw(len).await?;
+        if raw.scale_factor.is_some() {
+            self.scale_factor = raw.scale_factor;
+        }
+        let scale_factor = self.scale_factor.ok_or(SdpError::InvalidScaleFactor)?;
+        Ok(raw.to_reading(scale_factor))
+    }
+
+    /// Consumes the sensor driver and returns the underlying I2C peripheral.
+    pub fn release(self) -> I2C {
+        self.i2c
+    }
+
+    async fn read_raw(&mut self, len: usize) -> Result<RawMeasurement, SdpError> {
+        if self.mode.is_none() {
+            return Err(SdpError::NotMeasuring);
+        }
+        let mut buf = [0u8; MEASUREMENT_SIZE];
+        self.i2c
+            .read(self.address, &mut buf[..len])
+            .await
+            .map_err(|_| SdpError::I2cError)?;
+        RawMeasurement::parse(&buf[..len])
+    }
+}
+
+impl<I2C> DifferentialPressureSensor for Sdp<I2C>
+where
+    I2C: I2c,
+{
+    type Error = SdpError;
+
+    async fn read_pressure(&mut self) -> Result<Reading, SdpError> {
+        self.read_data().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::SdpMock;
+    use embassy_time::Instant;
+    use futures_executor::block_on;
+
+    extern crate std;
+    use std::vec;
+
+    /// 5 Pa and 25 °C at 240 counts per Pascal
+    const WORDS: [u16; 3] = [1200, 5000, 240];
+
+    fn sensor() -> Sdp<SdpMock> {
+        Sdp::new(SdpMock::new(SDP3X_ADDR, WORDS), SdpModel::Sdp32)
+    }
+
+    #[test]
+    fn test_start_and_read() {
+        let mut sensor = sensor();
+        assert_eq!(block_on(sensor.read_data()), Err(SdpError::NotMeasuring));
+
+        block_on(sensor.start_continuous(ContinuousMode::default())).unwrap();
+        let reading = block_on(sensor.read_data()).unwrap();
+        assert_eq!(reading.differential_pressure_pa, 5.0);
+        assert_eq!(sensor.scale_factor(), Some(240));
+        block_on(sensor.read_data()).unwrap();
+
+        let i2c = sensor.release();
+        assert_eq!(i2c.writes(), [(SDP3X_ADDR, vec![0x36, 0x15])]);
+        assert_eq!(i2c.reads(), [MEASUREMENT_SIZE, SHORT_MEASUREMENT_SIZE]);
+    }
+
+    #[test]
+    fn test_stop_and_reset() {
+        let mut sensor = sensor();
+        block_on(sensor.start_continuous(ContinuousMode::default())).unwrap();
+
+        let start = Instant::now();
+        block_on(sensor.stop_continuous()).unwrap();
+        assert!(start.elapsed().as_micros() >= u64::from(STOP_DELAY_US));
+        assert_eq!(block_on(sensor.read_data()), Err(SdpError::NotMeasuring));
+
+        block_on(sensor.start_continuous(ContinuousMode::default())).unwrap();
+        block_on(sensor.soft_reset()).unwrap();
+        assert_eq!(sensor.mode(), None);
+
+        let i2c = sensor.release();
+        assert_eq!(i2c.writes()[1], (SDP3X_ADDR, vec![0x3F, 0xF9]));
+        assert_eq!(i2c.writes()[3], (GENERAL_CALL_ADDR, vec![CMD_SOFT_RESET]));
+        assert!(i2c.reads().is_empty());
+    }
+}
@@ -0,0 +1,282 @@
+//! Blocking (synchronous) API for SDP3x / SDP8xx sensor communication.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use ms4525do::sdp::blocking::Sdp;
+//! use ms4525do::sdp::{ContinuousMode, SdpModel};
+//!
+//! let mut sensor = Sdp::new(i2c, SdpModel::Sdp810Range500);
+//! sensor.start_continuous(ContinuousMode::default(), &mut delay)?;
+//!
+//! loop {
+//!     match sensor.read_data() {
+//!         Ok(reading) => println!("Airspeed: {} m/s", reading.airspeed()),
+//!         Err(e) => println!("Error: {:?}", e),
+//!     }
+//!     delay.delay_ms(20);
+//! }
+//! ```
+
+use super::*;
+use crate::sensor::blocking::DifferentialPressureSensor;
+use embedded_hal::delay::DelayNs;
+use embedded_hal::i2c::I2c;
+
+/// SDP3x / SDP8xx sensor driver with blocking I2C communication.
+///
+/// # Type Parameters
+///
+/// * `I2C` - The I2C peripheral type implementing `embedded_hal::i2c::I2c`
+pub struct Sdp<I2C> {
+    i2c: I2C,
+    address: u8,
+    mode: Option<ContinuousMode>,
+    scale_factor: Option<u16>,
+}
+
+impl<I2C> Sdp<I2C>
+where
+    I2C: I2c,
+{
+    /// Creates a new sensor instance at the default address of the given part.
+    ///
+    /// Call [`Sdp::start_continuous`] before reading data.
+    pub fn new(i2c: I2C, model: SdpModel) -> Self {
+        Self::new_with_address(i2c, model.address())
+    }
+
+    /// Creates a new sensor instance with a custom I2C address.
+    ///
+    /// # Arguments
+    ///
+    /// * `i2c` - The I2C peripheral for communication with the sensor
+    /// * `address` - 7-bit I2C address
+    pub fn new_with_address(i2c: I2C, address: u8) -> Self {
+        Self {
+            i2c,
+            address,
+            mode: None,
+            scale_factor: None,
+        }
+    }
+
+    /// Returns the active continuous measurement mode, if measuring.
+    pub fn mode(&self) -> Option<ContinuousMode> {
+        self.mode
+    }
+
+    /// Returns the scale factor read from the sensor, if it has been read.
+    pub fn scale_factor(&self) -> Option<u16> {
+        self.scale_factor
+    }
+
+    /// Resets all sensors on the bus that support the I2C general call.
+    ///
+    /// The reset is sent to the general call address, so other devices on
+    /// the same bus may reset as well.
+    ///
+    /// # Errors
+    ///
+    /// * `SdpError::I2cError` - I2C communication failure
+    pub fn soft_reset<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), SdpError> {
+        self.i2c
+            .write(GENERAL_CALL_ADDR, &[CMD_SOFT_RESET])
+            .map_err(|_| SdpError::I2cError)?;
+        self.mode = None;
+        delay.delay_ms(RESET_DELAY_MS);
+        Ok(())
+    }
+
+    /// Starts continuous measurement and waits for the first result.
+    ///
+    /// # Errors
+    ///
+    /// * `SdpError::I2cError` - I2C communication failure
+    pub fn start_continuous<D: DelayNs>(
+        &mut self,
+        mode: ContinuousMode,
+        delay: &mut D,
+    ) -> Result<(), SdpError> {
+        self.i2c
+            .write(self.address, &mode.command().to_be_bytes())
+            .map_err(|_| SdpError::I2cError)?;
+        self.mode = Some(mode);
+        delay.delay_ms(START_DELAY_MS);
+        Ok(())
+    }
+
+    /// Stops continuous measurement.
+    ///
+    /// # Errors
+    ///
+    /// * `SdpError::I2cError` - I2C communication failure
+    pub fn stop_continuous<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), SdpError> {
+        self.i2c
+            .write(self.address, &CMD_STOP_CONTINUOUS.to_be_bytes())
+            .map_err(|_| SdpError::I2cError)?;
+        self.mode = None;
+        delay.delay_us(STOP_DELAY_US);
+        Ok(())
+    }
+
+    /// Reads the differential pressure scale factor from the sensor.
+    ///
+    /// The value is cached, so later calls to [`Sdp::read_data`] read only
+    /// the pressure and temperature words.
+    ///
+    /// # Errors
+    ///
+    /// * `SdpError::NotMeasuring` - Continuous measurement has not been started
+    /// * `SdpError::I2cError` - I2C communication failure
+    /// * `SdpError::CrcMismatch` - A data word fails its CRC-8 check
+    /// * `SdpError::InvalidScaleFactor` - The sensor reported a scale factor of zero
+    pub fn read_scale_factor(&mut self) -> Result<u16, SdpError> {
+        let raw = self.read_raw(MEASUREMENT_SIZE)?;
+        let scale_factor = raw.scale_factor.ok_or(SdpError::InvalidScaleFactor)?;
+        self.scale_factor = Some(scale_factor);
+        Ok(scale_factor)
+    }
+
+    /// Reads the latest differential pressure and temperature measurement.
+    ///
+    /// The scale factor is read along with the first measurement.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Reading)` - Differential pressure in Pascals and temperature in °C
+    /// * `Err(SdpError)` - Error if communication fails or data is invalid
+    ///
+    /// # Errors
+    ///
+    /// * `SdpError::NotMeasuring` - Continuous measurement has not been started
+    /// * `SdpError::I2cError` - I2C communication failure
+    /// * `SdpError::CrcMismatch` - A data word fails its CRC-8 check
+    /// * `SdpError::InvalidScaleFactor` - The sensor reported a scale factor of zero
+    pub fn read_data(&mut self) -> Result<Reading, SdpError> {
+        let len = match self.scale_factor {
+            Some(_) => SHORT_MEASUREMENT_SIZE,
+            None => MEASUREMENT_SIZE,
+        };
+        let raw = self.read_raw(len)?;
+        if raw.scale_factor.is_some() {
+            self.scale_factor = raw.scale_factor;
+        }
+        let scale_factor = self.scale_factor.ok_or(SdpError::InvalidScaleFactor)?;
+        Ok(raw.to_reading(scale_factor))
+    }
+
+    /// Consumes the sensor driver and returns the underlying I2C peripheral.
+    pub fn release(self) -> I2C {
+        self.i2c
+    }
+
+    fn read_raw(&mut self, len: usize) -> Result<RawMeasurement, SdpError> {
+        if self.mode.is_none() {
+            return Err(SdpError::NotMeasuring);
+        }
+        let mut buf = [0u8; MEASUREMENT_SIZE];
+        self.i2c
+            .read(self.address, &mut buf[..len])
+            .map_err(|_| SdpError::I2cError)?;
+        RawMeasurement::parse(&buf[..len])
+    }
+}
+
+impl<I2C> DifferentialPressureSensor for Sdp<I2C>
+where
+    I2C: I2c,
+{
+    type Error = SdpError;
+
+    fn read_pressure<D: DelayNs>(&mut self, _delay: &mut D) -> Result<Reading, SdpError> {
+        self.read_data()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{SdpMock, TotalDelay};
+
+    extern crate std;
+    use std::vec;
+
+    /// -10 Pa and 25 °C at 60 counts per Pascal
+    const WORDS: [u16; 3] = [(-600i16) as u16, 5000, 60];
+
+    fn sensor() -> Sdp<SdpMock> {
+        Sdp::new(SdpMock::new(SDP8XX_ADDR, WORDS), SdpModel::Sdp810Range500)
+    }
+
+    #[test]
+    fn test_start_continuous_command() {
+        let mut sensor = sensor();
+        let mut delay = TotalDelay::default();
+        let mode = ContinuousMode {
+            compensation: TemperatureCompensation::MassFlow,
+            average_till_read: false,
+        };
+        sensor.start_continuous(mode, &mut delay).unwrap();
+        assert_eq!(sensor.mode(), Some(mode));
+        assert_eq!(delay.ns, u64::from(START_DELAY_MS) * 1_000_000);
+        assert_eq!(sensor.release().writes(), [(SDP8XX_ADDR, vec![0x36, 0x08])]);
+    }
+
+    #[test]
+    fn test_scale_factor_read_once() {
+        let mut sensor = sensor();
+        sensor
+            .start_continuous(ContinuousMode::default(), &mut TotalDelay::default())
+            .unwrap();
+        assert_eq!(sensor.scale_factor(), None);
+
+        let reading = sensor.read_data().unwrap();
+        assert_eq!(reading.differential_pressure_pa, -10.0);
+        assert_eq!(reading.temperature_c, 25.0);
+        assert_eq!(sensor.scale_factor(), Some(60));
+        sensor.read_data().unwrap();
+
+        // The scale factor comes with the first measurement only
+        let i2c = sensor.release();
+        assert_eq!(i2c.reads(), [MEASUREMENT_SIZE, SHORT_MEASUREMENT_SIZE]);
+    }
+
+    #[test]
+    fn test_not_measuring() {
+        let mut sensor = sensor();
+        assert_eq!(sensor.read_data(), Err(SdpError::NotMeasuring));
+
+        let mut delay = TotalDelay::default();
+        sensor
+            .start_continuous(ContinuousMode::default(), &mut delay)
+            .unwrap();
+        delay.ns = 0;
+        sensor.stop_continuous(&mut delay).unwrap();
+        assert_eq!(delay.ns, u64::from(STOP_DELAY_US) * 1_000);
+        assert_eq!(sensor.mode(), None);
+        assert_eq!(sensor.read_scale_factor(), Err(SdpError::NotMeasuring));
+
+        let i2c = sensor.release();
+        assert_eq!(i2c.writes()[1], (SDP8XX_ADDR, vec![0x3F, 0xF9]));
+        assert!(i2c.reads().is_empty());
+    }
+
+    #[test]
+    fn test_soft_reset_general_call() {
+        let mut sensor = sensor();
+        let mut delay = TotalDelay::default();
+        sensor
+            .start_continuous(ContinuousMode::default(), &mut delay)
+            .unwrap();
+        delay.ns = 0;
+        sensor.soft_reset(&mut delay).unwrap();
+        assert_eq!(delay.ns, u64::from(RESET_DELAY_MS) * 1_000_000);
+        assert_eq!(sensor.mode(), None);
+        assert_eq!(sensor.read_data(), Err(SdpError::NotMeasuring));
+        assert_eq!(
+            sensor.release().writes()[1],
+            (GENERAL_CALL_ADDR, vec![CMD_SOFT_RESET])
+        );
+    }
+}
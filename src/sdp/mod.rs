@@ -0,0 +1,333 @@
+//! Driver for Sensirion SDP3x and SDP8xx differential pressure sensors.
+//!
+//! The SDP31, SDP32, SDP33 and SDP810 are digital differential pressure
+//! sensors with a 16-bit signed output, an on-chip temperature sensor and a
+//! CRC-8 after every data word. They are read in continuous-measurement mode:
+//! once started, the sensor updates its output every 0.5 ms and each I2C read
+//! returns the latest (optionally averaged) value.
+//!
+//! The drivers return the same [`Reading`] type as the MS4525DO and implement
+//! [`crate::sensor`]'s `DifferentialPressureSensor`, so downstream airspeed
+//! code works with any of the supported sensors.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use ms4525do::sdp::blocking::Sdp;
+//! use ms4525do::sdp::{ContinuousMode, SdpModel};
+//!
+//! let mut sensor = Sdp::new(i2c, SdpModel::Sdp31);
+//! sensor.start_continuous(ContinuousMode::default(), &mut delay)?;
+//!
+//! let reading = sensor.read_data()?;
+//! println!("Airspeed: {} m/s", reading.airspeed());
+//! ```
+
+#[cfg(feature = "async")]
+pub mod async_api;
+
+#[cfg(feature = "blocking")]
+pub mod blocking;
+
+use crate::common::Reading;
+
+#[cfg(feature = "defmt")]
+use defmt::Format;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Default 7-bit I2C address of SDP3x parts
+pub const SDP3X_ADDR: u8 = 0x21;
+
+/// Default 7-bit I2C address of SDP8xx parts (SDP8x0)
+pub const SDP8XX_ADDR: u8 = 0x25;
+
+/// Stop continuous measurement command
+pub const CMD_STOP_CONTINUOUS: u16 = 0x3FF9;
+
+/// I2C general call address, used for the soft reset
+pub const GENERAL_CALL_ADDR: u8 = 0x00;
+
+/// Soft reset command byte, sent to the general call address
+pub const CMD_SOFT_RESET: u8 = 0x06;
+
+/// Time to wait after a soft reset before the sensor accepts commands, in milliseconds
+pub const RESET_DELAY_MS: u32 = 20;
+
+/// Time from starting continuous measurement to the first valid result, in milliseconds
+pub const START_DELAY_MS: u32 = 8;
+
+/// Time to wait after stopping continuous measurement, in microseconds
+pub const STOP_DELAY_US: u32 = 500;
+
+/// Temperature scale factor, in counts per °C
+pub const TEMPERATURE_SCALE_FACTOR: f32 = 200.0;
+
+/// Size of a full measurement (pressure, temperature and scale factor words with CRCs)
+pub const MEASUREMENT_SIZE: usize = 9;
+
+/// Size of a measurement without the scale factor word
+pub const SHORT_MEASUREMENT_SIZE: usize = 6;
+
+/// Errors that can occur during SDP sensor operations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SdpError {
+    /// I2C communication error occurred.
+    I2cError,
+
+    /// A data word does not match its CRC-8 checksum.
+    CrcMismatch,
+
+    /// The sensor reported a differential pressure scale factor of zero.
+    InvalidScaleFactor,
+
+    /// A measurement was requested while continuous measurement is stopped.
+    ///
+    /// Call `start_continuous` first.
+    NotMeasuring,
+}
+
+impl core::fmt::Display for SdpError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SdpError::I2cError => write!(f, "I2C communication error"),
+            SdpError::CrcMismatch => write!(f, "CRC mismatch"),
+            SdpError::InvalidScaleFactor => write!(f, "Invalid scale factor"),
+            SdpError::NotMeasuring => write!(f, "Continuous measurement not started"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SdpError {}
+
+/// Supported SDP part.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SdpModel {
+    /// SDP31, ±500 Pa
+    Sdp31,
+    /// SDP32, ±125 Pa
+    Sdp32,
+    /// SDP33, ±1500 Pa
+    Sdp33,
+    /// SDP810-500Pa
+    Sdp810Range500,
+    /// SDP810-125Pa
+    Sdp810Range125,
+}
+
+impl SdpModel {
+    /// Returns the default I2C address of this part.
+    pub fn address(&self) -> u8 {
+        match self {
+            SdpModel::Sdp31 | SdpModel::Sdp32 | SdpModel::Sdp33 => SDP3X_ADDR,
+            SdpModel::Sdp810Range500 | SdpModel::Sdp810Range125 => SDP8XX_ADDR,
+        }
+    }
+
+    /// Returns the datasheet differential pressure scale factor, in counts per Pascal.
+    ///
+    /// The drivers use the scale factor reported by the sensor itself; this
+    /// value is useful for checking it.
+    pub fn nominal_scale_factor(&self) -> u16 {
+        match self {
+            SdpModel::Sdp31 | SdpModel::Sdp810Range500 => 60,
+            SdpModel::Sdp32 | SdpModel::Sdp810Range125 => 240,
+            SdpModel::Sdp33 => 20,
+        }
+    }
+}
+
+/// Temperature compensation applied to the differential pressure output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum TemperatureCompensation {
+    /// Compensated for mass flow measurement across a flow restriction
+    MassFlow,
+    /// Compensated for plain differential pressure, as needed by a pitot tube
+    #[default]
+    DifferentialPressure,
+}
+
+/// Continuous measurement configuration.
+///
+/// The default is differential pressure compensation with averaging, as
+/// used for pitot tubes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ContinuousMode {
+    /// Temperature compensation of the pressure output
+    pub compensation: TemperatureCompensation,
+    /// Average all samples taken since the previous read instead of
+    /// returning only the latest one
+    pub average_till_read: bool,
+}
+
+impl Default for ContinuousMode {
+    fn default() -> Self {
+        Self {
+            compensation: TemperatureCompensation::DifferentialPressure,
+            average_till_read: true,
+        }
+    }
+}
+
+impl ContinuousMode {
+    /// Returns the 16-bit command starting this measurement mode.
+    pub fn command(&self) -> u16 {
+        match (self.compensation, self.average_till_read) {
+            (TemperatureCompensation::MassFlow, true) => 0x3603,
+            (TemperatureCompensation::MassFlow, false) => 0x3608,
+            (TemperatureCompensation::DifferentialPressure, true) => 0x3615,
+            (TemperatureCompensation::DifferentialPressure, false) => 0x361E,
+        }
+    }
+}
+
+/// Computes the Sensirion CRC-8 of a data word.
+///
+/// Polynomial 0x31 (x^8 + x^5 + x^4 + 1), initial value 0xFF, no reflection.
+pub fn crc8(data: &[u8]) -> u8 {
+    let mut crc = 0xFFu8;
+    for &byte in data {
+        crc ^= byte;
+        for _ in 0..8 {
+            crc = if crc & 0x80 != 0 {
+                (crc << 1) ^ 0x31
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+/// Checks the CRC of the `index`-th 3-byte word of a measurement and returns its value.
+fn word(buf: &[u8], index: usize) -> Result<u16, SdpError> {
+    let chunk = &buf[3 * index..3 * index + 3];
+    if crc8(&chunk[..2]) != chunk[2] {
+        return Err(SdpError::CrcMismatch);
+    }
+    Ok(u16::from_be_bytes([chunk[0], chunk[1]]))
+}
+
+/// Raw, CRC-checked measurement words.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+pub struct RawMeasurement {
+    /// Signed differential pressure counts
+    pub differential_pressure: i16,
+    /// Signed temperature counts
+    pub temperature: i16,
+    /// Differential pressure scale factor, if it was read
+    pub scale_factor: Option<u16>,
+}
+
+impl RawMeasurement {
+    /// Parses a measurement read from the sensor.
+    ///
+    /// # Arguments
+    ///
+    /// * `buf` - [`SHORT_MEASUREMENT_SIZE`] or [`MEASUREMENT_SIZE`] bytes read from the sensor
+    ///
+    /// # Errors
+    ///
+    /// * `SdpError::CrcMismatch` - A data word fails its CRC-8 check
+    /// * `SdpError::InvalidScaleFactor` - The scale factor word is zero
+    pub fn parse(buf: &[u8]) -> Result<Self, SdpError> {
+        let differential_pressure = word(buf, 0)? as i16;
+        let temperature = word(buf, 1)? as i16;
+        let scale_factor = if buf.len() >= MEASUREMENT_SIZE {
+            match word(buf, 2)? {
+                0 => return Err(SdpError::InvalidScaleFactor),
+                scale_factor => Some(scale_factor),
+            }
+        } else {
+            None
+        };
+        Ok(Self {
+            differential_pressure,
+            temperature,
+            scale_factor,
+        })
+    }
+
+    /// Converts the raw words to physical units.
+    ///
+    /// # Arguments
+    ///
+    /// * `scale_factor` - Differential pressure scale factor in counts per Pascal
+    pub fn to_reading(&self, scale_factor: u16) -> Reading {
+        Reading {
+            differential_pressure_pa: self.differential_pressure as f32 / scale_factor as f32,
+            temperature_c: self.temperature as f32 / TEMPERATURE_SCALE_FACTOR,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Encodes words the way the sensor sends them
+    fn frame<const N: usize>(words: [u16; 3]) -> [u8; N] {
+        let mut buf = [0u8; N];
+        for (chunk, word) in buf.chunks_mut(3).zip(words) {
+            chunk[..2].copy_from_slice(&word.to_be_bytes());
+            chunk[2] = crc8(&chunk[..2]);
+        }
+        buf
+    }
+
+    #[test]
+    fn test_crc8_reference() {
+        // Example from the SDP3x datasheet
+        assert_eq!(crc8(&[0xBE, 0xEF]), 0x92);
+    }
+
+    #[test]
+    fn test_parse_measurement() {
+        let buf: [u8; MEASUREMENT_SIZE] = frame([(-600i16) as u16, 5000, 60]);
+        let raw = RawMeasurement::parse(&buf).unwrap();
+        assert_eq!(raw.scale_factor, Some(60));
+
+        let reading = raw.to_reading(60);
+        assert_eq!(reading.differential_pressure_pa, -10.0);
+        assert_eq!(reading.temperature_c, 25.0);
+
+        let buf: [u8; SHORT_MEASUREMENT_SIZE] = frame([1200, 5000, 0]);
+        let raw = RawMeasurement::parse(&buf).unwrap();
+        assert_eq!(raw.scale_factor, None);
+        assert_eq!(raw.to_reading(240).differential_pressure_pa, 5.0);
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_data() {
+        let mut buf: [u8; MEASUREMENT_SIZE] = frame([600, 5000, 60]);
+        buf[4] ^= 0x01;
+        assert_eq!(RawMeasurement::parse(&buf), Err(SdpError::CrcMismatch));
+
+        let buf: [u8; MEASUREMENT_SIZE] = frame([600, 5000, 0]);
+        assert_eq!(
+            RawMeasurement::parse(&buf),
+            Err(SdpError::InvalidScaleFactor)
+        );
+    }
+
+    #[test]
+    fn test_continuous_mode_commands() {
+        assert_eq!(ContinuousMode::default().command(), 0x3615);
+        let mode = ContinuousMode {
+            compensation: TemperatureCompensation::MassFlow,
+            average_till_read: false,
+        };
+        assert_eq!(mode.command(), 0x3608);
+    }
+}
@@ -0,0 +1,409 @@
+//! Self-test and plug-in detection.
+//!
+//! `self_test` on both drivers confirms at boot that an MS4525DO is on the
+//! bus and working, and returns a [`SelfTestReport`] with the outcome of each
+//! check rather than a single [`Ms4525doError`]. It takes a short window of
+//! readings and checks that:
+//!
+//! 1. The sensor acknowledges its address (plug-in detection)
+//! 2. Every reading shows the `NormalOperation` → `StaleData` status progression
+//! 3. The temperature is plausible: within a tolerance of the board
+//!    temperature if one is given, otherwise inside the operating range
+//! 4. The differential pressure noise over the window is below a limit
+//! 5. The bridge output stays inside the output band of the configured
+//!    transfer function
+//!
+//! The self-test does not retry and does not update the health statistics,
+//! retry counters or stuck-sensor detector of the driver.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use ms4525do::self_test::SelfTestConfig;
+//!
+//! let config = SelfTestConfig {
+//!     board_temperature_c: Some(imu.temperature()),
+//!     ..SelfTestConfig::default()
+//! };
+//! let report = sensor.self_test(&mut delay, &config);
+//! if !report.passed() {
+//!     prearm_fail!("Airspeed: {:?}", report);
+//! }
+//! ```
+
+use crate::common::RangeStatus;
+use crate::Ms4525doError;
+
+#[cfg(any(feature = "blocking", feature = "async"))]
+use crate::common::{check_range, validate_frames, DATA_SIZE};
+#[cfg(any(feature = "blocking", feature = "async"))]
+use crate::transfer::TransferFunction;
+
+#[cfg(feature = "defmt")]
+use defmt::Format;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Operating temperature range of the MS4525DO, in °C.
+pub const OPERATING_RANGE_C: (f32, f32) = (-50.0, 150.0);
+
+/// Configuration of a self-test.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SelfTestConfig {
+    /// Number of readings in the test window
+    pub samples: u16,
+    /// Time between readings, in milliseconds
+    pub interval_ms: u32,
+    /// Board temperature to compare the sensor temperature to, in °C
+    pub board_temperature_c: Option<f32>,
+    /// Maximum difference to the board temperature, in °C
+    pub max_temperature_delta_c: f32,
+    /// Maximum standard deviation of the differential pressure, in Pascals
+    pub max_noise_pa: f32,
+}
+
+impl Default for SelfTestConfig {
+    /// 20 readings 5 ms apart, 20 °C temperature tolerance, 5 Pa noise limit.
+    ///
+    /// One count of the ±1 PSI MS4525DO is about 1 Pa, and its output noise is
+    /// 1-2 counts RMS.
+    fn default() -> Self {
+        Self {
+            samples: 20,
+            interval_ms: 5,
+            board_temperature_c: None,
+            max_temperature_delta_c: 20.0,
+            max_noise_pa: 5.0,
+        }
+    }
+}
+
+/// Outcome of one self-test check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum CheckResult {
+    /// The check passed
+    Passed,
+    /// The check failed
+    Failed,
+    /// The check could not run, e.g. because the sensor did not answer
+    Skipped,
+}
+
+/// Detailed result of a self-test.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SelfTestReport {
+    /// The sensor acknowledged its address
+    pub present: bool,
+    /// Every reading showed the `NormalOperation` → `StaleData` progression
+    pub status_progression: CheckResult,
+    /// The mean temperature is plausible
+    pub temperature: CheckResult,
+    /// The differential pressure noise is below the limit
+    pub noise: CheckResult,
+    /// Every reading was inside the output band of the transfer function
+    pub output_band: CheckResult,
+    /// Number of readings attempted
+    pub samples: u16,
+    /// Number of readings that passed validation
+    pub valid_samples: u16,
+    /// Number of readings that failed on the bus
+    pub i2c_errors: u16,
+    /// First error seen, if any
+    pub first_error: Option<Ms4525doError>,
+    /// First reading outside the output band, or `InRange`
+    pub range_status: RangeStatus,
+    /// Mean temperature of the valid readings, in °C
+    pub temperature_c: Option<f32>,
+    /// Mean differential pressure of the valid readings, in Pascals
+    pub mean_pressure_pa: Option<f32>,
+    /// Standard deviation of the differential pressure, in Pascals
+    pub noise_pa: Option<f32>,
+}
+
+impl SelfTestReport {
+    /// Returns true if the sensor is present and no check failed.
+    pub fn passed(&self) -> bool {
+        self.present
+            && [
+                self.status_progression,
+                self.temperature,
+                self.noise,
+                self.output_band,
+            ]
+            .iter()
+            .all(|check| *check != CheckResult::Failed)
+    }
+}
+
+/// Accumulates the readings of a self-test window.
+#[cfg(any(feature = "blocking", feature = "async"))]
+pub(crate) struct SelfTestRun {
+    config: SelfTestConfig,
+    report: SelfTestReport,
+    status_failed: bool,
+    temperature_sum: f32,
+    pressure_mean: f32,
+    pressure_m2: f32,
+}
+
+#[cfg(any(feature = "blocking", feature = "async"))]
+impl SelfTestRun {
+    pub(crate) fn new(config: &SelfTestConfig) -> Self {
+        Self {
+            config: *config,
+            report: SelfTestReport {
+                present: false,
+                status_progression: CheckResult::Skipped,
+                temperature: CheckResult::Skipped,
+                noise: CheckResult::Skipped,
+                output_band: CheckResult::Skipped,
+                samples: 0,
+                valid_samples: 0,
+                i2c_errors: 0,
+                first_error: None,
+                range_status: RangeStatus::InRange,
+                temperature_c: None,
+                mean_pressure_pa: None,
+                noise_pa: None,
+            },
+            status_failed: false,
+            temperature_sum: 0.0,
+            pressure_mean: 0.0,
+            pressure_m2: 0.0,
+        }
+    }
+
+    /// Returns the number of readings to take.
+    pub(crate) fn samples(&self) -> u16 {
+        self.config.samples.max(1)
+    }
+
+    /// Returns the time between readings, in milliseconds.
+    pub(crate) fn interval_ms(&self) -> u32 {
+        self.config.interval_ms
+    }
+
+    /// Records the packets of one reading.
+    ///
+    /// # Returns
+    ///
+    /// `false` if the test should stop because the sensor did not answer the
+    /// first reading
+    pub(crate) fn record<T: TransferFunction>(
+        &mut self,
+        frames: Result<[[u8; DATA_SIZE]; 2], Ms4525doError>,
+        transfer: &T,
+    ) -> bool {
+        self.report.samples += 1;
+        let result = frames.and_then(|[data_1, data_2]| validate_frames(&data_1, &data_2));
+
+        let (bridge_data, temperature) = match result {
+            Ok(counts) => counts,
+            Err(e) => {
+                self.report.first_error.get_or_insert(e);
+                if e == Ms4525doError::I2cError {
+                    self.report.i2c_errors += 1;
+                    // A sensor that does not acknowledge the first request is not there
+                    return self.report.present;
+                }
+                self.report.present = true;
+                self.status_failed = true;
+                return true;
+            }
+        };
+        self.report.present = true;
+
+        if let Err(Ms4525doError::DataOutOfRange(status)) =
+            check_range(bridge_data, transfer.output_band())
+        {
+            if self.report.range_status == RangeStatus::InRange {
+                self.report.range_status = status;
+                self.report
+                    .first_error
+                    .get_or_insert(Ms4525doError::DataOutOfRange(status));
+            }
+        }
+
+        // Welford's algorithm for the pressure mean and variance
+        self.report.valid_samples += 1;
+        let pressure = transfer.pressure_pa(bridge_data);
+        let delta = pressure - self.pressure_mean;
+        self.pressure_mean += delta / self.report.valid_samples as f32;
+        self.pressure_m2 += delta * (pressure - self.pressure_mean);
+        self.temperature_sum += transfer.temperature_c(temperature);
+        true
+    }
+
+    /// Evaluates the checks over the recorded readings.
+    pub(crate) fn finish(self) -> SelfTestReport {
+        let mut report = self.report;
+        if !report.present {
+            return report;
+        }
+
+        report.status_progression = if self.status_failed {
+            CheckResult::Failed
+        } else {
+            CheckResult::Passed
+        };
+
+        let valid = report.valid_samples;
+        if valid == 0 {
+            return report;
+        }
+
+        report.output_band = if report.range_status == RangeStatus::InRange {
+            CheckResult::Passed
+        } else {
+            CheckResult::Failed
+        };
+
+        let temperature_c = self.temperature_sum / valid as f32;
+        let plausible = match self.config.board_temperature_c {
+            Some(board) => {
+                libm::fabsf(temperature_c - board) <= self.config.max_temperature_delta_c
+            }
+            None => temperature_c > OPERATING_RANGE_C.0 && temperature_c < OPERATING_RANGE_C.1,
+        };
+        report.temperature = if plausible {
+            CheckResult::Passed
+        } else {
+            CheckResult::Failed
+        };
+        report.temperature_c = Some(temperature_c);
+        report.mean_pressure_pa = Some(self.pressure_mean);
+
+        if valid >= 2 {
+            let noise_pa = libm::sqrtf(self.pressure_m2 / (valid - 1) as f32);
+            report.noise = if noise_pa <= self.config.max_noise_pa {
+                CheckResult::Passed
+            } else {
+                CheckResult::Failed
+            };
+            report.noise_pa = Some(noise_pa);
+        }
+        report
+    }
+}
+
+#[cfg(all(test, any(feature = "blocking", feature = "async")))]
+mod tests {
+    use super::*;
+    use crate::mock::{frames, SensorMock};
+    use crate::transfer::Ms4525doTransfer;
+
+    fn run(readings: &[Result<[[u8; DATA_SIZE]; 2], Ms4525doError>]) -> SelfTestReport {
+        let config = SelfTestConfig {
+            board_temperature_c: Some(25.0),
+            ..SelfTestConfig::default()
+        };
+        let mut run = SelfTestRun::new(&config);
+        for reading in readings {
+            if !run.record(*reading, &Ms4525doTransfer::default()) {
+                break;
+            }
+        }
+        run.finish()
+    }
+
+    #[test]
+    fn test_healthy_sensor_passes() {
+        // 25 °C is 767 counts
+        let readings = [8191, 8192, 8193, 8192].map(|bridge| Ok(frames(bridge, 767)));
+        let report = run(&readings);
+        assert!(report.passed(), "{:?}", report);
+        assert_eq!(report.valid_samples, 4);
+        assert!(report.noise_pa.unwrap() < 1.0);
+        assert!((report.temperature_c.unwrap() - 25.0).abs() < 0.2);
+        assert_eq!(report.first_error, None);
+    }
+
+    #[test]
+    fn test_missing_sensor() {
+        let report = run(&[Err(Ms4525doError::I2cError); 3]);
+        assert!(!report.present);
+        assert!(!report.passed());
+        assert_eq!(report.samples, 1);
+        assert_eq!(report.status_progression, CheckResult::Skipped);
+    }
+
+    #[test]
+    fn test_failed_checks() {
+        // Second reading: two fresh packets
+        let [fresh, _] = frames(8192, 767);
+        let readings = [
+            Ok(frames(8192, 1100)),
+            Ok([fresh, fresh]),
+            Ok(frames(8400, 1100)),
+            Ok(frames(15000, 1100)),
+        ];
+        let report = run(&readings);
+        assert!(report.present);
+        assert!(!report.passed());
+        assert_eq!(report.status_progression, CheckResult::Failed);
+        assert_eq!(report.temperature, CheckResult::Failed);
+        assert_eq!(report.noise, CheckResult::Failed);
+        assert_eq!(report.output_band, CheckResult::Failed);
+        assert_eq!(report.range_status, RangeStatus::SaturatedHigh);
+        assert_eq!(report.valid_samples, 3);
+        assert!(matches!(
+            report.first_error,
+            Some(Ms4525doError::InvalidStatus(_))
+        ));
+    }
+
+    fn driver_config() -> SelfTestConfig {
+        SelfTestConfig {
+            samples: 2,
+            ..SelfTestConfig::default()
+        }
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_driver_self_test() {
+        use crate::blocking::Ms4525do;
+        use crate::mock::NoDelay;
+
+        let readings = [Some(frames(8192, 767)), Some(frames(8193, 767))];
+        let mut sensor = Ms4525do::new(SensorMock::new(readings));
+        let report = sensor.self_test(&mut NoDelay, &driver_config());
+        assert!(report.passed(), "{:?}", report);
+        assert_eq!(report.valid_samples, 2);
+        assert_eq!(sensor.health().attempts(), 0);
+        sensor.release().assert_finished();
+
+        // Nothing acknowledges the address: the test stops after the probe
+        let mut sensor = Ms4525do::new(SensorMock::new([None]));
+        let report = sensor.self_test(&mut NoDelay, &driver_config());
+        assert!(!report.present);
+        assert_eq!(report.noise, CheckResult::Skipped);
+        sensor.release().assert_finished();
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_async_driver_self_test() {
+        use crate::async_api::Ms4525do;
+        use futures_executor::block_on;
+
+        let readings = [Some(frames(8192, 767)), Some(frames(8193, 767))];
+        let mut sensor = Ms4525do::new(SensorMock::new(readings));
+        let report = block_on(sensor.self_test(&driver_config()));
+        assert!(report.passed(), "{:?}", report);
+        assert_eq!(sensor.health().attempts(), 0);
+        sensor.release().assert_finished();
+
+        let mut sensor = Ms4525do::new(SensorMock::new([None]));
+        let report = block_on(sensor.self_test(&driver_config()));
+        assert!(!report.present);
+        sensor.release().assert_finished();
+    }
+}
@@ -0,0 +1,65 @@
+//! Sensor-agnostic differential pressure trait.
+//!
+//! [`blocking::DifferentialPressureSensor`] and
+//! [`async_api::DifferentialPressureSensor`] are implemented by every driver in
+//! this crate, so code downstream of the driver (airspeed calculation,
+//! logging, telemetry) can be written once and used with the MS4525DO,
+//! MS5525DSO or Sensirion SDP parts.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use ms4525do::sensor::blocking::DifferentialPressureSensor;
+//!
+//! fn airspeed<S: DifferentialPressureSensor, D: DelayNs>(sensor: &mut S, delay: &mut D) -> Option<f32> {
+//!     sensor.read_pressure(delay).ok().map(|reading| reading.airspeed())
+//! }
+//! ```
+
+/// Blocking form of the sensor trait.
+#[cfg(feature = "blocking")]
+pub mod blocking {
+    use crate::Reading;
+    use core::fmt::Debug;
+    use embedded_hal::delay::DelayNs;
+
+    /// A differential pressure sensor read with blocking I2C.
+    pub trait DifferentialPressureSensor {
+        /// Driver-specific error type.
+        type Error: Debug;
+
+        /// Reads a validated differential pressure and temperature measurement.
+        ///
+        /// # Arguments
+        ///
+        /// * `delay` - Delay provider for sensors that need to wait for a conversion
+        ///
+        /// # Returns
+        ///
+        /// * `Ok(Reading)` - Differential pressure in Pascals and temperature in °C
+        /// * `Err(Self::Error)` - Error if communication fails or data is invalid
+        fn read_pressure<D: DelayNs>(&mut self, delay: &mut D) -> Result<Reading, Self::Error>;
+    }
+}
+
+/// Async form of the sensor trait.
+#[cfg(feature = "async")]
+pub mod async_api {
+    use crate::Reading;
+    use core::fmt::Debug;
+
+    /// A differential pressure sensor read with async I2C.
+    #[allow(async_fn_in_trait)]
+    pub trait DifferentialPressureSensor {
+        /// Driver-specific error type.
+        type Error: Debug;
+
+        /// Reads a validated differential pressure and temperature measurement.
+        ///
+        /// # Returns
+        ///
+        /// * `Ok(Reading)` - Differential pressure in Pascals and temperature in °C
+        /// * `Err(Self::Error)` - Error if communication fails or data is invalid
+        async fn read_pressure(&mut self) -> Result<Reading, Self::Error>;
+    }
+}
@@ -0,0 +1,399 @@
+//! Using the drivers on a shared I2C bus.
+//!
+//! The MS4525DO usually shares its bus with a barometer, a magnetometer or a
+//! GPS compass. Create the driver with `Ms4525do::new_shared` or
+//! `Ms4525do::new_shared_with_address` on one of the bus sharing devices of
+//! `embedded-hal-bus` or `embassy-embedded-hal`:
+//!
+//! | Driver | Sharing between | Device type |
+//! |--------|-----------------|-------------|
+//! | [`blocking`](crate::blocking) | code in one thread or context | `embedded_hal_bus::i2c::RefCellDevice` |
+//! | [`blocking`](crate::blocking) | interrupts and main loop | `embedded_hal_bus::i2c::CriticalSectionDevice` |
+//! | [`blocking`](crate::blocking) | threads (`std`) | `embedded_hal_bus::i2c::MutexDevice` |
+//! | [`async_api`](crate::async_api) | Embassy tasks | `embassy_embedded_hal::shared_bus::asynch::i2c::I2cDevice` |
+//!
+//! # Transaction Guarantees
+//!
+//! A shared-bus device locks the bus for the duration of one `transaction`
+//! call, so only what the driver puts in one transaction is atomic. The
+//! `new_shared` constructors select [`FetchMode::Pipelined`](crate::FetchMode::Pipelined),
+//! with which `read_data` issues:
+//!
+//! 1. On the first call, and after a reading that failed validation or on the
+//!    bus: the measurement request as one write transaction, followed by the
+//!    2 ms conversion delay, during which the bus is free
+//! 2. One transaction of `Read`, `Write(READ_MR)`, `Read`: both data packets,
+//!    and between them the measurement request for the next call
+//!
+//! Atomic: the two packets, and the request for the next reading sent between
+//! them. No other device's transaction can land in between.
+//!
+//! Not atomic: the time between two calls, in which the sensor converts and
+//! other devices use the bus. No mode holds the bus through a conversion, as
+//! that would block every other device for the conversion time of each
+//! reading. Traffic to other addresses does not affect the conversion.
+//!
+//! With the other constructors, the default [`FetchMode::Transaction`](crate::FetchMode::Transaction)
+//! fetches the two packets in one transaction but sends the measurement
+//! request in a transaction of its own before the conversion delay, and
+//! [`FetchMode::SeparateReads`](crate::FetchMode::SeparateReads) makes no
+//! guarantee at all: other traffic can land between the two reads.
+//!
+//! If something else reads the sensor itself between the request and the
+//! fetch, it consumes the fresh packet: the double-read validation then sees a
+//! stale first packet, reports [`Ms4525doError::InvalidStatus`](crate::Ms4525doError::InvalidStatus)
+//! and the read is retried according to the retry policy. Both transaction
+//! modes need an SCL clock above 20 kHz, see [`FetchMode`](crate::FetchMode).
+//!
+//! # Example
+//!
+//! ```ignore
+//! use core::cell::RefCell;
+//! use embedded_hal_bus::i2c::RefCellDevice;
+//! use ms4525do::blocking::Ms4525do;
+//!
+//! let bus = RefCell::new(i2c);
+//! let mut airspeed = Ms4525do::new_shared(RefCellDevice::new(&bus));
+//! let mut baro = Bmp388::new(RefCellDevice::new(&bus));
+//!
+//! let (pressure_pa, temp_c) = airspeed.read_data(&mut delay)?;
+//! let altitude = baro.altitude()?;
+//! ```
+//!
+//! With Embassy, the bus lives in a static `Mutex` shared by the tasks:
+//!
+//! ```ignore
+//! use embassy_embedded_hal::shared_bus::asynch::i2c::I2cDevice;
+//! use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+//! use embassy_sync::mutex::Mutex;
+//! use ms4525do::async_api::Ms4525do;
+//! use static_cell::StaticCell;
+//!
+//! static I2C_BUS: StaticCell<Mutex<CriticalSectionRawMutex, I2c<'static, Async>>> = StaticCell::new();
+//!
+//! let bus = I2C_BUS.init(Mutex::new(i2c));
+//! let airspeed = Ms4525do::new_shared(I2cDevice::new(bus));
+//! let compass = Ist8310::new(I2cDevice::new(bus));
+//! spawner.spawn(airspeed_task(airspeed)).unwrap();
+//! spawner.spawn(compass_task(compass)).unwrap();
+//! ```
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{validate_frames, DATA_SIZE, MS4525DO_ADDR, READ_MR};
+    use crate::FetchMode;
+    use embassy_time::{Duration, Instant};
+    use embedded_hal::i2c::{ErrorKind, ErrorType, Operation};
+
+    const BARO_ADDR: u8 = 0x77;
+
+    /// Conversion time of the simulated sensor
+    const CONVERSION_TIME: Duration = Duration::from_millis(1);
+
+    /// One transaction seen on the bus
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    struct Transfer {
+        address: u8,
+        /// Writes on the wire
+        writes: u8,
+        /// Reads on the wire, after merging adjacent read operations
+        reads: u8,
+    }
+
+    /// Bus with an MS4525DO and a barometer, logging every transaction.
+    ///
+    /// Follows the `embedded-hal` transaction contract: adjacent operations of
+    /// the same direction are one operation on the wire, and only a change of
+    /// direction issues a repeated start. The sensor answers each read with
+    /// one packet followed by `0xff` bytes, and marks the packet fresh only if
+    /// a conversion completed since the last read.
+    struct Bus {
+        log: [Option<Transfer>; 64],
+        len: usize,
+        /// Start of the conversion in progress
+        conversion: Option<Instant>,
+        /// A completed conversion has not been read yet
+        fresh: bool,
+    }
+
+    impl Bus {
+        fn new() -> Self {
+            Self {
+                log: [None; 64],
+                len: 0,
+                conversion: None,
+                fresh: false,
+            }
+        }
+
+        /// Returns the packet the sensor clocks out after a start condition
+        fn packet(&mut self) -> [u8; DATA_SIZE] {
+            if let Some(start) = self.conversion {
+                if start.elapsed() >= CONVERSION_TIME {
+                    self.conversion = None;
+                    self.fresh = true;
+                }
+            }
+            let status = if core::mem::take(&mut self.fresh) {
+                0x20
+            } else {
+                0xa0
+            };
+            [status, 0x00, 0x5f, 0xe0]
+        }
+
+        fn transfer(&mut self, address: u8, operations: &mut [Operation<'_>]) {
+            let mut entry = Transfer {
+                address,
+                writes: 0,
+                reads: 0,
+            };
+            let mut packet = [0xff; DATA_SIZE];
+            let mut position = 0;
+            let mut reading = false;
+            for operation in operations {
+                match operation {
+                    Operation::Write(_) => {
+                        entry.writes += 1;
+                        reading = false;
+                        if address == MS4525DO_ADDR {
+                            self.conversion = Some(Instant::now());
+                        }
+                    }
+                    Operation::Read(buf) => {
+                        if !reading {
+                            entry.reads += 1;
+                            reading = true;
+                            position = 0;
+                            if address == MS4525DO_ADDR {
+                                packet = self.packet();
+                            }
+                        }
+                        if address == MS4525DO_ADDR {
+                            for byte in buf.iter_mut() {
+                                *byte = packet.get(position).copied().unwrap_or(0xff);
+                                position += 1;
+                            }
+                        }
+                    }
+                }
+            }
+            self.log[self.len] = Some(entry);
+            self.len += 1;
+        }
+
+        fn transfers(&self) -> impl Iterator<Item = Transfer> + '_ {
+            self.log[..self.len].iter().flatten().copied()
+        }
+
+        /// Checks the `(writes, reads)` of every sensor transaction
+        fn assert_sensor_transactions(&self, expected: &[(u8, u8)]) {
+            let mut sensor = self.transfers().filter(|t| t.address == MS4525DO_ADDR);
+            for &(writes, reads) in expected {
+                let transfer = sensor.next().unwrap();
+                assert_eq!((transfer.writes, transfer.reads), (writes, reads));
+            }
+            assert_eq!(sensor.next(), None);
+        }
+
+        /// Checks that the barometer used the bus between the first
+        /// measurement request and the fetch of its data
+        fn assert_baro_during_conversion(&self) {
+            let position = |address| self.transfers().position(|t| t.address == address);
+            let request = position(MS4525DO_ADDR).unwrap();
+            let baro = position(BARO_ADDR).unwrap();
+            let fetch = self
+                .transfers()
+                .skip(request + 1)
+                .position(|t| t.address == MS4525DO_ADDR)
+                .unwrap()
+                + request
+                + 1;
+            assert!(request < baro && baro < fetch);
+        }
+    }
+
+    impl ErrorType for Bus {
+        type Error = ErrorKind;
+    }
+
+    impl embedded_hal::i2c::I2c for Bus {
+        fn transaction(
+            &mut self,
+            address: u8,
+            operations: &mut [Operation<'_>],
+        ) -> Result<(), ErrorKind> {
+            self.transfer(address, operations);
+            Ok(())
+        }
+    }
+
+    #[cfg(feature = "async")]
+    impl embedded_hal_async::i2c::I2c for Bus {
+        async fn transaction(
+            &mut self,
+            address: u8,
+            operations: &mut [Operation<'_>],
+        ) -> Result<(), ErrorKind> {
+            self.transfer(address, operations);
+            Ok(())
+        }
+    }
+
+    /// Delay that lets another driver use the bus while the sensor converts
+    #[cfg(feature = "blocking")]
+    struct BaroDuringDelay<I2C>(I2C);
+
+    #[cfg(feature = "blocking")]
+    impl<I2C: embedded_hal::i2c::I2c> embedded_hal::delay::DelayNs for BaroDuringDelay<I2C> {
+        fn delay_ns(&mut self, ns: u32) {
+            let mut pressure = [0u8; 3];
+            self.0
+                .write_read(BARO_ADDR, &[0x04], &mut pressure)
+                .unwrap();
+            embassy_time::block_for(Duration::from_nanos(ns.into()));
+        }
+    }
+
+    #[test]
+    fn test_adjacent_reads_merge() {
+        use embedded_hal::i2c::I2c;
+
+        let mut bus = Bus::new();
+        bus.write(MS4525DO_ADDR, &[READ_MR]).unwrap();
+        embassy_time::block_for(CONVERSION_TIME);
+
+        // Two adjacent reads are one 8-byte read, not two packets
+        let mut data_1 = [0u8; DATA_SIZE];
+        let mut data_2 = [0u8; DATA_SIZE];
+        bus.transaction(
+            MS4525DO_ADDR,
+            &mut [Operation::Read(&mut data_1), Operation::Read(&mut data_2)],
+        )
+        .unwrap();
+        assert_eq!(data_2, [0xff; DATA_SIZE]);
+        assert!(validate_frames(&data_1, &data_2).is_err());
+    }
+
+    /// Sensor transactions of two readings with the `new_shared` constructors:
+    /// the request of the second reading is sent between the packets of the first
+    const SHARED_READS: [(u8, u8); 3] = [(1, 0), (1, 2), (1, 2)];
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_refcell_device() {
+        use crate::blocking::Ms4525do;
+        use core::cell::RefCell;
+        use embedded_hal_bus::i2c::RefCellDevice;
+
+        let bus = RefCell::new(Bus::new());
+        let mut sensor = Ms4525do::new_shared(RefCellDevice::new(&bus));
+        assert_eq!(sensor.fetch_mode(), FetchMode::Pipelined);
+        let mut delay = BaroDuringDelay(RefCellDevice::new(&bus));
+
+        assert!(sensor.read_data(&mut delay).is_ok());
+        embassy_time::block_for(CONVERSION_TIME);
+        assert!(sensor.read_data(&mut delay).is_ok());
+        bus.borrow().assert_sensor_transactions(&SHARED_READS);
+        bus.borrow().assert_baro_during_conversion();
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_refcell_device_fetch_modes() {
+        use crate::blocking::Ms4525do;
+        use core::cell::RefCell;
+        use embedded_hal_bus::i2c::RefCellDevice;
+
+        for (fetch_mode, expected) in [
+            (FetchMode::Transaction, &[(1, 0), (1, 2)][..]),
+            (FetchMode::SeparateReads, &[(1, 0), (0, 1), (0, 1)][..]),
+        ] {
+            let bus = RefCell::new(Bus::new());
+            let mut sensor = Ms4525do::new(RefCellDevice::new(&bus));
+            sensor.set_fetch_mode(fetch_mode);
+            let mut delay = BaroDuringDelay(RefCellDevice::new(&bus));
+
+            assert!(sensor.read_data(&mut delay).is_ok());
+            bus.borrow().assert_sensor_transactions(expected);
+            bus.borrow().assert_baro_during_conversion();
+        }
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_critical_section_device() {
+        use crate::blocking::Ms4525do;
+        use core::cell::RefCell;
+        use embedded_hal_bus::i2c::CriticalSectionDevice;
+
+        let bus = critical_section::Mutex::new(RefCell::new(Bus::new()));
+        let mut sensor = Ms4525do::new_shared(CriticalSectionDevice::new(&bus));
+        let mut delay = BaroDuringDelay(CriticalSectionDevice::new(&bus));
+
+        assert!(sensor.read_data(&mut delay).is_ok());
+        embassy_time::block_for(CONVERSION_TIME);
+        assert!(sensor.read_data(&mut delay).is_ok());
+        critical_section::with(|cs| {
+            let bus = bus.borrow_ref(cs);
+            bus.assert_sensor_transactions(&SHARED_READS);
+            bus.assert_baro_during_conversion();
+        });
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_mutex_device() {
+        extern crate std;
+        use crate::blocking::Ms4525do;
+        use embedded_hal_bus::i2c::MutexDevice;
+
+        let bus = std::sync::Mutex::new(Bus::new());
+        let mut sensor = Ms4525do::new_shared_with_address(MutexDevice::new(&bus), MS4525DO_ADDR);
+        let mut delay = BaroDuringDelay(MutexDevice::new(&bus));
+
+        assert!(sensor.read_data(&mut delay).is_ok());
+        embassy_time::block_for(CONVERSION_TIME);
+        assert!(sensor.read_data(&mut delay).is_ok());
+        let bus = bus.lock().unwrap();
+        bus.assert_sensor_transactions(&SHARED_READS);
+        bus.assert_baro_during_conversion();
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_embassy_i2c_device() {
+        use crate::async_api::Ms4525do;
+        use embassy_embedded_hal::shared_bus::asynch::i2c::I2cDevice;
+        use embassy_sync::blocking_mutex::raw::NoopRawMutex;
+        use embassy_sync::mutex::Mutex;
+        use embassy_time::Timer;
+        use embedded_hal_async::i2c::I2c;
+
+        let bus: Mutex<NoopRawMutex, Bus> = Mutex::new(Bus::new());
+        let mut sensor = Ms4525do::new_shared(I2cDevice::new(&bus));
+        assert_eq!(sensor.fetch_mode(), FetchMode::Pipelined);
+        let mut baro = I2cDevice::new(&bus);
+
+        let sensor_task = async {
+            assert!(sensor.read_data().await.is_ok());
+            Timer::after(CONVERSION_TIME).await;
+            assert!(sensor.read_data().await.is_ok());
+        };
+        let baro_task = async {
+            // Polls the barometer every 500 µs while the sensor converts
+            for _ in 0..3 {
+                let mut pressure = [0u8; 3];
+                baro.write_read(BARO_ADDR, &[0x04], &mut pressure)
+                    .await
+                    .unwrap();
+                Timer::after(Duration::from_micros(500)).await;
+            }
+        };
+        futures_executor::block_on(futures_util::future::join(sensor_task, baro_task));
+
+        let bus = futures_executor::block_on(bus.lock());
+        bus.assert_sensor_transactions(&SHARED_READS);
+        bus.assert_baro_during_conversion();
+    }
+}
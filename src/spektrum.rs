@@ -0,0 +1,290 @@
+//! Spektrum X-Bus / SRXL2 airspeed telemetry formatting.
+//!
+//! This module turns airspeed readings into the 16-byte telemetry record that
+//! Spektrum receivers expect from the TM1000 airspeed sensor (X-Bus I2C address
+//! 0x11), and wraps that record in an SRXL2 telemetry packet for receivers that
+//! are connected over SRXL2 instead of X-Bus.
+//!
+//! All multi-byte fields are big-endian, as required by the Spektrum telemetry
+//! specification.
+//!
+//! # Example
+//!
+//! ```
+//! use ms4525do::spektrum::{encode_srxl2_telemetry, AirspeedTelemetry};
+//! use ms4525do::Reading;
+//!
+//! let mut telemetry = AirspeedTelemetry::new();
+//!
+//! // A reading from `Ms4525do::read_data`
+//! let reading = Reading::from((375.0, 20.0));
+//! let record = telemetry.update_from_reading(&reading);
+//! assert_eq!(record[0], 0x11);
+//!
+//! // Forward the same record to an SRXL2 receiver with device ID 0x21
+//! let packet = encode_srxl2_telemetry(0x21, &record);
+//! assert_eq!(packet[0], 0xA6);
+//! ```
+
+use crate::common::Reading;
+use crate::units::MetersPerSecond;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// X-Bus I2C address (and telemetry identifier) of the Spektrum airspeed sensor
+pub const SPEKTRUM_AIRSPEED_ADDR: u8 = 0x11;
+
+/// Size of a Spektrum telemetry record (16 bytes)
+pub const TELEMETRY_RECORD_SIZE: usize = 16;
+
+/// Size of an SRXL2 telemetry packet carrying one record (22 bytes)
+pub const SRXL2_TELEMETRY_PACKET_SIZE: usize = 22;
+
+/// SRXL2 packet header byte
+pub const SRXL2_HEADER: u8 = 0xA6;
+
+/// SRXL2 packet type for telemetry sensor data
+pub const SRXL2_TELEMETRY_PACKET_TYPE: u8 = 0x80;
+
+/// Tracks the current and maximum airspeed and formats them as Spektrum
+/// airspeed telemetry records.
+///
+/// The maximum airspeed is kept across calls to [`AirspeedTelemetry::update`]
+/// until [`AirspeedTelemetry::reset_max`] is called, matching the behaviour of
+/// the original TM1000 airspeed sensor.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AirspeedTelemetry {
+    max_airspeed_kmh: u16,
+}
+
+impl AirspeedTelemetry {
+    /// Creates a new telemetry formatter with the maximum airspeed cleared.
+    pub const fn new() -> Self {
+        Self {
+            max_airspeed_kmh: 0,
+        }
+    }
+
+    /// Records a new airspeed and returns the telemetry record to send.
+    ///
+    /// # Arguments
+    ///
+    /// * `airspeed_m_s` - Current airspeed in meters per second
+    ///
+    /// # Returns
+    ///
+    /// 16-byte Spektrum airspeed telemetry record
+    pub fn update(&mut self, airspeed_m_s: f32) -> [u8; TELEMETRY_RECORD_SIZE] {
+        let airspeed_kmh = airspeed_to_kmh(airspeed_m_s);
+        self.max_airspeed_kmh = self.max_airspeed_kmh.max(airspeed_kmh);
+        encode_airspeed_record(airspeed_kmh, self.max_airspeed_kmh)
+    }
+
+    /// Records the airspeed of a sensor reading and returns the telemetry record to send.
+    ///
+    /// The airspeed is calculated with [`Reading::airspeed`]; pass
+    /// `sample.reading` for a timestamped reading.
+    ///
+    /// # Arguments
+    ///
+    /// * `reading` - Differential pressure and temperature, e.g. from `read_data`
+    ///
+    /// # Returns
+    ///
+    /// 16-byte Spektrum airspeed telemetry record
+    pub fn update_from_reading(&mut self, reading: &Reading) -> [u8; TELEMETRY_RECORD_SIZE] {
+        self.update(reading.airspeed())
+    }
+
+    /// Returns the maximum airspeed seen since creation or the last reset, in km/h.
+    pub fn max_airspeed_kmh(&self) -> u16 {
+        self.max_airspeed_kmh
+    }
+
+    /// Clears the recorded maximum airspeed.
+    pub fn reset_max(&mut self) {
+        self.max_airspeed_kmh = 0;
+    }
+}
+
+/// Converts an airspeed in meters per second to whole kilometers per hour.
+///
+/// Negative values are clamped to zero and values beyond the range of the
+/// telemetry field saturate at `u16::MAX`.
+///
+/// # Arguments
+///
+/// * `airspeed_m_s` - Airspeed in meters per second
+///
+/// # Returns
+///
+/// Airspeed in km/h, rounded to the nearest integer
+pub fn airspeed_to_kmh(airspeed_m_s: f32) -> u16 {
+    let kmh = libm::roundf(MetersPerSecond(airspeed_m_s).kmh());
+    if kmh.is_nan() || kmh <= 0.0 {
+        0
+    } else if kmh >= u16::MAX as f32 {
+        u16::MAX
+    } else {
+        kmh as u16
+    }
+}
+
+/// Builds a Spektrum airspeed telemetry record.
+///
+/// Layout (big-endian):
+///
+/// | Byte  | Field                        |
+/// |-------|------------------------------|
+/// | 0     | Identifier (0x11)            |
+/// | 1     | Secondary ID (0x00)          |
+/// | 2-3   | Airspeed, 1 km/h per count   |
+/// | 4-5   | Max airspeed, 1 km/h per count |
+/// | 6-15  | Spare (zero)                 |
+///
+/// # Arguments
+///
+/// * `airspeed_kmh` - Current airspeed in km/h
+/// * `max_airspeed_kmh` - Maximum airspeed in km/h
+///
+/// # Returns
+///
+/// 16-byte Spektrum airspeed telemetry record
+pub fn encode_airspeed_record(
+    airspeed_kmh: u16,
+    max_airspeed_kmh: u16,
+) -> [u8; TELEMETRY_RECORD_SIZE] {
+    let mut record = [0u8; TELEMETRY_RECORD_SIZE];
+    record[0] = SPEKTRUM_AIRSPEED_ADDR;
+    record[2..4].copy_from_slice(&airspeed_kmh.to_be_bytes());
+    record[4..6].copy_from_slice(&max_airspeed_kmh.to_be_bytes());
+    record
+}
+
+/// Wraps a telemetry record in an SRXL2 telemetry packet.
+///
+/// Layout:
+///
+/// | Byte  | Field                               |
+/// |-------|-------------------------------------|
+/// | 0     | Header (0xA6)                       |
+/// | 1     | Packet type (0x80, telemetry)       |
+/// | 2     | Packet length (22)                  |
+/// | 3     | Destination device ID               |
+/// | 4-19  | Telemetry record                    |
+/// | 20-21 | CRC-16/XMODEM over bytes 0-19 (big-endian) |
+///
+/// # Arguments
+///
+/// * `device_id` - SRXL2 device ID of the receiver the packet is addressed to
+/// * `record` - 16-byte telemetry record, e.g. from [`encode_airspeed_record`]
+///
+/// # Returns
+///
+/// 22-byte SRXL2 telemetry packet
+pub fn encode_srxl2_telemetry(
+    device_id: u8,
+    record: &[u8; TELEMETRY_RECORD_SIZE],
+) -> [u8; SRXL2_TELEMETRY_PACKET_SIZE] {
+    let mut packet = [0u8; SRXL2_TELEMETRY_PACKET_SIZE];
+    packet[0] = SRXL2_HEADER;
+    packet[1] = SRXL2_TELEMETRY_PACKET_TYPE;
+    packet[2] = SRXL2_TELEMETRY_PACKET_SIZE as u8;
+    packet[3] = device_id;
+    packet[4..20].copy_from_slice(record);
+    let crc = srxl2_crc(&packet[..20]);
+    packet[20..22].copy_from_slice(&crc.to_be_bytes());
+    packet
+}
+
+/// Computes the SRXL2 packet CRC (CRC-16/XMODEM, polynomial 0x1021, initial value 0).
+///
+/// # Arguments
+///
+/// * `data` - Packet bytes excluding the trailing CRC
+///
+/// # Returns
+///
+/// 16-bit CRC value
+pub fn srxl2_crc(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0;
+    for &byte in data {
+        crc ^= (byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_airspeed_to_kmh() {
+        assert_eq!(airspeed_to_kmh(0.0), 0);
+        assert_eq!(airspeed_to_kmh(-5.0), 0);
+        assert_eq!(airspeed_to_kmh(10.0), 36);
+        assert_eq!(airspeed_to_kmh(f32::NAN), 0);
+        assert_eq!(airspeed_to_kmh(1.0e6), u16::MAX);
+    }
+
+    #[test]
+    fn test_encode_airspeed_record() {
+        let record = encode_airspeed_record(0x0123, 0x0456);
+        assert_eq!(record[0], SPEKTRUM_AIRSPEED_ADDR);
+        assert_eq!(record[1], 0x00);
+        assert_eq!(&record[2..6], &[0x01, 0x23, 0x04, 0x56]);
+        assert!(record[6..].iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn test_max_airspeed_tracking() {
+        let mut telemetry = AirspeedTelemetry::new();
+        telemetry.update(20.0);
+        let record = telemetry.update(10.0);
+        assert_eq!(u16::from_be_bytes([record[2], record[3]]), 36);
+        assert_eq!(u16::from_be_bytes([record[4], record[5]]), 72);
+
+        telemetry.reset_max();
+        assert_eq!(telemetry.max_airspeed_kmh(), 0);
+    }
+
+    #[test]
+    fn test_update_from_reading() {
+        let reading = Reading {
+            differential_pressure_pa: 375.0,
+            temperature_c: 20.0,
+        };
+        let mut telemetry = AirspeedTelemetry::new();
+        let record = telemetry.update_from_reading(&reading);
+        let expected = airspeed_to_kmh(reading.airspeed());
+        assert_eq!(expected, 90);
+        assert_eq!(u16::from_be_bytes([record[2], record[3]]), expected);
+        assert_eq!(telemetry.max_airspeed_kmh(), expected);
+    }
+
+    #[test]
+    fn test_srxl2_crc() {
+        // CRC-16/XMODEM check value
+        assert_eq!(srxl2_crc(b"123456789"), 0x31C3);
+    }
+
+    #[test]
+    fn test_encode_srxl2_telemetry() {
+        let record = encode_airspeed_record(36, 72);
+        let packet = encode_srxl2_telemetry(0x21, &record);
+        assert_eq!(&packet[..4], &[0xA6, 0x80, 22, 0x21]);
+        assert_eq!(&packet[4..20], &record);
+
+        let crc = u16::from_be_bytes([packet[20], packet[21]]);
+        assert_eq!(crc, srxl2_crc(&packet[..20]));
+    }
+}
@@ -0,0 +1,308 @@
+//! `Stream`-based API for continuous readings.
+//!
+//! [`Ms4525do::into_stream`] turns an async driver into a
+//! [`Stream`] of timestamped readings at a fixed rate, so filtering, logging
+//! and telemetry pipelines can be written with stream combinators (e.g. from
+//! `futures::StreamExt`) directly on top of the driver. It works on any async
+//! runtime for which `embassy-time` has a driver; on tokio or async-std,
+//! enable the `std` feature of `embassy-time`.
+//!
+//! A stream only reads the sensor when it is polled. If the consumer falls
+//! behind, [`Backpressure`] decides whether the missed sampling ticks are
+//! skipped or read back-to-back to catch up. [`ReadingStream::release`] ends
+//! the stream and hands the I2C bus back like [`Ms4525do::release`]. The
+//! stream must be pinned to be polled, e.g. with `core::pin::pin!` or
+//! `Box::pin`.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use futures::StreamExt;
+//! use ms4525do::async_api::Ms4525do;
+//!
+//! let mut stream = core::pin::pin!(Ms4525do::new(i2c).into_stream(50));
+//! while let Some(sample) = stream.next().await {
+//!     match sample {
+//!         Ok(sample) => println!("{} µs: {} m/s", sample.request_us, sample.reading.airspeed()),
+//!         Err(e) => println!("Error: {:?}", e),
+//!     }
+//!     if done() {
+//!         break;
+//!     }
+//! }
+//! let i2c = stream.release().await.unwrap();
+//! ```
+
+use crate::async_api::Ms4525do;
+use crate::timing::TimestampedReading;
+use crate::transfer::TransferFunction;
+use crate::Ms4525doError;
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use embassy_time::{Duration, Instant, Timer};
+use embedded_hal_async::i2c::I2c;
+use futures_core::Stream;
+
+#[cfg(feature = "defmt")]
+use defmt::Format;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Item of a [`ReadingStream`].
+pub type StreamItem = Result<TimestampedReading, Ms4525doError>;
+
+/// What a [`ReadingStream`] does with sampling ticks missed by a slow consumer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Backpressure {
+    /// Drop missed ticks and read once, so every item is as fresh as possible
+    Skip,
+    /// Read missed ticks back-to-back to catch up, keeping at most this many
+    /// and dropping older ones
+    Queue(u32),
+}
+
+/// Sensor and schedule owned by a [`ReadingStream`].
+pub struct StreamState<I2C, T> {
+    sensor: Ms4525do<I2C, T>,
+    period: Duration,
+    next: Instant,
+    backpressure: Backpressure,
+}
+
+impl<I2C, T> StreamState<I2C, T> {
+    /// Returns the number of missed ticks to drop before the next read.
+    fn ticks_to_drop(&self, now: Instant) -> u32 {
+        let period_ticks = self.period.as_ticks().max(1);
+        let behind = now.saturating_duration_since(self.next).as_ticks();
+        let missed = (behind / period_ticks).min(u32::MAX as u64) as u32;
+        match self.backpressure {
+            Backpressure::Skip => missed,
+            Backpressure::Queue(max_backlog) => missed.saturating_sub(max_backlog),
+        }
+    }
+}
+
+/// Waits for the next tick and reads the sensor.
+async fn step<I2C: I2c, T: TransferFunction>(
+    mut state: StreamState<I2C, T>,
+) -> (StreamState<I2C, T>, StreamItem, u32) {
+    Timer::at(state.next).await;
+
+    let dropped = state.ticks_to_drop(Instant::now());
+    state.next += state.period * dropped;
+
+    let item = state.sensor.read_data_timestamped().await;
+    state.next += state.period;
+    (state, item, dropped)
+}
+
+pin_project_lite::pin_project! {
+    /// Stream of timestamped readings, created by [`Ms4525do::into_stream`].
+    pub struct ReadingStream<I2C, T, F> {
+        state: Option<StreamState<I2C, T>>,
+        #[pin]
+        read: Option<F>,
+        step: fn(StreamState<I2C, T>) -> F,
+        skipped: u32,
+    }
+}
+
+impl<I2C, T, F> ReadingStream<I2C, T, F>
+where
+    I2C: I2c,
+    T: TransferFunction,
+    F: Future<Output = (StreamState<I2C, T>, StreamItem, u32)>,
+{
+    /// Returns the number of sampling ticks dropped because of backpressure.
+    pub fn skipped(&self) -> u32 {
+        self.skipped
+    }
+
+    /// Returns the sensor driver, or `None` while a read is in progress.
+    pub fn sensor(&self) -> Option<&Ms4525do<I2C, T>> {
+        self.state.as_ref().map(|state| &state.sensor)
+    }
+
+    /// Ends the stream and returns the sensor driver.
+    ///
+    /// A read in progress is completed first, so the sensor is never left
+    /// mid-transaction; its result is discarded. The stream yields no more
+    /// items afterwards.
+    ///
+    /// # Returns
+    ///
+    /// The sensor driver, or `None` if it has already been taken
+    pub async fn into_sensor(mut self: Pin<&mut Self>) -> Option<Ms4525do<I2C, T>> {
+        core::future::poll_fn(|cx| self.as_mut().poll_finish(cx)).await
+    }
+
+    /// Ends the stream and returns the underlying I2C peripheral.
+    ///
+    /// See [`ReadingStream::into_sensor`].
+    pub async fn release(self: Pin<&mut Self>) -> Option<I2C> {
+        self.into_sensor().await.map(Ms4525do::release)
+    }
+
+    fn poll_finish(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Ms4525do<I2C, T>>> {
+        let mut this = self.project();
+        if let Some(read) = this.read.as_mut().as_pin_mut() {
+            let (state, _item, _dropped) = core::task::ready!(read.poll(cx));
+            this.read.set(None);
+            *this.state = Some(state);
+        }
+        Poll::Ready(this.state.take().map(|state| state.sensor))
+    }
+}
+
+impl<I2C, T, F> Stream for ReadingStream<I2C, T, F>
+where
+    F: Future<Output = (StreamState<I2C, T>, StreamItem, u32)>,
+{
+    type Item = StreamItem;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<StreamItem>> {
+        let mut this = self.project();
+        if this.read.is_none() {
+            match this.state.take() {
+                Some(state) => this.read.set(Some((this.step)(state))),
+                None => return Poll::Ready(None),
+            }
+        }
+
+        let read = this.read.as_mut().as_pin_mut();
+        let (state, item, dropped) = match read {
+            Some(read) => core::task::ready!(read.poll(cx)),
+            None => return Poll::Ready(None),
+        };
+        this.read.set(None);
+        *this.state = Some(state);
+        *this.skipped = this.skipped.saturating_add(dropped);
+        Poll::Ready(Some(item))
+    }
+}
+
+impl<I2C, T> Ms4525do<I2C, T>
+where
+    I2C: I2c,
+    T: TransferFunction,
+{
+    /// Turns the driver into a stream of readings at `rate_hz` (at least 1 Hz).
+    ///
+    /// Missed sampling ticks are skipped, see [`Ms4525do::into_stream_with`].
+    pub fn into_stream(
+        self,
+        rate_hz: u32,
+    ) -> ReadingStream<I2C, T, impl Future<Output = (StreamState<I2C, T>, StreamItem, u32)>> {
+        self.into_stream_with(rate_hz, Backpressure::Skip)
+    }
+
+    /// Turns the driver into a stream of readings with the given backpressure handling.
+    ///
+    /// The first reading is taken as soon as the stream is polled. The
+    /// following ones are scheduled at fixed deadlines, so the rate does not
+    /// drift with the read duration.
+    ///
+    /// # Arguments
+    ///
+    /// * `rate_hz` - Sampling rate, e.g. 50 for 50 Hz
+    /// * `backpressure` - What to do with ticks missed by a slow consumer
+    pub fn into_stream_with(
+        self,
+        rate_hz: u32,
+        backpressure: Backpressure,
+    ) -> ReadingStream<I2C, T, impl Future<Output = (StreamState<I2C, T>, StreamItem, u32)>> {
+        ReadingStream {
+            state: Some(StreamState {
+                sensor: self,
+                period: Duration::from_hz(rate_hz.max(1) as u64),
+                next: Instant::now(),
+                backpressure,
+            }),
+            read: None,
+            step: step::<I2C, T>,
+            skipped: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_hal_async::i2c::{ErrorKind, ErrorType, Operation};
+    use futures_executor::block_on;
+    use futures_util::StreamExt;
+
+    /// Sensor returning a fresh packet followed by a stale copy on every measurement
+    struct MockI2c {
+        reads: usize,
+    }
+
+    impl ErrorType for MockI2c {
+        type Error = ErrorKind;
+    }
+
+    impl I2c for MockI2c {
+        async fn transaction(
+            &mut self,
+            _address: u8,
+            operations: &mut [Operation<'_>],
+        ) -> Result<(), ErrorKind> {
+            for operation in operations {
+                if let Operation::Read(buf) = operation {
+                    let status = if self.reads.is_multiple_of(2) {
+                        0x20
+                    } else {
+                        0xa0
+                    };
+                    buf.copy_from_slice(&[status, 0x00, 0x5f, 0xe0]);
+                    self.reads += 1;
+                }
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_stream_and_release() {
+        let mut stream = core::pin::pin!(Ms4525do::new(MockI2c { reads: 0 }).into_stream(1_000));
+        let mut last_us = None;
+        for _ in 0..5 {
+            let sample = block_on(stream.next()).unwrap().unwrap();
+            assert!(sample.fetch_us >= sample.request_us);
+            if let Some(last) = last_us {
+                assert!(sample.request_us > last);
+            }
+            last_us = Some(sample.request_us);
+        }
+        assert!(stream.sensor().is_some());
+
+        let i2c = block_on(stream.as_mut().release()).unwrap();
+        assert_eq!(i2c.reads, 10);
+        assert!(block_on(stream.next()).is_none());
+    }
+
+    #[test]
+    fn test_backpressure() {
+        let mut skip = core::pin::pin!(Ms4525do::new(MockI2c { reads: 0 }).into_stream(100));
+        block_on(skip.next());
+        embassy_time::block_for(Duration::from_millis(200));
+        block_on(skip.next());
+        assert!(skip.skipped() >= 18, "{} skipped", skip.skipped());
+
+        let mut queue = core::pin::pin!(Ms4525do::new(MockI2c { reads: 0 })
+            .into_stream_with(100, Backpressure::Queue(u32::MAX)));
+        block_on(queue.next());
+        embassy_time::block_for(Duration::from_millis(200));
+        // The backlog of 20 ticks is read back-to-back (2 ms each) instead of being dropped
+        let start = Instant::now();
+        for _ in 0..10 {
+            block_on(queue.next());
+        }
+        assert!(start.elapsed() < Duration::from_millis(80));
+        assert_eq!(queue.skipped(), 0);
+    }
+}
@@ -0,0 +1,352 @@
+//! Stuck-sensor and frozen-value detection.
+//!
+//! After an internal lockup, an MS4525DO can keep returning the same valid
+//! frame with a `NormalOperation` status. Both reads of the double-read
+//! validation then match, so `read_data` cannot tell it apart from a healthy
+//! sensor in still air. A working sensor always shows a few counts of noise,
+//! however, so a frame that stays bit-for-bit identical is suspicious.
+//!
+//! [`StuckDetector`] tracks the variance of the raw bridge and temperature
+//! counts of successive good readings. When both stay below the sensor's noise
+//! floor for longer than a configurable time, the sensor is reported stuck.
+//! Enabled on a driver, `read_data` then returns
+//! [`Ms4525doError::SensorStuck`](crate::Ms4525doError::SensorStuck) and can
+//! optionally run a [`ReinitHook`], e.g. power cycling the sensor.
+//!
+//! # Example
+//!
+//! ```
+//! use ms4525do::stuck::{StuckConfig, StuckDetector};
+//!
+//! let mut detector = StuckDetector::new(StuckConfig::default());
+//! let mut stuck = false;
+//! for t in 0..200 {
+//!     stuck = detector.update(8192, 1024, Some(t * 20));
+//! }
+//! assert!(stuck);
+//! ```
+
+#[cfg(feature = "defmt")]
+use defmt::{warn, Format};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Configuration of the [`StuckDetector`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StuckConfig {
+    /// Bridge count variance below which the pressure is considered frozen, in counts²
+    pub bridge_variance_floor: f32,
+    /// Temperature count variance below which the temperature is considered frozen, in counts²
+    pub temperature_variance_floor: f32,
+    /// Minimum time the counts must stay frozen, in milliseconds (ignored without a clock)
+    pub min_frozen_ms: u64,
+    /// Minimum number of frozen readings
+    pub min_frozen_samples: u16,
+    /// Run the driver's re-init hook when the sensor is reported stuck
+    pub auto_reinit: bool,
+}
+
+impl Default for StuckConfig {
+    /// 0.25 counts² on both channels, at least 2 s and 50 readings, no re-init.
+    ///
+    /// The MS4525DO bridge output has a noise of about 1-2 counts RMS, so a
+    /// working sensor stays well above the bridge floor.
+    fn default() -> Self {
+        Self {
+            bridge_variance_floor: 0.25,
+            temperature_variance_floor: 0.25,
+            min_frozen_ms: 2_000,
+            min_frozen_samples: 50,
+            auto_reinit: false,
+        }
+    }
+}
+
+/// Hook run by the drivers to re-initialize a stuck sensor.
+///
+/// Implemented for any `FnMut()` closure, so a hook can capture e.g. the
+/// GPIO that switches the sensor supply.
+pub trait ReinitHook {
+    /// Re-initializes the sensor.
+    fn reinit(&mut self);
+}
+
+impl<F: FnMut()> ReinitHook for F {
+    fn reinit(&mut self) {
+        self()
+    }
+}
+
+/// Running mean and variance of one channel (Welford's algorithm).
+#[derive(Debug, Clone, Copy, Default)]
+struct RunningVariance {
+    mean: f32,
+    m2: f32,
+}
+
+impl RunningVariance {
+    fn restart(&mut self, value: f32) {
+        self.mean = value;
+        self.m2 = 0.0;
+    }
+
+    fn update(&mut self, value: f32, count: u16) {
+        let delta = value - self.mean;
+        self.mean += delta / count as f32;
+        self.m2 += delta * (value - self.mean);
+    }
+
+    fn variance(&self, count: u16) -> f32 {
+        if count < 2 {
+            0.0
+        } else {
+            self.m2 / (count - 1) as f32
+        }
+    }
+}
+
+/// Detects a sensor that keeps returning the same frame.
+#[derive(Debug, Clone)]
+pub struct StuckDetector {
+    config: StuckConfig,
+    bridge: RunningVariance,
+    temperature: RunningVariance,
+    samples: u16,
+    frozen_since_ms: Option<u64>,
+    stuck: bool,
+}
+
+impl StuckDetector {
+    /// Creates a detector with the given configuration.
+    pub fn new(config: StuckConfig) -> Self {
+        Self {
+            config,
+            bridge: RunningVariance::default(),
+            temperature: RunningVariance::default(),
+            samples: 0,
+            frozen_since_ms: None,
+            stuck: false,
+        }
+    }
+
+    /// Returns the detector configuration.
+    pub fn config(&self) -> &StuckConfig {
+        &self.config
+    }
+
+    /// Feeds the raw counts of a reading that passed validation.
+    ///
+    /// The counts are accumulated while their variance stays below the noise
+    /// floor. As soon as either channel rises above it the sensor is live, and
+    /// tracking restarts from the current reading.
+    ///
+    /// # Arguments
+    ///
+    /// * `bridge_data` - 14-bit raw pressure value
+    /// * `temperature_counts` - 11-bit raw temperature value
+    /// * `now_ms` - Current time in milliseconds, or `None` without a clock
+    ///
+    /// # Returns
+    ///
+    /// `true` if the sensor is stuck
+    pub fn update(
+        &mut self,
+        bridge_data: u16,
+        temperature_counts: u16,
+        now_ms: Option<u64>,
+    ) -> bool {
+        let bridge = bridge_data as f32;
+        let temperature = temperature_counts as f32;
+
+        if self.samples == 0 {
+            self.restart(bridge, temperature, now_ms);
+            return false;
+        }
+
+        self.samples = self.samples.saturating_add(1);
+        self.bridge.update(bridge, self.samples);
+        self.temperature.update(temperature, self.samples);
+
+        if self.bridge.variance(self.samples) > self.config.bridge_variance_floor
+            || self.temperature.variance(self.samples) > self.config.temperature_variance_floor
+        {
+            self.restart(bridge, temperature, now_ms);
+            return false;
+        }
+
+        if self.frozen_since_ms.is_none() {
+            self.frozen_since_ms = now_ms;
+        }
+
+        if !self.stuck && self.frozen_long_enough(now_ms) {
+            self.stuck = true;
+
+            #[cfg(feature = "defmt")]
+            warn!(
+                "Sensor stuck: {} identical readings, bridge {}, temperature {}",
+                self.samples, bridge_data, temperature_counts
+            );
+
+            #[cfg(all(not(feature = "defmt"), feature = "log"))]
+            log::warn!(
+                "Sensor stuck: {} identical readings, bridge {}, temperature {}",
+                self.samples,
+                bridge_data,
+                temperature_counts
+            );
+        }
+        self.stuck
+    }
+
+    fn frozen_long_enough(&self, now_ms: Option<u64>) -> bool {
+        let enough_time = match (now_ms, self.frozen_since_ms) {
+            (Some(now), Some(since)) => now.saturating_sub(since) >= self.config.min_frozen_ms,
+            _ => true,
+        };
+        enough_time && self.samples >= self.config.min_frozen_samples
+    }
+
+    fn restart(&mut self, bridge: f32, temperature: f32, now_ms: Option<u64>) {
+        self.bridge.restart(bridge);
+        self.temperature.restart(temperature);
+        self.samples = 1;
+        self.frozen_since_ms = now_ms;
+        self.stuck = false;
+    }
+
+    /// Returns true while the sensor is reported stuck.
+    pub fn is_stuck(&self) -> bool {
+        self.stuck
+    }
+
+    /// Returns the number of readings in the current frozen stretch.
+    pub fn frozen_samples(&self) -> u16 {
+        self.samples
+    }
+
+    /// Clears the detection state, e.g. after the sensor has been re-initialized.
+    pub fn reset(&mut self) {
+        *self = Self::new(self.config);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> StuckConfig {
+        StuckConfig {
+            min_frozen_ms: 100,
+            min_frozen_samples: 5,
+            ..StuckConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_detects_frozen_frame() {
+        let mut detector = StuckDetector::new(config());
+        for t in 0..5 {
+            assert!(!detector.update(8000, 1000, Some(t * 20)));
+        }
+        // Enough samples, but not yet 100 ms
+        assert!(!detector.update(8000, 1000, Some(99)));
+        assert!(detector.update(8000, 1000, Some(100)));
+        assert!(detector.is_stuck());
+
+        // A live reading clears the state
+        assert!(!detector.update(8010, 1000, Some(120)));
+        assert_eq!(detector.frozen_samples(), 1);
+    }
+
+    #[test]
+    fn test_noisy_sensor_is_live() {
+        let mut detector = StuckDetector::new(config());
+        let noise = [0u16, 2, 1, 3, 0, 1, 2, 0, 3, 1];
+        for (t, n) in (0..100).zip(noise.iter().cycle()) {
+            assert!(!detector.update(8000 + n, 1000, Some(t * 20)));
+        }
+    }
+
+    #[test]
+    fn test_sample_count_without_clock() {
+        let mut detector = StuckDetector::new(config());
+        for _ in 0..4 {
+            assert!(!detector.update(8000, 1000, None));
+        }
+        assert!(detector.update(8000, 1000, None));
+        detector.reset();
+        assert!(!detector.is_stuck());
+    }
+
+    /// The same valid frame, `readings` times in a row
+    #[cfg(feature = "blocking")]
+    fn frozen_sensor(readings: usize) -> crate::blocking::Ms4525do<crate::mock::SensorMock> {
+        use crate::blocking::Ms4525do;
+        use crate::mock::{frames, SensorMock};
+        use crate::retry::RetryPolicy;
+
+        let frozen = frames(8192, 767);
+        let mut sensor = Ms4525do::new(SensorMock::new((0..readings).map(|_| Some(frozen))));
+        sensor.set_retry_policy(RetryPolicy::none());
+        sensor
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_driver_reports_stuck_sensor() {
+        use crate::mock::NoDelay;
+        use crate::Ms4525doError;
+
+        let mut sensor = frozen_sensor(4);
+        sensor.set_stuck_detection(Some(StuckConfig {
+            min_frozen_samples: 3,
+            ..StuckConfig::default()
+        }));
+
+        assert!(sensor.read_data(&mut NoDelay).is_ok());
+        assert!(sensor.read_data(&mut NoDelay).is_ok());
+        assert_eq!(
+            sensor.read_data(&mut NoDelay),
+            Err(Ms4525doError::SensorStuck)
+        );
+        assert_eq!(
+            sensor.read_data(&mut NoDelay),
+            Err(Ms4525doError::SensorStuck)
+        );
+        assert_eq!(sensor.health().errors.sensor_stuck, 2);
+        sensor.release().assert_finished();
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_driver_runs_reinit_hook() {
+        use crate::mock::NoDelay;
+        use crate::Ms4525doError;
+        use core::cell::Cell;
+
+        // The hook captures its state, e.g. the sensor supply GPIO
+        let power_cycles = Cell::new(0);
+        let mut sensor =
+            frozen_sensor(4).with_reinit_hook(|| power_cycles.set(power_cycles.get() + 1));
+        sensor.set_stuck_detection(Some(StuckConfig {
+            min_frozen_samples: 3,
+            auto_reinit: true,
+            ..StuckConfig::default()
+        }));
+
+        assert!(sensor.read_data(&mut NoDelay).is_ok());
+        assert!(sensor.read_data(&mut NoDelay).is_ok());
+        assert_eq!(
+            sensor.read_data(&mut NoDelay),
+            Err(Ms4525doError::SensorStuck)
+        );
+        assert_eq!(power_cycles.get(), 1);
+        // The detector starts over after the re-init
+        assert!(sensor.read_data(&mut NoDelay).is_ok());
+        assert_eq!(power_cycles.get(), 1);
+        sensor.release().assert_finished();
+    }
+}
@@ -0,0 +1,308 @@
+//! Sample timestamps and sampling-rate statistics.
+//!
+//! Fusion algorithms need to know when a sample was taken, not just when
+//! `read_data` returned. `read_data_timestamped` on both drivers stamps a
+//! [`TimestampedReading`] with the time the measurement request was sent and
+//! the time the data was fetched, using a [`Clock`]. The async driver uses
+//! [`EmbassyClock`]; the blocking driver takes any user-supplied monotonic
+//! clock, which also times its health statistics and stuck-sensor detection.
+//!
+//! [`JitterStats`] accumulates the intervals between samples to confirm the
+//! loop really runs at the intended rate.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use ms4525do::timing::{Clock, JitterStats};
+//!
+//! struct SysTick;
+//! impl Clock for SysTick {
+//!     fn now_us(&mut self) -> u64 {
+//!         monotonic_micros()
+//!     }
+//! }
+//!
+//! let mut stats = JitterStats::new(20_000); // 50 Hz
+//! loop {
+//!     let sample = sensor.read_data_timestamped(&mut delay, &mut SysTick)?;
+//!     stats.update(sample.request_us);
+//!     // ...
+//! }
+//! // stats.rate_hz(), stats.jitter_rms_us(), stats.max_jitter_us
+//! ```
+
+use crate::common::Reading;
+
+#[cfg(feature = "defmt")]
+use defmt::Format;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A monotonic clock with microsecond resolution.
+pub trait Clock {
+    /// Returns the current time in microseconds.
+    fn now_us(&mut self) -> u64;
+}
+
+/// [`Clock`] backed by `embassy_time::Instant`.
+#[cfg(feature = "async")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EmbassyClock;
+
+#[cfg(feature = "async")]
+impl Clock for EmbassyClock {
+    fn now_us(&mut self) -> u64 {
+        embassy_time::Instant::now().as_micros()
+    }
+}
+
+/// Clock used by the untimestamped reads, which always returns 0.
+#[cfg(any(feature = "async", feature = "blocking"))]
+pub(crate) struct NoClock;
+
+#[cfg(any(feature = "async", feature = "blocking"))]
+impl Clock for NoClock {
+    fn now_us(&mut self) -> u64 {
+        0
+    }
+}
+
+/// A validated reading with the times it was requested and fetched.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TimestampedReading {
+    /// The measurement
+    pub reading: Reading,
+    /// Time the measurement request was sent, in microseconds
+    pub request_us: u64,
+    /// Time both data packets had been read, in microseconds
+    pub fetch_us: u64,
+}
+
+impl TimestampedReading {
+    /// Returns the time between the measurement request and the data fetch, in microseconds.
+    pub fn latency_us(&self) -> u64 {
+        self.fetch_us.saturating_sub(self.request_us)
+    }
+}
+
+/// Sampling interval, rate and jitter statistics.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct JitterStats {
+    /// Intended sampling period, in microseconds
+    pub target_period_us: u64,
+    /// Number of intervals measured
+    pub intervals: u32,
+    /// Shortest interval seen, in microseconds
+    pub min_interval_us: u64,
+    /// Longest interval seen, in microseconds
+    pub max_interval_us: u64,
+    /// Largest deviation of an interval from the target period, in microseconds
+    pub max_jitter_us: u64,
+    last_us: Option<u64>,
+    mean_us: f32,
+    m2: f32,
+}
+
+impl JitterStats {
+    /// Creates empty statistics for the given target period.
+    ///
+    /// # Arguments
+    ///
+    /// * `target_period_us` - Intended sampling period, e.g. 20 000 µs for 50 Hz
+    pub fn new(target_period_us: u64) -> Self {
+        Self {
+            target_period_us,
+            intervals: 0,
+            min_interval_us: u64::MAX,
+            max_interval_us: 0,
+            max_jitter_us: 0,
+            last_us: None,
+            mean_us: 0.0,
+            m2: 0.0,
+        }
+    }
+
+    /// Records the timestamp of a new sample.
+    ///
+    /// # Arguments
+    ///
+    /// * `timestamp_us` - Sample time in microseconds, e.g. [`TimestampedReading::request_us`]
+    pub fn update(&mut self, timestamp_us: u64) {
+        if let Some(last) = self.last_us.replace(timestamp_us) {
+            let interval = timestamp_us.saturating_sub(last);
+            self.intervals = self.intervals.saturating_add(1);
+            self.min_interval_us = self.min_interval_us.min(interval);
+            self.max_interval_us = self.max_interval_us.max(interval);
+            self.max_jitter_us = self
+                .max_jitter_us
+                .max(interval.abs_diff(self.target_period_us));
+
+            // Welford's algorithm for the interval mean and variance
+            let delta = interval as f32 - self.mean_us;
+            self.mean_us += delta / self.intervals as f32;
+            self.m2 += delta * (interval as f32 - self.mean_us);
+        }
+    }
+
+    /// Returns the mean interval between samples, in microseconds.
+    pub fn mean_interval_us(&self) -> f32 {
+        self.mean_us
+    }
+
+    /// Returns the mean sampling rate in Hz, or 0 before two samples.
+    pub fn rate_hz(&self) -> f32 {
+        if self.mean_us > 0.0 {
+            1_000_000.0 / self.mean_us
+        } else {
+            0.0
+        }
+    }
+
+    /// Returns the standard deviation of the interval, in microseconds.
+    pub fn jitter_rms_us(&self) -> f32 {
+        if self.intervals < 2 {
+            0.0
+        } else {
+            libm::sqrtf(self.m2 / (self.intervals - 1) as f32)
+        }
+    }
+
+    /// Clears the statistics, keeping the target period.
+    pub fn reset(&mut self) {
+        *self = Self::new(self.target_period_us);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_jitter_stats() {
+        let mut stats = JitterStats::new(20_000);
+        assert_eq!(stats.rate_hz(), 0.0);
+
+        for t in [0, 20_000, 39_000, 60_000, 80_500, 100_000] {
+            stats.update(t);
+        }
+        assert_eq!(stats.intervals, 5);
+        assert_eq!(stats.min_interval_us, 19_000);
+        assert_eq!(stats.max_interval_us, 21_000);
+        assert_eq!(stats.max_jitter_us, 1_000);
+        assert!((stats.mean_interval_us() - 20_000.0).abs() < 0.1);
+        assert!((stats.rate_hz() - 50.0).abs() < 1e-3);
+        // Intervals 20000, 19000, 21000, 20500, 19500
+        assert!((stats.jitter_rms_us() - 790.57).abs() < 0.1);
+
+        stats.reset();
+        assert_eq!(stats.intervals, 0);
+        assert_eq!(stats.target_period_us, 20_000);
+    }
+
+    #[test]
+    fn test_latency() {
+        let sample = TimestampedReading {
+            reading: Reading::default(),
+            request_us: 1_000,
+            fetch_us: 3_400,
+        };
+        assert_eq!(sample.latency_us(), 2_400);
+    }
+
+    /// Advances by 1 ms on every read
+    #[cfg(feature = "blocking")]
+    struct StepClock(u64);
+
+    #[cfg(feature = "blocking")]
+    impl Clock for StepClock {
+        fn now_us(&mut self) -> u64 {
+            self.0 += 1_000;
+            self.0
+        }
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_driver_timestamps() {
+        use crate::blocking::Ms4525do;
+        use crate::health::HealthStatus;
+        use crate::mock::{frames, NoDelay, SensorMock};
+
+        let mut sensor = Ms4525do::new(SensorMock::new([Some(frames(8192, 767))]));
+        let sample = sensor
+            .read_data_timestamped(&mut NoDelay, &mut StepClock(0))
+            .unwrap();
+        assert_eq!((sample.request_us, sample.fetch_us), (1_000, 2_000));
+        assert_eq!(sample.latency_us(), 1_000);
+
+        // The same clock times the health statistics
+        assert_eq!(sensor.health().last_good_ms, Some(3));
+        assert_eq!(
+            sensor.health_status_with_clock(&mut StepClock(100_000)),
+            HealthStatus::Healthy
+        );
+        assert_eq!(
+            sensor.health_status_with_clock(&mut StepClock(300_000)),
+            HealthStatus::Degraded
+        );
+        sensor.release().assert_finished();
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_driver_timestamps_pipelined() {
+        use crate::blocking::Ms4525do;
+        use crate::mock::{frames, NoDelay, SensorMock};
+
+        let mut sensor = Ms4525do::new_shared(SensorMock::new([Some(frames(8192, 767))]));
+        let mut clock = StepClock(0);
+        let sample = sensor
+            .read_data_timestamped(&mut NoDelay, &mut clock)
+            .unwrap();
+        assert_eq!((sample.request_us, sample.fetch_us), (1_000, 2_000));
+
+        // The second measurement was requested by the first fetch
+        let sample = sensor
+            .read_data_timestamped(&mut NoDelay, &mut clock)
+            .unwrap();
+        assert_eq!((sample.request_us, sample.fetch_us), (2_000, 4_000));
+        sensor.release().assert_finished();
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_driver_times_stuck_detection() {
+        use crate::blocking::Ms4525do;
+        use crate::mock::{frames, NoDelay, SensorMock};
+        use crate::stuck::StuckConfig;
+        use crate::Ms4525doError;
+
+        let frozen = Some(frames(8192, 767));
+        let mut sensor = Ms4525do::new(SensorMock::new([frozen; 3]));
+        sensor.set_stuck_detection(Some(StuckConfig {
+            min_frozen_ms: 5,
+            min_frozen_samples: 2,
+            ..StuckConfig::default()
+        }));
+
+        // Each read takes 3 ms of the clock; the counts are frozen for 5 ms
+        // only at the third read
+        let mut clock = StepClock(0);
+        assert!(sensor
+            .read_data_timestamped(&mut NoDelay, &mut clock)
+            .is_ok());
+        assert!(sensor
+            .read_data_timestamped(&mut NoDelay, &mut clock)
+            .is_ok());
+        assert_eq!(
+            sensor.read_data_timestamped(&mut NoDelay, &mut clock),
+            Err(Ms4525doError::SensorStuck)
+        );
+        sensor.release().assert_finished();
+    }
+}
@@ -0,0 +1,315 @@
+//! Transfer functions for sensors sharing the MS4525DO 4-byte frame.
+//!
+//! Honeywell TruStability HSC/SSC/ABP and All Sensors DLVR digital pressure
+//! sensors use the same I2C frame as the MS4525DO: 2 status bits, a 14-bit
+//! bridge value and an 11-bit temperature value. Only the conversion from raw
+//! counts to physical units differs, which is captured by the
+//! [`TransferFunction`] trait.
+//!
+//! The drivers are generic over a transfer function and default to
+//! [`Ms4525doTransfer`], so existing code keeps working unchanged.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use ms4525do::blocking::Ms4525do;
+//! use ms4525do::transfer::{LinearTransfer, OutputType, INH2O_TO_PA};
+//!
+//! // Honeywell HSCDRRN001ND2A3: ±1 inH2O, transfer function A, address 0x28
+//! let transfer = LinearTransfer::honeywell(OutputType::A, -INH2O_TO_PA, INH2O_TO_PA);
+//! let mut sensor = Ms4525do::new_with_transfer(i2c, 0x28, transfer);
+//!
+//! // All Sensors DLVR-L01D: ±1 inH2O
+//! let transfer = LinearTransfer::dlvr(2.0 * INH2O_TO_PA);
+//! let mut sensor = Ms4525do::new_with_transfer(i2c, 0x28, transfer);
+//! ```
+
+use crate::common::{calculate_pressure_differential_pa, calculate_temperature_deg_c, PSI_TO_PA};
+
+#[cfg(feature = "defmt")]
+use defmt::Format;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Conversion factor from inches of water (at 4°C) to Pascals
+pub const INH2O_TO_PA: f32 = 249.0889;
+
+/// Conversion factor from millibar to Pascals
+pub const MBAR_TO_PA: f32 = 100.0;
+
+/// Full scale of the 14-bit bridge output (2^14 - 1 counts)
+pub const BRIDGE_FULL_SCALE: f32 = 16383.0;
+
+/// Conversion from raw sensor counts to physical units.
+pub trait TransferFunction {
+    /// Converts 14-bit bridge counts to differential pressure in Pascals.
+    fn pressure_pa(&self, bridge_counts: u16) -> f32;
+
+    /// Converts 11-bit temperature counts to degrees Celsius.
+    ///
+    /// The default implementation uses the -50°C to +150°C transfer function
+    /// shared by the MS4525DO, Honeywell and All Sensors parts.
+    fn temperature_c(&self, temperature_counts: u16) -> f32 {
+        calculate_temperature_deg_c(temperature_counts)
+    }
+
+    /// Returns the (minimum, maximum) bridge counts of the valid output band.
+    ///
+    /// Readings outside the band are rejected by `read_data` as saturated.
+    /// The default implementation is the Type A band (10% to 90%).
+    fn output_band(&self) -> (u16, u16) {
+        OutputType::A.count_range()
+    }
+}
+
+/// Transfer function of the MS4525DO 001PD (±1 PSI) variant.
+///
+/// The ±1 PSI range maps onto the output band of the part's output type:
+/// Type A (10% to 90%, the default) or Type B (5% to 95%). Type A uses the
+/// crate's `calculate_pressure_differential_pa`. Both use the datasheet sign
+/// convention shared by all transfer functions: positive when port 1 is at the
+/// higher pressure. This is the default transfer function of the drivers.
+///
+/// # Example
+///
+/// ```ignore
+/// use ms4525do::transfer::{Ms4525doTransfer, OutputType};
+///
+/// // MS4525DO-DS5BI001DP: Type B output
+/// let transfer = Ms4525doTransfer::new(OutputType::B);
+/// let mut sensor = Ms4525do::new_with_transfer(i2c, 0x28, transfer);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Ms4525doTransfer {
+    /// Output type of the part, setting the output band
+    pub output_type: OutputType,
+}
+
+impl Ms4525doTransfer {
+    /// Creates the transfer function for the given output type.
+    pub fn new(output_type: OutputType) -> Self {
+        Self { output_type }
+    }
+}
+
+impl Default for Ms4525doTransfer {
+    /// Type A output, the most common MS4525DO variant.
+    fn default() -> Self {
+        Self::new(OutputType::A)
+    }
+}
+
+impl TransferFunction for Ms4525doTransfer {
+    fn pressure_pa(&self, bridge_counts: u16) -> f32 {
+        match self.output_type {
+            OutputType::A => calculate_pressure_differential_pa(bridge_counts),
+            output_type => {
+                let (min, max) = output_type.fractions();
+                let fraction = bridge_counts as f32 / BRIDGE_FULL_SCALE;
+                ((fraction - min) * 2.0 / (max - min) - 1.0) * PSI_TO_PA
+            }
+        }
+    }
+
+    fn output_band(&self) -> (u16, u16) {
+        self.output_type.count_range()
+    }
+}
+
+/// Digital output band of a sensor, as a fraction of the 14-bit full scale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum OutputType {
+    /// 10% to 90% of full scale (MS4525DO Type A, Honeywell A, DLVR)
+    A,
+    /// 5% to 95% of full scale (MS4525DO Type B, Honeywell B)
+    B,
+    /// 5% to 85% of full scale (Honeywell C)
+    C,
+    /// 4% to 94% of full scale (Honeywell F)
+    F,
+}
+
+impl OutputType {
+    /// Returns the (minimum, maximum) output of this band, as a fraction of full scale.
+    fn fractions(&self) -> (f32, f32) {
+        match self {
+            OutputType::A => (0.10, 0.90),
+            OutputType::B => (0.05, 0.95),
+            OutputType::C => (0.05, 0.85),
+            OutputType::F => (0.04, 0.94),
+        }
+    }
+
+    /// Returns the (minimum, maximum) output counts of this band.
+    pub fn count_range(&self) -> (u16, u16) {
+        let (min, max) = self.fractions();
+        (
+            libm::roundf(min * BRIDGE_FULL_SCALE) as u16,
+            libm::roundf(max * BRIDGE_FULL_SCALE) as u16,
+        )
+    }
+}
+
+/// Linear transfer function mapping an output count band onto a pressure range.
+///
+/// `P = (counts - counts_min) * (pressure_max - pressure_min) / (counts_max - counts_min) + pressure_min`
+///
+/// This is the transfer function of Honeywell TruStability HSC/SSC/ABP and
+/// All Sensors DLVR parts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LinearTransfer {
+    /// Output counts at the minimum pressure
+    pub counts_min: u16,
+    /// Output counts at the maximum pressure
+    pub counts_max: u16,
+    /// Minimum pressure of the range, in Pascals
+    pub pressure_min_pa: f32,
+    /// Maximum pressure of the range, in Pascals
+    pub pressure_max_pa: f32,
+}
+
+impl LinearTransfer {
+    /// Creates a linear transfer function from an explicit count band and pressure range.
+    ///
+    /// # Arguments
+    ///
+    /// * `counts_min` - Output counts at the minimum pressure
+    /// * `counts_max` - Output counts at the maximum pressure
+    /// * `pressure_min_pa` - Minimum pressure in Pascals
+    /// * `pressure_max_pa` - Maximum pressure in Pascals
+    pub const fn new(
+        counts_min: u16,
+        counts_max: u16,
+        pressure_min_pa: f32,
+        pressure_max_pa: f32,
+    ) -> Self {
+        Self {
+            counts_min,
+            counts_max,
+            pressure_min_pa,
+            pressure_max_pa,
+        }
+    }
+
+    /// Creates the transfer function of a Honeywell TruStability HSC/SSC/ABP part.
+    ///
+    /// # Arguments
+    ///
+    /// * `output_type` - Transfer function letter from the part number
+    /// * `pressure_min_pa` - Minimum pressure of the range, in Pascals
+    /// * `pressure_max_pa` - Maximum pressure of the range, in Pascals
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ms4525do::transfer::{LinearTransfer, OutputType, TransferFunction, MBAR_TO_PA};
+    ///
+    /// // HSCDRRN160MD2A3: ±160 mbar, transfer function A
+    /// let transfer = LinearTransfer::honeywell(OutputType::A, -160.0 * MBAR_TO_PA, 160.0 * MBAR_TO_PA);
+    /// assert!(transfer.pressure_pa(8192).abs() < 5.0);
+    /// ```
+    pub fn honeywell(output_type: OutputType, pressure_min_pa: f32, pressure_max_pa: f32) -> Self {
+        let (counts_min, counts_max) = output_type.count_range();
+        Self::new(counts_min, counts_max, pressure_min_pa, pressure_max_pa)
+    }
+
+    /// Creates the transfer function of an All Sensors DLVR differential part.
+    ///
+    /// DLVR parts use `P = 1.25 * (counts - 8192) / 16384 * FSS`, which is a
+    /// symmetric ±FSS/2 range over the 10% to 90% output band.
+    ///
+    /// # Arguments
+    ///
+    /// * `full_scale_span_pa` - Full scale span (FSS) in Pascals, e.g. 2 inH2O for a DLVR-L01D
+    pub fn dlvr(full_scale_span_pa: f32) -> Self {
+        // 8192 ± 16384 / 2.5 counts; exact values are not integers, so fold
+        // the rounding into the pressure range instead of the counts.
+        Self::new(
+            1638,
+            14746,
+            1.25 * (1638.0 - 8192.0) / 16384.0 * full_scale_span_pa,
+            1.25 * (14746.0 - 8192.0) / 16384.0 * full_scale_span_pa,
+        )
+    }
+}
+
+impl TransferFunction for LinearTransfer {
+    fn pressure_pa(&self, bridge_counts: u16) -> f32 {
+        let span_counts = self.counts_max as f32 - self.counts_min as f32;
+        let span_pa = self.pressure_max_pa - self.pressure_min_pa;
+        (bridge_counts as f32 - self.counts_min as f32) * span_pa / span_counts
+            + self.pressure_min_pa
+    }
+
+    fn output_band(&self) -> (u16, u16) {
+        (self.counts_min, self.counts_max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ms4525do_transfer_matches_common() {
+        for counts in [0, 1638, 8192, 14745, 16383] {
+            assert_eq!(
+                Ms4525doTransfer::default().pressure_pa(counts),
+                calculate_pressure_differential_pa(counts)
+            );
+        }
+        assert_eq!(
+            Ms4525doTransfer::default().temperature_c(767),
+            calculate_temperature_deg_c(767)
+        );
+    }
+
+    #[test]
+    fn test_ms4525do_type_b() {
+        let transfer = Ms4525doTransfer::new(OutputType::B);
+        assert_eq!(transfer.output_band(), (819, 15564));
+        assert!((transfer.pressure_pa(819) + PSI_TO_PA).abs() < 1.0);
+        assert!((transfer.pressure_pa(15564) - PSI_TO_PA).abs() < 1.0);
+        assert!(transfer.pressure_pa(8192).abs() < 1.0);
+        // Near full scale, in band for Type B but saturated for Type A
+        assert!(crate::check_range(15000, transfer.output_band()).is_ok());
+        assert!(crate::check_range(15000, Ms4525doTransfer::default().output_band()).is_err());
+    }
+
+    #[test]
+    fn test_output_type_count_range() {
+        assert_eq!(OutputType::A.count_range(), (1638, 14745));
+        assert_eq!(OutputType::B.count_range(), (819, 15564));
+    }
+
+    #[test]
+    fn test_honeywell_transfer() {
+        // ±1 psi, transfer function A
+        let psi = 6894.76;
+        let transfer = LinearTransfer::honeywell(OutputType::A, -psi, psi);
+        assert!((transfer.pressure_pa(1638) + psi).abs() < 0.01);
+        assert!((transfer.pressure_pa(14745) - psi).abs() < 0.01);
+        assert!(transfer.pressure_pa(8192).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_dlvr_transfer() {
+        let fss = 2.0 * INH2O_TO_PA;
+        let transfer = LinearTransfer::dlvr(fss);
+        for counts in [1638u16, 4000, 8192, 12000, 14746] {
+            let expected = 1.25 * (counts as f32 - 8192.0) / 16384.0 * fss;
+            assert!(
+                (transfer.pressure_pa(counts) - expected).abs() < 0.01,
+                "DLVR pressure incorrect at {} counts",
+                counts
+            );
+        }
+    }
+}
@@ -0,0 +1,543 @@
+//! PX4 ULog writer and reader for airspeed topics.
+//!
+//! This module writes sensor readings as ULog files containing
+//! `differential_pressure` and `airspeed` topics compatible with PX4 tooling
+//! such as pyulog and Flight Review, and replays such files back as a stream
+//! of [`UlogRecord`]s.
+//!
+//! The reader resolves fields by name from the format definitions in the file,
+//! so it also accepts `differential_pressure` logs recorded by PX4 itself.
+//!
+//! # Example
+//!
+//! ```
+//! use ms4525do::ulog::{UlogReader, UlogRecord, UlogWriter};
+//!
+//! let mut writer = UlogWriter::new(Vec::new(), 0)?;
+//! writer.write_record(&UlogRecord {
+//!     timestamp_us: 20_000,
+//!     differential_pressure_pa: 52.3,
+//!     temperature_c: 21.5,
+//!     error_count: 0,
+//!     device_id: 0,
+//! })?;
+//! let bytes = writer.into_inner();
+//!
+//! let mut reader = UlogReader::new(bytes.as_slice())?;
+//! let record = reader.next().unwrap()?;
+//! assert_eq!(record.timestamp_us, 20_000);
+//! # Ok::<(), std::io::Error>(())
+//! ```
+
+use crate::common::calculate_airspeed;
+use std::collections::HashMap;
+use std::io::{self, Read, Write};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// ULog file magic, followed by the file format version byte
+pub const ULOG_MAGIC: [u8; 7] = [b'U', b'L', b'o', b'g', 0x01, 0x12, 0x35];
+
+/// ULog file format version written by [`UlogWriter`]
+pub const ULOG_VERSION: u8 = 1;
+
+/// Name of the differential pressure topic
+pub const DIFFERENTIAL_PRESSURE_TOPIC: &str = "differential_pressure";
+
+/// Name of the airspeed topic
+pub const AIRSPEED_TOPIC: &str = "airspeed";
+
+/// PX4 bus type identifier for I2C devices, used in [`px4_device_id`]
+pub const PX4_BUS_TYPE_I2C: u8 = 1;
+
+const DIFFERENTIAL_PRESSURE_FORMAT: &str = "differential_pressure:uint64_t timestamp;\
+    uint64_t timestamp_sample;uint32_t device_id;float differential_pressure_pa;\
+    float temperature;uint32_t error_count;";
+
+const AIRSPEED_FORMAT: &str = "airspeed:uint64_t timestamp;uint64_t timestamp_sample;\
+    float indicated_airspeed_m_s;float true_airspeed_m_s;float air_temperature_celsius;\
+    float confidence;";
+
+const DIFFERENTIAL_PRESSURE_MSG_ID: u16 = 0;
+const AIRSPEED_MSG_ID: u16 = 1;
+
+const MSG_FLAG_BITS: u8 = b'B';
+const MSG_FORMAT: u8 = b'F';
+const MSG_INFO: u8 = b'I';
+const MSG_ADD_LOGGED: u8 = b'A';
+const MSG_DATA: u8 = b'D';
+
+/// One logged sensor sample.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct UlogRecord {
+    /// Time the sample was taken, in microseconds
+    pub timestamp_us: u64,
+    /// Differential pressure in Pascals
+    pub differential_pressure_pa: f32,
+    /// Sensor temperature in degrees Celsius
+    pub temperature_c: f32,
+    /// Number of failed reads so far
+    pub error_count: u32,
+    /// PX4-style device identifier, see [`px4_device_id`]
+    pub device_id: u32,
+}
+
+/// Builds a PX4 device identifier from its bus location and device type.
+///
+/// # Arguments
+///
+/// * `bus_type` - PX4 bus type (e.g. [`PX4_BUS_TYPE_I2C`])
+/// * `bus` - Bus index (0-31)
+/// * `address` - 7-bit I2C address of the sensor
+/// * `devtype` - PX4 device type code
+///
+/// # Returns
+///
+/// Device identifier as stored in the `device_id` field of PX4 sensor topics
+pub fn px4_device_id(bus_type: u8, bus: u8, address: u8, devtype: u8) -> u32 {
+    ((bus_type & 0x07) as u32)
+        | (((bus & 0x1F) as u32) << 3)
+        | ((address as u32) << 8)
+        | ((devtype as u32) << 16)
+}
+
+/// Writes sensor readings to a ULog stream.
+///
+/// The file header and definitions section are written on construction; each
+/// call to [`UlogWriter::write_record`] then appends one `differential_pressure`
+/// and one `airspeed` data message.
+pub struct UlogWriter<W> {
+    writer: W,
+}
+
+impl<W> UlogWriter<W>
+where
+    W: Write,
+{
+    /// Creates a new ULog writer and writes the file header and topic definitions.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - Destination for the ULog data
+    /// * `start_timestamp_us` - Logging start time in microseconds
+    ///
+    /// # Errors
+    ///
+    /// Returns any error reported by the underlying writer.
+    pub fn new(writer: W, start_timestamp_us: u64) -> io::Result<Self> {
+        Self::with_info(writer, start_timestamp_us, &[])
+    }
+
+    /// Creates a new ULog writer with additional string info messages.
+    ///
+    /// Info messages are written into the definitions section as
+    /// `char[N] <key>` entries, e.g. `("sys_name", "ms4525do")`.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - Destination for the ULog data
+    /// * `start_timestamp_us` - Logging start time in microseconds
+    /// * `info` - Key/value pairs to record in the file
+    ///
+    /// # Errors
+    ///
+    /// Returns an `InvalidInput` error if a key, including its `char[N]` type
+    /// prefix, is longer than 255 bytes, and any error reported by the
+    /// underlying writer.
+    pub fn with_info(
+        writer: W,
+        start_timestamp_us: u64,
+        info: &[(&str, &str)],
+    ) -> io::Result<Self> {
+        let mut ulog = Self { writer };
+
+        ulog.writer.write_all(&ULOG_MAGIC)?;
+        ulog.writer.write_all(&[ULOG_VERSION])?;
+        ulog.writer.write_all(&start_timestamp_us.to_le_bytes())?;
+
+        // Flag bits: no compat/incompat flags, no appended data
+        ulog.write_message(MSG_FLAG_BITS, &[0u8; 40])?;
+
+        for (key, value) in info {
+            let key = format!("char[{}] {}", value.len(), key);
+            let key_len = u8::try_from(key.len())
+                .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "info key too long"))?;
+            let mut payload = Vec::with_capacity(1 + key.len() + value.len());
+            payload.push(key_len);
+            payload.extend_from_slice(key.as_bytes());
+            payload.extend_from_slice(value.as_bytes());
+            ulog.write_message(MSG_INFO, &payload)?;
+        }
+
+        ulog.write_message(MSG_FORMAT, DIFFERENTIAL_PRESSURE_FORMAT.as_bytes())?;
+        ulog.write_message(MSG_FORMAT, AIRSPEED_FORMAT.as_bytes())?;
+
+        ulog.subscribe(DIFFERENTIAL_PRESSURE_MSG_ID, DIFFERENTIAL_PRESSURE_TOPIC)?;
+        ulog.subscribe(AIRSPEED_MSG_ID, AIRSPEED_TOPIC)?;
+
+        Ok(ulog)
+    }
+
+    /// Appends one reading as `differential_pressure` and `airspeed` data messages.
+    ///
+    /// Airspeed is derived with [`calculate_airspeed`]; indicated and true
+    /// airspeed are logged with the same value.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error reported by the underlying writer.
+    pub fn write_record(&mut self, record: &UlogRecord) -> io::Result<()> {
+        let mut payload = [0u8; 34];
+        payload[0..2].copy_from_slice(&DIFFERENTIAL_PRESSURE_MSG_ID.to_le_bytes());
+        payload[2..10].copy_from_slice(&record.timestamp_us.to_le_bytes());
+        payload[10..18].copy_from_slice(&record.timestamp_us.to_le_bytes());
+        payload[18..22].copy_from_slice(&record.device_id.to_le_bytes());
+        payload[22..26].copy_from_slice(&record.differential_pressure_pa.to_le_bytes());
+        payload[26..30].copy_from_slice(&record.temperature_c.to_le_bytes());
+        payload[30..34].copy_from_slice(&record.error_count.to_le_bytes());
+        self.write_message(MSG_DATA, &payload)?;
+
+        let airspeed = calculate_airspeed(record.differential_pressure_pa, record.temperature_c);
+        let mut payload = [0u8; 34];
+        payload[0..2].copy_from_slice(&AIRSPEED_MSG_ID.to_le_bytes());
+        payload[2..10].copy_from_slice(&record.timestamp_us.to_le_bytes());
+        payload[10..18].copy_from_slice(&record.timestamp_us.to_le_bytes());
+        payload[18..22].copy_from_slice(&airspeed.to_le_bytes());
+        payload[22..26].copy_from_slice(&airspeed.to_le_bytes());
+        payload[26..30].copy_from_slice(&record.temperature_c.to_le_bytes());
+        payload[30..34].copy_from_slice(&1.0f32.to_le_bytes());
+        self.write_message(MSG_DATA, &payload)
+    }
+
+    /// Flushes the underlying writer.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error reported by the underlying writer.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    /// Consumes the ULog writer and returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    fn subscribe(&mut self, msg_id: u16, topic: &str) -> io::Result<()> {
+        let mut payload = Vec::with_capacity(3 + topic.len());
+        payload.push(0); // multi_id
+        payload.extend_from_slice(&msg_id.to_le_bytes());
+        payload.extend_from_slice(topic.as_bytes());
+        self.write_message(MSG_ADD_LOGGED, &payload)
+    }
+
+    fn write_message(&mut self, msg_type: u8, payload: &[u8]) -> io::Result<()> {
+        let size = u16::try_from(payload.len()).map_err(|_| invalid_data("message too long"))?;
+        self.writer.write_all(&size.to_le_bytes())?;
+        self.writer.write_all(&[msg_type])?;
+        self.writer.write_all(payload)
+    }
+}
+
+/// Byte offsets of the fields needed to rebuild a [`UlogRecord`].
+#[derive(Debug, Clone, Copy)]
+struct RecordLayout {
+    timestamp: usize,
+    device_id: Option<usize>,
+    differential_pressure_pa: usize,
+    temperature: usize,
+    error_count: Option<usize>,
+}
+
+/// Replays the `differential_pressure` topic of a ULog stream as [`UlogRecord`]s.
+///
+/// The reader is an iterator; it yields one record per `differential_pressure`
+/// data message and ends at the end of the stream. All other messages are
+/// skipped.
+pub struct UlogReader<R> {
+    reader: R,
+    formats: HashMap<String, String>,
+    layouts: HashMap<u16, RecordLayout>,
+    done: bool,
+}
+
+impl<R> UlogReader<R>
+where
+    R: Read,
+{
+    /// Creates a new ULog reader and validates the file header.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidData` if the stream does not start with a ULog header,
+    /// or any error reported by the underlying reader.
+    pub fn new(mut reader: R) -> io::Result<Self> {
+        let mut header = [0u8; 16];
+        reader.read_exact(&mut header)?;
+        if header[..7] != ULOG_MAGIC {
+            return Err(invalid_data("not a ULog file"));
+        }
+
+        Ok(Self {
+            reader,
+            formats: HashMap::new(),
+            layouts: HashMap::new(),
+            done: false,
+        })
+    }
+
+    /// Reads the next `differential_pressure` record, or `None` at end of stream.
+    fn read_record(&mut self) -> io::Result<Option<UlogRecord>> {
+        loop {
+            let mut header = [0u8; 3];
+            match self.reader.read_exact(&mut header) {
+                Ok(()) => {}
+                Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+                Err(e) => return Err(e),
+            }
+
+            let size = u16::from_le_bytes([header[0], header[1]]) as usize;
+            let mut payload = vec![0u8; size];
+            self.reader.read_exact(&mut payload)?;
+
+            match header[2] {
+                MSG_FORMAT => {
+                    let format = String::from_utf8_lossy(&payload);
+                    if let Some((name, fields)) = format.split_once(':') {
+                        self.formats.insert(name.into(), fields.into());
+                    }
+                }
+                MSG_ADD_LOGGED if payload.len() > 3 => {
+                    let msg_id = u16::from_le_bytes([payload[1], payload[2]]);
+                    let topic = String::from_utf8_lossy(&payload[3..]);
+                    if topic == DIFFERENTIAL_PRESSURE_TOPIC {
+                        let layout = self.record_layout()?;
+                        self.layouts.insert(msg_id, layout);
+                    }
+                }
+                MSG_DATA if payload.len() >= 2 => {
+                    let msg_id = u16::from_le_bytes([payload[0], payload[1]]);
+                    if let Some(layout) = self.layouts.get(&msg_id) {
+                        return decode_record(layout, &payload[2..]).map(Some);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn record_layout(&self) -> io::Result<RecordLayout> {
+        let offset = |name: &str| self.field_offset(DIFFERENTIAL_PRESSURE_TOPIC, name);
+        Ok(RecordLayout {
+            timestamp: offset("timestamp")?.ok_or_else(|| invalid_data("missing timestamp"))?,
+            device_id: offset("device_id")?,
+            differential_pressure_pa: offset("differential_pressure_pa")?
+                .ok_or_else(|| invalid_data("missing differential_pressure_pa"))?,
+            temperature: offset("temperature")?
+                .ok_or_else(|| invalid_data("missing temperature"))?,
+            error_count: offset("error_count")?,
+        })
+    }
+
+    /// Returns the byte offset of a top-level field of a format, if present.
+    fn field_offset(&self, format: &str, field: &str) -> io::Result<Option<usize>> {
+        let fields = self
+            .formats
+            .get(format)
+            .ok_or_else(|| invalid_data("topic subscribed before its format"))?;
+
+        let mut offset = 0;
+        for definition in fields.split(';').filter(|f| !f.is_empty()) {
+            let (field_type, name) = definition
+                .split_once(' ')
+                .ok_or_else(|| invalid_data("malformed field definition"))?;
+            if name == field {
+                return Ok(Some(offset));
+            }
+            offset += self.type_size(field_type)?;
+        }
+        Ok(None)
+    }
+
+    /// Returns the size in bytes of a ULog field type, including arrays and nested formats.
+    fn type_size(&self, field_type: &str) -> io::Result<usize> {
+        if let Some((base, count)) = field_type.split_once('[') {
+            let count: usize = count
+                .trim_end_matches(']')
+                .parse()
+                .map_err(|_| invalid_data("malformed array length"))?;
+            return Ok(self.type_size(base)? * count);
+        }
+
+        Ok(match field_type {
+            "int8_t" | "uint8_t" | "char" | "bool" => 1,
+            "int16_t" | "uint16_t" => 2,
+            "int32_t" | "uint32_t" | "float" => 4,
+            "int64_t" | "uint64_t" | "double" => 8,
+            nested => {
+                let fields = self
+                    .formats
+                    .get(nested)
+                    .ok_or_else(|| invalid_data("unknown field type"))?;
+                let mut size = 0;
+                for definition in fields.split(';').filter(|f| !f.is_empty()) {
+                    let (field_type, _) = definition
+                        .split_once(' ')
+                        .ok_or_else(|| invalid_data("malformed field definition"))?;
+                    size += self.type_size(field_type)?;
+                }
+                size
+            }
+        })
+    }
+
+    /// Consumes the ULog reader and returns the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R> Iterator for UlogReader<R>
+where
+    R: Read,
+{
+    type Item = io::Result<UlogRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        match self.read_record() {
+            Ok(Some(record)) => Some(Ok(record)),
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+fn decode_record(layout: &RecordLayout, data: &[u8]) -> io::Result<UlogRecord> {
+    let bytes = |offset: usize, len: usize| {
+        data.get(offset..offset + len)
+            .ok_or_else(|| invalid_data("truncated data message"))
+    };
+    let u32_at = |offset: usize| -> io::Result<u32> {
+        Ok(u32::from_le_bytes(bytes(offset, 4)?.try_into().unwrap()))
+    };
+    let f32_at = |offset: usize| -> io::Result<f32> { Ok(f32::from_bits(u32_at(offset)?)) };
+
+    Ok(UlogRecord {
+        timestamp_us: u64::from_le_bytes(bytes(layout.timestamp, 8)?.try_into().unwrap()),
+        differential_pressure_pa: f32_at(layout.differential_pressure_pa)?,
+        temperature_c: f32_at(layout.temperature)?,
+        error_count: layout.error_count.map(u32_at).transpose()?.unwrap_or(0),
+        device_id: layout.device_id.map(u32_at).transpose()?.unwrap_or(0),
+    })
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(timestamp_us: u64) -> UlogRecord {
+        UlogRecord {
+            timestamp_us,
+            differential_pressure_pa: 42.5,
+            temperature_c: 23.25,
+            error_count: 3,
+            device_id: px4_device_id(PX4_BUS_TYPE_I2C, 1, 0x28, 0),
+        }
+    }
+
+    #[test]
+    fn test_header() {
+        let writer = UlogWriter::new(Vec::new(), 0x0102).unwrap();
+        let bytes = writer.into_inner();
+        assert_eq!(&bytes[..7], &ULOG_MAGIC);
+        assert_eq!(bytes[7], ULOG_VERSION);
+        assert_eq!(u64::from_le_bytes(bytes[8..16].try_into().unwrap()), 0x0102);
+        // Flag bits message must directly follow the header
+        assert_eq!(&bytes[16..19], &[40, 0, b'B']);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut writer = UlogWriter::with_info(Vec::new(), 0, &[("sys_name", "ms4525do")]).unwrap();
+        for i in 1..=3 {
+            writer.write_record(&record(i * 20_000)).unwrap();
+        }
+        let bytes = writer.into_inner();
+
+        let records: Vec<_> = UlogReader::new(bytes.as_slice())
+            .unwrap()
+            .collect::<io::Result<_>>()
+            .unwrap();
+        assert_eq!(records, [record(20_000), record(40_000), record(60_000)]);
+    }
+
+    #[test]
+    fn test_rejects_long_info_key() {
+        // "char[8] " plus the key must fit in the one-byte key length
+        let key = "k".repeat(247);
+        assert!(UlogWriter::with_info(Vec::new(), 0, &[(&key, "ms4525do")]).is_ok());
+
+        let key = "k".repeat(248);
+        let err = UlogWriter::with_info(Vec::new(), 0, &[(&key, "ms4525do")])
+            .err()
+            .unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn test_reader_resolves_padded_formats() {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&ULOG_MAGIC);
+        bytes.push(ULOG_VERSION);
+        bytes.extend_from_slice(&0u64.to_le_bytes());
+
+        let mut message = |msg_type: u8, payload: &[u8]| {
+            bytes.extend_from_slice(&(payload.len() as u16).to_le_bytes());
+            bytes.push(msg_type);
+            bytes.extend_from_slice(payload);
+        };
+
+        message(
+            MSG_FORMAT,
+            b"differential_pressure:uint64_t timestamp;uint8_t[4] _padding0;\
+              float temperature;float differential_pressure_pa;",
+        );
+        message(MSG_ADD_LOGGED, b"\x00\x07\x00differential_pressure");
+
+        let mut data = vec![0x07, 0x00];
+        data.extend_from_slice(&500u64.to_le_bytes());
+        data.extend_from_slice(&[0; 4]);
+        data.extend_from_slice(&15.0f32.to_le_bytes());
+        data.extend_from_slice(&(-3.0f32).to_le_bytes());
+        message(MSG_DATA, &data);
+
+        let mut reader = UlogReader::new(bytes.as_slice()).unwrap();
+        let replayed = reader.next().unwrap().unwrap();
+        assert_eq!(replayed.timestamp_us, 500);
+        assert_eq!(replayed.temperature_c, 15.0);
+        assert_eq!(replayed.differential_pressure_pa, -3.0);
+        assert_eq!(replayed.error_count, 0);
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn test_rejects_bad_magic() {
+        let bytes = [0u8; 16];
+        assert!(UlogReader::new(bytes.as_slice()).is_err());
+    }
+}
@@ -0,0 +1,387 @@
+//! 1-σ measurement uncertainty for readings and airspeed.
+//!
+//! Combines three independent pressure uncertainty sources in quadrature:
+//!
+//! 1. Quantization of the 14-bit bridge output (uniform over one LSB)
+//! 2. The datasheet total error band (TEB), treated as a uniform bound that
+//!    depends on temperature and full-scale span
+//! 3. Noise, estimated from recent samples with [`NoiseEstimator`]
+//!
+//! The result is carried through zero-offset correction and into airspeed,
+//! where the uncertainty grows steeply as the differential pressure
+//! approaches zero.
+//!
+//! # Example
+//!
+//! ```
+//! use ms4525do::uncertainty::{NoiseEstimator, UncertaintyModel};
+//! use ms4525do::Reading;
+//!
+//! let model = UncertaintyModel::ms4525do_001pd();
+//! let mut noise = NoiseEstimator::<16>::new();
+//!
+//! let reading = Reading { differential_pressure_pa: 250.0, temperature_c: 22.0 };
+//! noise.push(reading.differential_pressure_pa);
+//!
+//! let uncertain = model.apply(&reading, noise.sigma());
+//! let airspeed = uncertain.airspeed();
+//! println!("{:.2} ± {:.2} m/s", airspeed.value, airspeed.sigma);
+//! ```
+
+use crate::atmosphere::CELSIUS_TO_KELVIN;
+use crate::common::{calculate_pressure_differential_pa, PSI_TO_PA};
+use crate::{calculate_airspeed, Reading};
+
+#[cfg(feature = "defmt")]
+use defmt::Format;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Standard deviation of a uniform distribution of unit width (1 / √12)
+const UNIFORM_WIDTH_SIGMA: f32 = 0.288_675_13;
+
+/// Standard deviation of a uniform distribution of unit half-width (1 / √3)
+const UNIFORM_BOUND_SIGMA: f32 = 0.577_350_26;
+
+/// A value with its 1-σ standard uncertainty.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Uncertain {
+    /// Best estimate
+    pub value: f32,
+    /// 1-σ standard uncertainty
+    pub sigma: f32,
+}
+
+impl Uncertain {
+    /// Creates a value with the given uncertainty.
+    pub fn new(value: f32, sigma: f32) -> Self {
+        Self { value, sigma }
+    }
+}
+
+/// A reading whose pressure and temperature carry 1-σ uncertainties.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct UncertainReading {
+    /// Differential pressure in Pascals
+    pub differential_pressure_pa: Uncertain,
+    /// Temperature in degrees Celsius
+    pub temperature_c: Uncertain,
+}
+
+impl UncertainReading {
+    /// Subtracts a zero offset, adding its uncertainty in quadrature.
+    ///
+    /// # Arguments
+    ///
+    /// * `offset_pa` - Zero offset in Pascals, e.g. the mean of readings taken
+    ///   with no airflow, with the uncertainty of that mean
+    pub fn with_zero_offset(&self, offset_pa: Uncertain) -> Self {
+        let pressure = self.differential_pressure_pa;
+        Self {
+            differential_pressure_pa: Uncertain::new(
+                pressure.value - offset_pa.value,
+                libm::hypotf(pressure.sigma, offset_pa.sigma),
+            ),
+            temperature_c: self.temperature_c,
+        }
+    }
+
+    /// Calculates the airspeed and its uncertainty.
+    ///
+    /// Linear propagation through `v = sqrt(2 * ΔP / ρ)` gives
+    /// `σ_v = σ_ΔP / (ρ * v)`, which diverges at zero differential pressure.
+    /// The pressure term is therefore capped at the airspeed corresponding to
+    /// a 1-σ pressure, `sqrt(2 * σ_ΔP / ρ)`. The temperature term follows
+    /// from `ρ ∝ 1 / T`.
+    ///
+    /// # Returns
+    ///
+    /// Airspeed in m/s, see [`calculate_airspeed`]
+    pub fn airspeed(&self) -> Uncertain {
+        let pressure = self.differential_pressure_pa;
+        let temperature = self.temperature_c;
+        let airspeed = calculate_airspeed(pressure.value, temperature.value);
+
+        // v² / |ΔP| = 2 / ρ, independent of ΔP
+        let unit_airspeed = calculate_airspeed(1.0, temperature.value);
+        let two_over_density = unit_airspeed * unit_airspeed;
+        let sigma_at_zero = libm::sqrtf(two_over_density * pressure.sigma);
+        let sigma_pressure = if airspeed > 0.0 {
+            (pressure.sigma * two_over_density / (2.0 * airspeed)).min(sigma_at_zero)
+        } else {
+            sigma_at_zero
+        };
+
+        let sigma_temperature =
+            airspeed * temperature.sigma / (2.0 * (temperature.value + CELSIUS_TO_KELVIN));
+
+        Uncertain::new(airspeed, libm::hypotf(sigma_pressure, sigma_temperature))
+    }
+}
+
+/// Datasheet total error band.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ErrorBand {
+    /// Total error band inside the compensated temperature range, in % of full-scale span
+    pub compensated_percent_fss: f32,
+    /// Total error band outside the compensated temperature range, in % of full-scale span
+    pub uncompensated_percent_fss: f32,
+    /// Lower limit of the compensated temperature range, in °C
+    pub compensated_min_c: f32,
+    /// Upper limit of the compensated temperature range, in °C
+    pub compensated_max_c: f32,
+}
+
+impl ErrorBand {
+    /// Returns the total error band at a temperature, in % of full-scale span.
+    pub fn percent_fss(&self, temperature_c: f32) -> f32 {
+        if (self.compensated_min_c..=self.compensated_max_c).contains(&temperature_c) {
+            self.compensated_percent_fss
+        } else {
+            self.uncompensated_percent_fss
+        }
+    }
+}
+
+/// Sensor-specific uncertainty model.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct UncertaintyModel {
+    /// Full-scale pressure span, in Pascals
+    pub full_scale_span_pa: f32,
+    /// Pressure change per output count, in Pascals
+    pub pressure_lsb_pa: f32,
+    /// Datasheet total error band of the pressure output
+    pub error_band: ErrorBand,
+    /// Temperature change per output count, in °C
+    pub temperature_lsb_c: f32,
+    /// Accuracy bound of the temperature output, in °C
+    pub temperature_accuracy_c: f32,
+}
+
+impl UncertaintyModel {
+    /// Returns the model of the MS4525DO 001PD (±1 PSI) variant.
+    ///
+    /// Uses a TEB of ±1% FSS over the -10°C to +85°C compensated range and
+    /// assumes ±2% FSS outside it, and a ±1.5°C temperature output accuracy.
+    pub fn ms4525do_001pd() -> Self {
+        Self {
+            full_scale_span_pa: 2.0 * PSI_TO_PA,
+            pressure_lsb_pa: libm::fabsf(
+                calculate_pressure_differential_pa(0) - calculate_pressure_differential_pa(1),
+            ),
+            error_band: ErrorBand {
+                compensated_percent_fss: 1.0,
+                uncompensated_percent_fss: 2.0,
+                compensated_min_c: -10.0,
+                compensated_max_c: 85.0,
+            },
+            temperature_lsb_c: 200.0 / 2047.0,
+            temperature_accuracy_c: 1.5,
+        }
+    }
+
+    /// Returns the 1-σ pressure uncertainty.
+    ///
+    /// # Arguments
+    ///
+    /// * `temperature_c` - Sensor temperature, which selects the error band
+    /// * `noise_sigma_pa` - Standard deviation of recent samples, see [`NoiseEstimator`]
+    ///
+    /// # Returns
+    ///
+    /// Combined standard uncertainty in Pascals
+    pub fn pressure_sigma(&self, temperature_c: f32, noise_sigma_pa: f32) -> f32 {
+        let quantization = self.pressure_lsb_pa * UNIFORM_WIDTH_SIGMA;
+        let error_band = self.error_band.percent_fss(temperature_c) / 100.0
+            * self.full_scale_span_pa
+            * UNIFORM_BOUND_SIGMA;
+        libm::sqrtf(
+            quantization * quantization + error_band * error_band + noise_sigma_pa * noise_sigma_pa,
+        )
+    }
+
+    /// Returns the 1-σ temperature uncertainty, in °C.
+    pub fn temperature_sigma(&self) -> f32 {
+        libm::hypotf(
+            self.temperature_lsb_c * UNIFORM_WIDTH_SIGMA,
+            self.temperature_accuracy_c * UNIFORM_BOUND_SIGMA,
+        )
+    }
+
+    /// Attaches uncertainties to a reading.
+    ///
+    /// # Arguments
+    ///
+    /// * `reading` - Reading from the sensor
+    /// * `noise_sigma_pa` - Standard deviation of recent samples, see [`NoiseEstimator`]
+    pub fn apply(&self, reading: &Reading, noise_sigma_pa: f32) -> UncertainReading {
+        UncertainReading {
+            differential_pressure_pa: Uncertain::new(
+                reading.differential_pressure_pa,
+                self.pressure_sigma(reading.temperature_c, noise_sigma_pa),
+            ),
+            temperature_c: Uncertain::new(reading.temperature_c, self.temperature_sigma()),
+        }
+    }
+}
+
+/// Estimates measurement noise from the last `N` samples.
+///
+/// # Type Parameters
+///
+/// * `N` - Number of samples in the window
+#[derive(Debug, Clone)]
+pub struct NoiseEstimator<const N: usize> {
+    samples: [f32; N],
+    next: usize,
+    len: usize,
+}
+
+impl<const N: usize> Default for NoiseEstimator<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> NoiseEstimator<N> {
+    /// Creates an empty estimator.
+    pub fn new() -> Self {
+        Self {
+            samples: [0.0; N],
+            next: 0,
+            len: 0,
+        }
+    }
+
+    /// Adds a sample, replacing the oldest one once the window is full.
+    pub fn push(&mut self, sample: f32) {
+        if N == 0 {
+            return;
+        }
+        self.samples[self.next] = sample;
+        self.next = (self.next + 1) % N;
+        self.len = (self.len + 1).min(N);
+    }
+
+    /// Returns the number of samples in the window.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if no samples have been added.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the mean of the samples in the window, or 0 if empty.
+    pub fn mean(&self) -> f32 {
+        if self.len == 0 {
+            return 0.0;
+        }
+        self.samples[..self.len].iter().sum::<f32>() / self.len as f32
+    }
+
+    /// Returns the sample standard deviation, or 0 with fewer than two samples.
+    pub fn sigma(&self) -> f32 {
+        if self.len < 2 {
+            return 0.0;
+        }
+        let mean = self.mean();
+        let sum_squares: f32 = self.samples[..self.len]
+            .iter()
+            .map(|sample| (sample - mean) * (sample - mean))
+            .sum();
+        libm::sqrtf(sum_squares / (self.len - 1) as f32)
+    }
+
+    /// Returns the mean and its standard uncertainty, e.g. for a zero offset.
+    pub fn mean_uncertain(&self) -> Uncertain {
+        let sigma = if self.len == 0 {
+            0.0
+        } else {
+            self.sigma() / libm::sqrtf(self.len as f32)
+        };
+        Uncertain::new(self.mean(), sigma)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pressure_sigma() {
+        let model = UncertaintyModel::ms4525do_001pd();
+        assert!((model.pressure_lsb_pa - 1.0521).abs() < 1e-3);
+
+        // 1% of 13789.5 Pa / √3
+        let sigma = model.pressure_sigma(25.0, 0.0);
+        assert!((sigma - 79.6).abs() < 0.1, "{}", sigma);
+        assert!(model.pressure_sigma(100.0, 0.0) > 1.9 * sigma);
+        assert!(model.pressure_sigma(25.0, 100.0) > sigma);
+    }
+
+    #[test]
+    fn test_noise_estimator() {
+        let mut noise = NoiseEstimator::<4>::new();
+        assert_eq!(noise.sigma(), 0.0);
+        for sample in [10.0, 12.0, 10.0, 12.0, 100.0] {
+            noise.push(sample);
+        }
+        // Window now holds 12, 10, 12, 100
+        assert_eq!(noise.len(), 4);
+        assert_eq!(noise.mean(), 33.5);
+
+        let mut noise = NoiseEstimator::<4>::new();
+        for sample in [1.0, 3.0, 1.0, 3.0] {
+            noise.push(sample);
+        }
+        assert!((noise.sigma() - 1.154_700_5).abs() < 1e-6);
+        assert!((noise.mean_uncertain().sigma - 0.577_350_3).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_zero_offset() {
+        let reading = UncertainReading {
+            differential_pressure_pa: Uncertain::new(110.0, 3.0),
+            temperature_c: Uncertain::new(20.0, 0.5),
+        };
+        let corrected = reading.with_zero_offset(Uncertain::new(10.0, 4.0));
+        assert_eq!(
+            corrected.differential_pressure_pa,
+            Uncertain::new(100.0, 5.0)
+        );
+    }
+
+    #[test]
+    fn test_airspeed_uncertainty() {
+        let at = |pressure: f32| {
+            UncertainReading {
+                differential_pressure_pa: Uncertain::new(pressure, 2.0),
+                temperature_c: Uncertain::new(20.0, 0.0),
+            }
+            .airspeed()
+        };
+
+        // Far from zero, the linear approximation applies
+        let fast = at(500.0);
+        let delta = calculate_airspeed(502.0, 20.0) - calculate_airspeed(500.0, 20.0);
+        assert!((fast.sigma - delta).abs() < 0.01 * delta);
+
+        // Uncertainty grows towards zero but stays finite
+        let slow = at(5.0);
+        let zero = at(0.0);
+        assert!(slow.sigma > 5.0 * fast.sigma);
+        assert!(zero.sigma >= slow.sigma && zero.sigma.is_finite());
+        assert!((zero.sigma - calculate_airspeed(2.0, 20.0)).abs() < 1e-3);
+    }
+}
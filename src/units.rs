@@ -0,0 +1,175 @@
+//! Lightweight typed physical units.
+//!
+//! Readings and airspeeds are plain `f32`s throughout the crate, which makes
+//! it easy to mix up Pa with hPa or m/s with knots. These newtypes carry the
+//! unit in the type and provide the conversions used in aviation and drone
+//! tooling. They are zero-cost wrappers around `f32`.
+//!
+//! # Example
+//!
+//! ```
+//! use ms4525do::units::{Celsius, Pascals};
+//! use ms4525do::airspeed;
+//!
+//! let speed = airspeed(Pascals::from_mbar(0.5), Celsius(20.0));
+//! println!("{:.1} kt", speed.knots());
+//! ```
+
+use crate::atmosphere::CELSIUS_TO_KELVIN;
+use crate::common::PSI_TO_PA;
+use crate::transfer::{INH2O_TO_PA, MBAR_TO_PA};
+
+#[cfg(feature = "defmt")]
+use defmt::Format;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Metres per second in one knot
+pub const KNOT_TO_MS: f32 = 1852.0 / 3600.0;
+
+/// Metres per second in one kilometre per hour
+pub const KMH_TO_MS: f32 = 1.0 / 3.6;
+
+/// Metres per second in one mile per hour
+pub const MPH_TO_MS: f32 = 0.447_04;
+
+/// Metres per second in one foot per second
+pub const FPS_TO_MS: f32 = 0.3048;
+
+/// A pressure in Pascals.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Pascals(pub f32);
+
+impl Pascals {
+    /// Creates a pressure from PSI.
+    pub fn from_psi(psi: f32) -> Self {
+        Self(psi * PSI_TO_PA)
+    }
+
+    /// Creates a pressure from millibar (hPa).
+    pub fn from_mbar(mbar: f32) -> Self {
+        Self(mbar * MBAR_TO_PA)
+    }
+
+    /// Creates a pressure from inches of water.
+    pub fn from_inh2o(inh2o: f32) -> Self {
+        Self(inh2o * INH2O_TO_PA)
+    }
+
+    /// Returns the pressure in PSI.
+    pub fn psi(&self) -> f32 {
+        self.0 / PSI_TO_PA
+    }
+
+    /// Returns the pressure in millibar (hPa).
+    pub fn mbar(&self) -> f32 {
+        self.0 / MBAR_TO_PA
+    }
+
+    /// Returns the pressure in inches of water.
+    pub fn inh2o(&self) -> f32 {
+        self.0 / INH2O_TO_PA
+    }
+}
+
+/// A temperature in degrees Celsius.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Celsius(pub f32);
+
+impl Celsius {
+    /// Creates a temperature from Kelvin.
+    pub fn from_kelvin(kelvin: f32) -> Self {
+        Self(kelvin - CELSIUS_TO_KELVIN)
+    }
+
+    /// Returns the temperature in Kelvin.
+    pub fn kelvin(&self) -> f32 {
+        self.0 + CELSIUS_TO_KELVIN
+    }
+}
+
+/// A speed in metres per second.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MetersPerSecond(pub f32);
+
+impl MetersPerSecond {
+    /// Creates a speed from knots.
+    pub fn from_knots(knots: f32) -> Self {
+        Self(knots * KNOT_TO_MS)
+    }
+
+    /// Creates a speed from kilometres per hour.
+    pub fn from_kmh(kmh: f32) -> Self {
+        Self(kmh * KMH_TO_MS)
+    }
+
+    /// Creates a speed from miles per hour.
+    pub fn from_mph(mph: f32) -> Self {
+        Self(mph * MPH_TO_MS)
+    }
+
+    /// Creates a speed from feet per second.
+    pub fn from_fps(fps: f32) -> Self {
+        Self(fps * FPS_TO_MS)
+    }
+
+    /// Returns the speed in knots.
+    pub fn knots(&self) -> f32 {
+        self.0 / KNOT_TO_MS
+    }
+
+    /// Returns the speed in kilometres per hour.
+    pub fn kmh(&self) -> f32 {
+        self.0 / KMH_TO_MS
+    }
+
+    /// Returns the speed in miles per hour.
+    pub fn mph(&self) -> f32 {
+        self.0 / MPH_TO_MS
+    }
+
+    /// Returns the speed in feet per second.
+    pub fn fps(&self) -> f32 {
+        self.0 / FPS_TO_MS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f32, b: f32) -> bool {
+        (a - b).abs() <= 1e-4 * b.abs().max(1.0)
+    }
+
+    #[test]
+    fn test_pressure_conversions() {
+        assert!(close(Pascals::from_psi(1.0).0, 6894.76));
+        assert!(close(Pascals(100.0).mbar(), 1.0));
+        assert!(close(Pascals::from_inh2o(1.0).0, 249.0889));
+        assert!(close(Pascals::from_mbar(12.5).psi(), 0.181_297));
+    }
+
+    #[test]
+    fn test_temperature_conversions() {
+        assert!(close(Celsius(0.0).kelvin(), 273.15));
+        assert!(close(Celsius::from_kelvin(300.0).0, 26.85));
+    }
+
+    #[test]
+    fn test_speed_conversions() {
+        let speed = MetersPerSecond(10.0);
+        assert!(close(speed.knots(), 19.438_45));
+        assert!(close(speed.kmh(), 36.0));
+        assert!(close(speed.mph(), 22.369_36));
+        assert!(close(speed.fps(), 32.808_4));
+        assert!(close(MetersPerSecond::from_knots(speed.knots()).0, 10.0));
+    }
+}